@@ -0,0 +1,160 @@
+//! Compares the arena `TrieChar` with the HashMap-per-node trie it replaced,
+//! on `dataset/words_th.txt`.
+//!
+//! Run with `cargo bench --bench trie_char`. Heap usage of both tries is
+//! printed before the timings.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use thongna::bytes_str::custom_string::{
+    CustomString, CustomStringBytesSlice, FixedCharsLengthByteSlice,
+};
+use thongna::tokenizer::dict_reader::{read_dict_entries, DictSource};
+use thongna::tokenizer::trie_char::TrieChar;
+
+const DICT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/dataset/words_th.txt");
+
+/// Keeps track of the bytes currently allocated on the heap.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// The previous layout: a HashMap of children per node plus a set of every word.
+#[derive(Default)]
+struct HashMapTrieNode {
+    children: FxHashMap<char, Self>,
+    end: bool,
+}
+
+#[derive(Default)]
+struct HashMapTrie {
+    words: FxHashSet<Vec<u8>>,
+    root: HashMapTrieNode,
+}
+
+impl HashMapTrie {
+    fn new(words: &[CustomString]) -> Self {
+        let mut trie = Self::default();
+        for word in words {
+            if trie.words.insert(word.raw_content().to_vec()) {
+                let mut node = &mut trie.root;
+                for &character in word.get_chars_content() {
+                    node = node.children.entry(character).or_default();
+                }
+                node.end = true;
+            }
+        }
+        trie
+    }
+
+    fn prefix_ref<'p>(&self, prefix: &'p CustomString) -> Vec<&'p CustomStringBytesSlice> {
+        let mut result = vec![];
+        let mut node = &self.root;
+        for (index, character) in prefix.get_chars_content().iter().enumerate() {
+            let Some(child) = node.children.get(character) else {
+                break;
+            };
+            if child.end {
+                result.push(prefix.raw_content().slice_by_char_indice(0, index + 1));
+            }
+            node = child;
+        }
+        result
+    }
+}
+
+fn heap_usage<T>(build: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let value = build();
+    (value, ALLOCATED.load(Ordering::Relaxed) - before)
+}
+
+fn load_words() -> Vec<CustomString> {
+    read_dict_entries(DictSource::FilePath(DICT_PATH.into()))
+        .unwrap()
+        .into_iter()
+        .map(|(word, _)| CustomString::new(&word).trim())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Every suffix of a text made of a sample of the words,
+/// as newmm looks up every position of its input.
+fn load_queries(words: &[CustomString]) -> Vec<CustomString> {
+    let text: String = words
+        .iter()
+        .step_by(50)
+        .map(|word| CustomString::convert_raw_bytes_to_std_string(word.raw_content()))
+        .collect();
+    let text = CustomString::new(&text);
+    (0..text.chars_len())
+        .map(|start| text.substring(start, text.chars_len()))
+        .collect()
+}
+
+fn bench_trie_char(c: &mut Criterion) {
+    let words = load_words();
+    let queries = load_queries(&words);
+
+    let (trie, trie_bytes) = heap_usage(|| TrieChar::new(&words));
+    let (hash_map_trie, hash_map_trie_bytes) = heap_usage(|| HashMapTrie::new(&words));
+    println!(
+        "{} words: TrieChar uses {} KiB, HashMap trie uses {} KiB",
+        trie.amount_of_words(),
+        trie_bytes / 1024,
+        hash_map_trie_bytes / 1024
+    );
+
+    let mut group = c.benchmark_group("build");
+    group.sample_size(10);
+    group.bench_function("TrieChar", |b| b.iter(|| TrieChar::new(black_box(&words))));
+    group.bench_function("HashMap trie", |b| {
+        b.iter(|| HashMapTrie::new(black_box(&words)))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("prefix_ref");
+    group.bench_function("TrieChar", |b| {
+        b.iter(|| {
+            queries
+                .iter()
+                .map(|query| TrieChar::prefix_ref(black_box(query), &trie).len())
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("HashMap trie", |b| {
+        b.iter(|| {
+            queries
+                .iter()
+                .map(|query| hash_map_trie.prefix_ref(black_box(query)).len())
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_trie_char);
+criterion_main!(benches);
@@ -0,0 +1,312 @@
+//! `thongna` command line: segment and normalize Thai text line by line,
+//! and compile dictionaries.
+//!
+//! Lines are read from the given files, or from stdin, and processed
+//! in parallel batches; output lines keep the order of input lines.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rayon::prelude::*;
+use serde_json::json;
+use thongna::tokenizer::compiled_dict::compile_dict;
+use thongna::tokenizer::dict_reader::DictSource;
+use thongna::tokenizer::token::Token;
+use thongna::tokenizer::token_patterns::{TokenPatternPreset, TokenPatterns};
+use thongna::{normalize, NewmmTokenizer, TccTokenizer, ThongnaResult};
+
+/// Lines read before processing them in parallel
+const BATCH_LINES: usize = 4096;
+
+#[derive(Parser, Debug)]
+#[command(name = "thongna", version, about = "Thai text processing")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Break every line into tokens
+    Tokenize(TokenizeArgs),
+    /// Normalize every line
+    Normalize(NormalizeArgs),
+    /// Manage dictionaries
+    #[command(subcommand)]
+    Dict(DictCommand),
+}
+
+#[derive(Subcommand, Debug)]
+enum DictCommand {
+    /// Compile a dictionary file for `tokenize --compiled-dict`
+    Compile {
+        /// Dictionary with one word per line, optionally `word<TAB>count`
+        input: PathBuf,
+        output: PathBuf,
+    },
+}
+
+#[derive(Args, Debug)]
+struct TokenizeArgs {
+    #[arg(long, value_enum, default_value_t = Engine::Newmm)]
+    engine: Engine,
+    /// Dictionary file, one word per line (newmm)
+    #[arg(long, value_name = "PATH", conflicts_with = "compiled_dict")]
+    dict: Option<PathBuf>,
+    /// Dictionary compiled by `thongna dict compile` (newmm)
+    #[arg(long, value_name = "PATH")]
+    compiled_dict: Option<PathBuf>,
+    /// Token pattern presets to match before the dictionary (newmm)
+    #[arg(long = "preset", value_name = "NAME")]
+    presets: Vec<TokenPatternPreset>,
+    /// Segment long lines in safe mode (newmm)
+    #[arg(long)]
+    safe: bool,
+    /// Separator between tokens in text output
+    #[arg(short, long, default_value = "|")]
+    delimiter: String,
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Files to read, stdin if none
+    files: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct NormalizeArgs {
+    /// Add spaces around numbers
+    #[arg(long)]
+    whitespace_number: bool,
+    /// Files to read, stdin if none
+    files: Vec<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Engine {
+    /// Dictionary-based maximal matching
+    Newmm,
+    /// Thai Character Clusters
+    Tcc,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    /// Tokens joined by the delimiter
+    Text,
+    /// A JSON array of tokens per line
+    Json,
+    /// A JSON array per line of tokens with their character offsets
+    Offsets,
+}
+
+enum Segmenter {
+    Newmm {
+        tokenizer: NewmmTokenizer,
+        safe: bool,
+    },
+    Tcc(TccTokenizer),
+}
+
+impl Segmenter {
+    fn from_args(args: &TokenizeArgs) -> Result<Self, String> {
+        match args.engine {
+            Engine::Tcc => Ok(Segmenter::Tcc(TccTokenizer::new())),
+            Engine::Newmm => {
+                let (path, tokenizer) = match (&args.dict, &args.compiled_dict) {
+                    (Some(path), _) => (path, NewmmTokenizer::new(&path.to_string_lossy())),
+                    (None, Some(path)) => (
+                        path,
+                        NewmmTokenizer::from_compiled_dict(&path.to_string_lossy()),
+                    ),
+                    (None, None) => {
+                        return Err(
+                            "newmm needs a dictionary, pass --dict or --compiled-dict".into()
+                        )
+                    }
+                };
+                let tokenizer =
+                    tokenizer.map_err(|error| format!("{}: {}", path.display(), error))?;
+                Ok(Segmenter::Newmm {
+                    tokenizer: tokenizer
+                        .with_token_patterns(TokenPatterns::from_presets(&args.presets)),
+                    safe: args.safe,
+                })
+            }
+        }
+    }
+
+    fn segment(&self, line: &str) -> ThongnaResult<Vec<Token>> {
+        match self {
+            Segmenter::Newmm { tokenizer, safe } => {
+                tokenizer.segment_with_offsets(line, *safe, false)
+            }
+            Segmenter::Tcc(tokenizer) => Ok(tokenizer.segment_with_offsets(line)),
+        }
+    }
+}
+
+fn format_tokens(tokens: &[Token], format: Format, delimiter: &str) -> String {
+    match format {
+        Format::Text => tokens
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect::<Vec<_>>()
+            .join(delimiter),
+        Format::Json => {
+            json!(tokens.iter().map(|token| &token.text).collect::<Vec<_>>()).to_string()
+        }
+        Format::Offsets => json!(tokens
+            .iter()
+            .map(|token| json!({ "text": token.text, "start": token.start, "end": token.end }))
+            .collect::<Vec<_>>())
+        .to_string(),
+    }
+}
+
+/// Apply `process` to every line of `reader`, writing one output line per input line.
+fn process_lines<R, W, F>(reader: R, writer: &mut W, process: F) -> Result<(), String>
+where
+    R: BufRead,
+    W: Write,
+    F: Fn(&str) -> ThongnaResult<String> + Sync,
+{
+    let mut lines = reader.lines();
+    loop {
+        let batch = lines
+            .by_ref()
+            .take(BATCH_LINES)
+            .collect::<io::Result<Vec<String>>>()
+            .map_err(|error| error.to_string())?;
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let outputs = batch
+            .par_iter()
+            .map(|line| process(line))
+            .collect::<ThongnaResult<Vec<String>>>()
+            .map_err(|error| error.to_string())?;
+        for output in outputs {
+            writeln!(writer, "{}", output).map_err(write_failed)?;
+        }
+    }
+}
+
+/// Run `process_lines` over every file in order, or over stdin if there is none.
+fn process_inputs<F>(files: &[PathBuf], process: F) -> Result<(), String>
+where
+    F: Fn(&str) -> ThongnaResult<String> + Sync,
+{
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    if files.is_empty() {
+        process_lines(io::stdin().lock(), &mut writer, &process)?;
+    }
+    for path in files {
+        let file = File::open(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        process_lines(BufReader::new(file), &mut writer, &process)?;
+    }
+    writer.flush().map_err(write_failed)
+}
+
+fn write_failed(error: io::Error) -> String {
+    // output piped into a command that stopped reading, like `head`
+    if error.kind() == io::ErrorKind::BrokenPipe {
+        std::process::exit(0);
+    }
+    error.to_string()
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    match cli.command {
+        Command::Tokenize(args) => {
+            let segmenter = Segmenter::from_args(&args)?;
+            process_inputs(&args.files, |line| {
+                Ok(format_tokens(
+                    &segmenter.segment(line)?,
+                    args.format,
+                    &args.delimiter,
+                ))
+            })
+        }
+        Command::Normalize(args) => process_inputs(&args.files, |line| {
+            Ok(normalize(line, args.whitespace_number))
+        }),
+        Command::Dict(DictCommand::Compile { input, output }) => {
+            compile_dict(DictSource::FilePath(input.clone()), Path::new(&output))
+                .map_err(|error| format!("{}: {}", input.display(), error))
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("thongna: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    const DICT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/dataset/words_th.txt");
+
+    fn tokenize(args: &[&str], input: &str) -> Result<String, String> {
+        let cli = Cli::try_parse_from([&["thongna", "tokenize"], args].concat())
+            .map_err(|error| error.to_string())?;
+        let Command::Tokenize(args) = cli.command else {
+            unreachable!()
+        };
+        let segmenter = Segmenter::from_args(&args)?;
+        let mut output = vec![];
+        process_lines(input.as_bytes(), &mut output, |line| {
+            Ok(format_tokens(
+                &segmenter.segment(line)?,
+                args.format,
+                &args.delimiter,
+            ))
+        })?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let input = "ไข่คน2021\n\nสวัสดีครับ\n".repeat(BATCH_LINES);
+        let output = tokenize(&["--dict", DICT_PATH], &input).unwrap();
+        assert_eq!(output, "ไข่|คน|2021\n\nสวัสดี|ครับ\n".repeat(BATCH_LINES));
+
+        assert_eq!(
+            tokenize(&["--dict", DICT_PATH, "-f", "json"], "ไข่คน").unwrap(),
+            "[\"ไข่\",\"คน\"]\n"
+        );
+        assert_eq!(
+            tokenize(&["--dict", DICT_PATH, "--format", "offsets"], "ไข่คน").unwrap(),
+            "[{\"end\":3,\"start\":0,\"text\":\"ไข่\"},{\"end\":5,\"start\":3,\"text\":\"คน\"}]\n"
+        );
+        assert_eq!(
+            tokenize(&["--engine", "tcc", "-d", " "], "ประเทศไทย").unwrap(),
+            "ป ระ เท ศ ไท ย\n"
+        );
+        assert_eq!(
+            tokenize(
+                &["--dict", DICT_PATH, "--preset", "url"],
+                "ดู https://thongna.dev"
+            )
+            .unwrap(),
+            "ดู| |https://thongna.dev\n"
+        );
+        assert!(tokenize(&[], "ไข่").is_err());
+        assert!(tokenize(&["--preset", "phone"], "ไข่").is_err());
+    }
+}
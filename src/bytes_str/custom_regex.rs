@@ -2,13 +2,16 @@
 // which translates normal, human readable thai regex
 // into 4-bytes zero-left-pad bytes regex pattern string
 
-use anyhow::{Error as AnyError, Result};
+use crate::error::ThongnaError;
 use regex_syntax::{
     hir::{Anchor, Class, Group, Literal as LiteralEnum, Repetition},
     hir::{ClassUnicodeRange, Hir, HirKind},
     is_meta_character, Parser,
 };
 use std::{error::Error, fmt::Display};
+
+type Result<T> = std::result::Result<T, UnsupportedCustomRegexParserError>;
+
 trait ToCustomStringRepr {
     fn to_custom_byte_repr(&self) -> Result<String>;
 }
@@ -17,10 +20,10 @@ trait ToCustomStringRepr {
 enum UnsupportedCustomRegexParserError {
     ByteLiteral,
     ByteClass,
-    DifferentRanges(char, char),
-    RepetitionRange,
     AnchorStartLine,
     AnchorEndLine,
+    WordBoundary,
+    Syntax(String),
 }
 enum IterableHirKind {
     Alternation(Vec<Hir>),
@@ -36,17 +39,16 @@ impl Display for UnsupportedCustomRegexParserError {
             UnsupportedCustomRegexParserError::ByteClass => {
                 write!(f, "Byte class is not supported")
             }
-            UnsupportedCustomRegexParserError::DifferentRanges(a, b) => {
-                write!(
-                    f,
-                    "Different byte length range is not supported {} {}",
-                    a, b
-                )
+            UnsupportedCustomRegexParserError::AnchorStartLine => {
+                write!(f, "Start of line anchor is not supported, use start of text")
+            }
+            UnsupportedCustomRegexParserError::AnchorEndLine => {
+                write!(f, "End of line anchor is not supported, use end of text")
             }
-            UnsupportedCustomRegexParserError::RepetitionRange => todo!(),
-            UnsupportedCustomRegexParserError::ByteLiteral => todo!(),
-            UnsupportedCustomRegexParserError::AnchorStartLine => todo!(),
-            UnsupportedCustomRegexParserError::AnchorEndLine => todo!(),
+            UnsupportedCustomRegexParserError::WordBoundary => {
+                write!(f, "Word boundary is not supported")
+            }
+            UnsupportedCustomRegexParserError::Syntax(reason) => write!(f, "{}", reason),
         }
     }
 }
@@ -60,11 +62,11 @@ impl ToCustomStringRepr for Hir {
 impl ToCustomStringRepr for HirKind {
     fn to_custom_byte_repr(&self) -> Result<String> {
         match self {
-            HirKind::Empty => todo!(),
+            HirKind::Empty => Ok(String::new()),
             HirKind::Literal(l) => l.to_custom_byte_repr(),
             HirKind::Class(c) => c.to_custom_byte_repr(),
             HirKind::Anchor(a) => a.to_custom_byte_repr(),
-            HirKind::WordBoundary(_) => todo!(),
+            HirKind::WordBoundary(_) => Err(UnsupportedCustomRegexParserError::WordBoundary),
             HirKind::Repetition(r) => r.to_custom_byte_repr(),
             HirKind::Group(g) => g.to_custom_byte_repr(),
             HirKind::Concat(c) => IterableHirKind::Concat(c.to_vec()).to_custom_byte_repr(),
@@ -77,8 +79,8 @@ impl ToCustomStringRepr for HirKind {
 impl ToCustomStringRepr for Anchor {
     fn to_custom_byte_repr(&self) -> Result<String> {
         match self {
-            Anchor::StartLine => todo!(),
-            Anchor::EndLine => todo!(),
+            Anchor::StartLine => Err(UnsupportedCustomRegexParserError::AnchorStartLine),
+            Anchor::EndLine => Err(UnsupportedCustomRegexParserError::AnchorEndLine),
             Anchor::StartText => Ok("^".to_string()),
             Anchor::EndText => Ok("$".to_string()),
         }
@@ -88,17 +90,15 @@ impl ToCustomStringRepr for LiteralEnum {
     fn to_custom_byte_repr(&self) -> Result<String> {
         match self {
             LiteralEnum::Unicode(a) => Ok(a.to_four_byte_string()),
-            LiteralEnum::Byte(b) => Err(AnyError::new(
-                UnsupportedCustomRegexParserError::ByteLiteral,
-            )),
+            LiteralEnum::Byte(_) => Err(UnsupportedCustomRegexParserError::ByteLiteral),
         }
     }
 }
 impl ToCustomStringRepr for Class {
     fn to_custom_byte_repr(&self) -> Result<String> {
         match self {
-            Class::Unicode(u) => Ok(u.ranges().to_four_byte_string()),
-            Class::Bytes(_) => Err(AnyError::from(UnsupportedCustomRegexParserError::ByteClass)),
+            Class::Unicode(u) => class_to_four_byte_string(u.ranges()),
+            Class::Bytes(_) => Err(UnsupportedCustomRegexParserError::ByteClass),
         }
     }
 }
@@ -118,11 +118,11 @@ impl ToCustomStringRepr for Repetition {
         };
 
         let repeated_expression = match &self.hir.kind() {
-            HirKind::Empty => todo!(),
+            HirKind::Empty => Ok(String::new()),
             HirKind::Literal(l) => l.to_custom_byte_repr(),
             HirKind::Class(c) => c.to_custom_byte_repr(),
             HirKind::Anchor(a) => a.to_custom_byte_repr(),
-            HirKind::WordBoundary(_) => todo!(),
+            HirKind::WordBoundary(_) => Err(UnsupportedCustomRegexParserError::WordBoundary),
             HirKind::Repetition(r) => r.to_custom_byte_repr(),
             HirKind::Group(g) => g.to_custom_byte_repr(),
             HirKind::Concat(c) => IterableHirKind::Concat(c.to_vec()).to_custom_byte_repr(),
@@ -144,7 +144,13 @@ impl ToCustomStringRepr for IterableHirKind {
                 let mut cus_str = String::new();
                 for member in a {
                     match member.kind() {
-                        HirKind::Empty => todo!(),
+                        HirKind::Empty => {
+                            if !cus_str.is_empty() {
+                                cus_str += "|()";
+                            } else {
+                                cus_str = "()".to_string();
+                            }
+                        }
                         HirKind::Literal(literal) => {
                             if !cus_str.is_empty() {
                                 cus_str = cus_str
@@ -172,7 +178,9 @@ impl ToCustomStringRepr for IterableHirKind {
                                 cus_str = format!("({})", &a.to_custom_byte_repr()?);
                             }
                         }
-                        HirKind::WordBoundary(_) => todo!(),
+                        HirKind::WordBoundary(_) => {
+                            return Err(UnsupportedCustomRegexParserError::WordBoundary)
+                        }
                         HirKind::Repetition(r) => {
                             if !cus_str.is_empty() {
                                 cus_str = cus_str
@@ -219,13 +227,15 @@ impl ToCustomStringRepr for IterableHirKind {
                 let mut cus_str = String::new();
                 for member in c {
                     match member.kind() {
-                        HirKind::Empty => todo!(),
+                        HirKind::Empty => {}
                         HirKind::Literal(literal) => {
                             cus_str = cus_str + &literal.to_custom_byte_repr()?;
                         }
                         HirKind::Class(c) => cus_str = cus_str + &c.to_custom_byte_repr()?,
                         HirKind::Anchor(a) => cus_str = cus_str + &a.to_custom_byte_repr()?,
-                        HirKind::WordBoundary(_) => todo!(),
+                        HirKind::WordBoundary(_) => {
+                            return Err(UnsupportedCustomRegexParserError::WordBoundary)
+                        }
                         HirKind::Repetition(r) => cus_str = cus_str + &r.to_custom_byte_repr()?,
                         HirKind::Group(g) => cus_str = cus_str + &g.to_custom_byte_repr()?,
                         HirKind::Concat(concat) => {
@@ -248,12 +258,12 @@ impl ToCustomStringRepr for IterableHirKind {
 impl ToCustomStringRepr for Group {
     fn to_custom_byte_repr(&self) -> Result<String> {
         let recur = match self.hir.kind() {
-            HirKind::Empty => todo!(),
+            HirKind::Empty => Ok(String::new()),
             HirKind::Literal(lit) => lit.to_custom_byte_repr(),
             HirKind::Class(c) => c.to_custom_byte_repr(),
             HirKind::Anchor(a) => a.to_custom_byte_repr(),
-            HirKind::WordBoundary(_) => todo!(),
-            HirKind::Repetition(_) => todo!(),
+            HirKind::WordBoundary(_) => Err(UnsupportedCustomRegexParserError::WordBoundary),
+            HirKind::Repetition(r) => r.to_custom_byte_repr(),
             HirKind::Group(g) => g.to_custom_byte_repr(),
             HirKind::Concat(c) => IterableHirKind::Concat(c.to_vec()).to_custom_byte_repr(),
             HirKind::Alternation(a) => {
@@ -264,17 +274,6 @@ impl ToCustomStringRepr for Group {
     }
 }
 
-fn get_char_range_byte_class(class_range: &ClassUnicodeRange) -> Option<UTFBytesLength> {
-    // currently allow only the same byte length
-    let start_class = char_class(class_range.start());
-    let end_class = char_class(class_range.end());
-    if start_class == end_class {
-        Some(start_class)
-    } else {
-        None
-    }
-}
-
 #[derive(PartialEq, Eq, Clone, Copy)]
 enum UTFBytesLength {
     One,
@@ -283,19 +282,32 @@ enum UTFBytesLength {
     Four,
 }
 fn char_class(character: char) -> UTFBytesLength {
-    let mut bytes_buffer: [u8; 4] = [0; 4];
-
-    character.encode_utf8(&mut bytes_buffer);
-    match bytes_buffer {
-        [_a, 0, 0, 0] => UTFBytesLength::One,
-        [_a, _b, 0, 0] => UTFBytesLength::Two,
-        [_a, _b, _c, 0] => UTFBytesLength::Three,
+    match character.len_utf8() {
+        1 => UTFBytesLength::One,
+        2 => UTFBytesLength::Two,
+        3 => UTFBytesLength::Three,
         _ => UTFBytesLength::Four,
     }
 }
 
-fn is_in_range<T: PartialEq + PartialOrd>(value: T, range: (T, T)) -> bool {
-    value >= range.0 && value <= range.1
+/// Last character of each UTF-8 byte length but the longest
+const UTF8_LENGTH_ENDS: [char; 3] = ['\u{7F}', '\u{7FF}', '\u{FFFF}'];
+
+/// Splits class ranges so that the characters of each range
+/// have the same UTF-8 byte length.
+fn split_by_byte_length(urange: &[ClassUnicodeRange]) -> Vec<(char, char)> {
+    let mut ranges = Vec::with_capacity(urange.len());
+    for range in urange {
+        let (mut start, end) = (range.start(), range.end());
+        for length_end in UTF8_LENGTH_ENDS {
+            if start <= length_end && length_end < end {
+                ranges.push((start, length_end));
+                start = char::from_u32(length_end as u32 + 1).unwrap();
+            }
+        }
+        ranges.push((start, end));
+    }
+    ranges
 }
 
 trait PadLeftZeroFourBytesRep {
@@ -306,55 +318,56 @@ fn escape_meta_character(c: char) -> String {
         format!(r"\{}", c)
     } else if c.is_whitespace() {
         format!("{:?}", c).replace('\'', "")
+    } else if c.is_control() {
+        format!(r"\x{{{:X}}}", c as u32)
     } else {
         c.to_string()
     }
 }
-impl PadLeftZeroFourBytesRep for &[ClassUnicodeRange] {
-    fn to_four_byte_string(&self) -> String {
-        let urange = self;
-        let char_classes = urange
+/// A class of characters of one byte length is padded like its characters,
+/// e.g. `\x00[ก-ฮ]`. A class mixing byte lengths becomes an alternation
+/// of such classes, one per byte length.
+fn class_to_four_byte_string(urange: &[ClassUnicodeRange]) -> Result<String> {
+    let ranges = split_by_byte_length(urange);
+    let mut classes: Vec<String> = vec![];
+    for length in [
+        UTFBytesLength::One,
+        UTFBytesLength::Two,
+        UTFBytesLength::Three,
+        UTFBytesLength::Four,
+    ] {
+        // we want to create all syntax of \x00\x00\x00[a-z]
+        let output_four_bytes_rep: Vec<String> = ranges
             .iter()
-            .map(get_char_range_byte_class)
-            .collect::<Vec<_>>();
-
-        if char_classes.iter().all(|elem| elem.is_some()) {
-            // must be the same class for every range pair!
-            let the_class = char_classes.first().unwrap().unwrap();
-
-            if char_classes.iter().all(|elem| elem.unwrap() == the_class) {
-                let pad_left_0 = match the_class {
-                    UTFBytesLength::One => r"\x00\x00\x00",
-                    UTFBytesLength::Two => r"\x00\x00",
-                    UTFBytesLength::Three => r"\x00",
-                    UTFBytesLength::Four => r"",
-                };
-                let mut output_four_bytes_rep: Vec<String> = vec![];
-                // we want to create all syntax of \x00\x00\x00[a-z]
-                for regex_range in urange.iter() {
-                    let (start, end) = (regex_range.start(), regex_range.end());
-                    if start == end {
-                        output_four_bytes_rep
-                            .push(escape_meta_character(end).to_string().replace("'", ""));
-                    } else {
-                        output_four_bytes_rep.push(
-                            format!(
-                                r"{}-{}",
-                                escape_meta_character(start),
-                                escape_meta_character(end)
-                            )
-                            .replace('\'', ""),
-                        );
-                    }
+            .filter(|(start, _)| char_class(*start) == length)
+            .map(|&(start, end)| {
+                if start == end {
+                    escape_meta_character(end)
+                } else {
+                    format!(
+                        r"{}-{}",
+                        escape_meta_character(start),
+                        escape_meta_character(end)
+                    )
                 }
-                format!(r"{}[{}]", pad_left_0, output_four_bytes_rep.join(""))
-            } else {
-                println!("{:?}", self);
-                todo!()
-            }
-        } else {
-            todo!()
+            })
+            .collect();
+        if output_four_bytes_rep.is_empty() {
+            continue;
         }
+        let pad_left_0 = match length {
+            UTFBytesLength::One => r"\x00\x00\x00",
+            UTFBytesLength::Two => r"\x00\x00",
+            UTFBytesLength::Three => r"\x00",
+            UTFBytesLength::Four => r"",
+        };
+        classes.push(format!(r"{}[{}]", pad_left_0, output_four_bytes_rep.join("")));
+    }
+    match classes.len() {
+        // an empty class matches nothing
+        0 => Ok(r"[^\x00-\x{10FFFF}]".to_string()),
+        1 => Ok(classes.remove(0)),
+        _ => Ok(format!("({})", classes.join("|"))),
     }
 }
 impl PadLeftZeroFourBytesRep for char {
@@ -384,7 +397,61 @@ impl PadLeftZeroFourBytesRep for char {
     }
 }
 
-pub fn regex_pattern_to_custom_pattern(regex_pattern: &str) -> Result<String> {
-    let hir = Parser::new().parse(regex_pattern)?;
-    hir.to_custom_byte_repr()
+pub fn regex_pattern_to_custom_pattern(regex_pattern: &str) -> std::result::Result<String, ThongnaError> {
+    Parser::new()
+        .parse(regex_pattern)
+        .map_err(|error| UnsupportedCustomRegexParserError::Syntax(error.to_string()))
+        .and_then(|hir| hir.to_custom_byte_repr())
+        .map_err(|error| ThongnaError::UnsupportedRegex {
+            pattern: regex_pattern.to_string(),
+            reason: error.to_string(),
+        })
+}
+
+#[test]
+fn test_unsupported_regex_errors() {
+    for pattern in [r"\bก", r"(?m)^ก", r"(?-u)\xff", r"(ก"] {
+        assert!(
+            matches!(
+                regex_pattern_to_custom_pattern(pattern),
+                Err(ThongnaError::UnsupportedRegex { .. })
+            ),
+            "{}",
+            pattern
+        );
+    }
+    let error = regex_pattern_to_custom_pattern(r"\bก").unwrap_err();
+    assert!(error.to_string().contains("Word boundary"));
+}
+
+#[test]
+fn test_mixed_byte_length_class() {
+    use crate::bytes_str::custom_string::CustomString;
+
+    assert_eq!(
+        regex_pattern_to_custom_pattern(r"[a-zก-ฮ]").unwrap(),
+        r"(\x00\x00\x00[a-z]|\x00[ก-ฮ])"
+    );
+    // ranges spanning byte lengths are split, control characters escaped
+    assert_eq!(
+        regex_pattern_to_custom_pattern(r"[\x00-\x{E01}]").unwrap(),
+        r"(\x00\x00\x00[\x{0}-\x{7F}]|\x00\x00[\x{80}-߿]|\x00[ࠀ-ก])"
+    );
+    let pattern = regex::bytes::Regex::new(&format!(
+        "^({})$",
+        regex_pattern_to_custom_pattern(r"[#@]\w+|.😀?").unwrap()
+    ))
+    .unwrap();
+    for text in ["#แฮชแท็ก", "@user_1", "é", "😀", "ก😀"] {
+        assert!(pattern.is_match(CustomString::new(text).raw_content()), "{}", text);
+    }
+    assert!(!pattern.is_match(CustomString::new("##").raw_content()));
+    assert!(!pattern.is_match(CustomString::new("กข").raw_content()));
+}
+
+#[test]
+fn test_empty_alternative() {
+    let pattern = regex::bytes::Regex::new(&regex_pattern_to_custom_pattern(r"^(ก|)ข").unwrap()).unwrap();
+    assert!(pattern.is_match(crate::bytes_str::custom_string::CustomString::new("ข").raw_content()));
+    assert!(pattern.is_match(crate::bytes_str::custom_string::CustomString::new("กข").raw_content()));
 }
@@ -97,7 +97,7 @@ impl FixedCharsLengthByteSlice for &CustomStringBytesSlice {
     }
 
     fn is_valid_custom_str_bytes(&self) -> bool {
-        if self.len() % 4 != 0 {
+        if self.len() % BYTES_PER_CHAR != 0 {
             return false;
         }
         for index in 0..self.chars_len() {
@@ -138,6 +138,19 @@ pub fn rfind_space_char_index(custom_text: &CustomStringBytesSlice) -> Option<us
     None
 }
 
+/// Decodes the custom four-byte representation of one character.
+pub fn decode_custom_char(custom_bytes: &CustomStringBytesSlice) -> Option<char> {
+    let leading_zeros = custom_bytes
+        .iter()
+        .take_while(|&&byte| byte == 0)
+        .count()
+        .min(BYTES_PER_CHAR - 1);
+    std::str::from_utf8(&custom_bytes[leading_zeros..])
+        .ok()?
+        .chars()
+        .next()
+}
+
 /// Check if a white space (including left-to-right and right-to-left marks)
 fn is_whitespace(custom_bytes: &CustomStringBytesSlice) -> bool {
     matches!(
@@ -203,7 +216,7 @@ fn to_four_bytes(input: &str) -> CustomStringBytesVec {
 fn trim_to_std_utf8(
     input: &CustomStringBytesSlice,
 ) -> Result<PreparedCustomBytes, Box<dyn error::Error>> {
-    if input.len() % 4 != 0 {
+    if input.len() % BYTES_PER_CHAR != 0 {
         Err(InvalidCustomStringByteError::new_invalid_length(input).into())
     } else {
         match input {
@@ -322,27 +335,25 @@ impl CustomString {
     }
 
     pub fn trim(&self) -> Self {
-        let mut new_content: &[u8] = &self.content;
+        let content = self.raw_content();
+        let mut start = 0;
+        let mut end = self.chars_len();
 
-        while !new_content.is_empty() && is_whitespace(&new_content[0..BYTES_PER_CHAR]) {
+        while start < end && is_whitespace(content.slice_by_char_indice(start, start + 1)) {
             // trim left
-            new_content = &new_content[BYTES_PER_CHAR..];
+            start += 1;
         }
 
-        while !new_content.is_empty()
-            && is_whitespace(&new_content[(new_content.len() - BYTES_PER_CHAR)..])
-        {
+        while end > start && is_whitespace(content.slice_by_char_indice(end - 1, end)) {
             // trim right
-            new_content = &new_content[..(new_content.len() - BYTES_PER_CHAR)];
+            end -= 1;
         }
 
-        let length = new_content.len() / BYTES_PER_CHAR;
-
         Self {
-            content: Arc::new(Vec::from(new_content)),
-            chars_content: self.chars_content.clone(),
+            content: Arc::new(Vec::from(content.slice_by_char_indice(start, end))),
+            chars_content: Arc::new(self.get_chars_content()[start..end].to_vec()),
             start: 0,
-            end: length,
+            end: end - start,
         }
     }
 
@@ -406,7 +417,7 @@ impl CustomString {
                     output_content.push(first_byte);
                     output_content.push(second_byte);
                 }
-                (Some(first_byte), Some(second_byte), Some(third_byte), None) => {
+                (None, Some(first_byte), Some(second_byte), Some(third_byte)) => {
                     output_content.push(first_byte);
                     output_content.push(second_byte);
                     output_content.push(third_byte);
@@ -446,6 +457,7 @@ impl CustomString {
 }
 
 #[test]
+#[allow(clippy::const_is_empty)]
 fn check_slice() {
     let ex: &[u8] = &[255, 255, 255, 255, 0, 255, 111, 0];
     assert_eq!(ex.slice_by_char_indice(0, 1), &[255, 255, 255, 255]);
@@ -470,6 +482,16 @@ fn test_bytes() {
     assert_eq!(custom_string.full_string_bytes_len() % 4, 0);
 }
 
+#[test]
+fn test_convert_raw_bytes_to_utf8_bytes() {
+    let text = "aß ก臺😀";
+    let custom_string = CustomString::new(text);
+    assert_eq!(
+        CustomString::convert_raw_bytes_to_utf8_bytes(custom_string.raw_content()),
+        text.as_bytes()
+    );
+}
+
 #[test]
 fn test_trim() {
     assert!(CustomString::new(" ").trim().is_empty());
@@ -478,4 +500,18 @@ fn test_trim() {
     assert!(CustomString::new("  \t\n ").trim().is_empty());
     assert_eq!(CustomString::new(" abc ").trim().chars_len(), 3);
     assert_eq!(CustomString::new(" aก  ").trim().full_string_bytes_len(), 8); // 2 chars * 4 bytes
+    assert_eq!(CustomString::new(" aก  ").trim().get_chars_content(), &['a', 'ก']);
+    assert_eq!(CustomString::new("x ab ").substring(1, 5).trim().get_chars_content(), &['a', 'b']);
+}
+
+#[test]
+fn test_decode_custom_char() {
+    let custom_string = CustomString::new("a\0ก臺😀");
+    let decoded: Vec<char> = (0..custom_string.chars_len())
+        .map(|index| {
+            decode_custom_char(custom_string.raw_content().slice_by_char_indice(index, index + 1))
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(decoded, vec!['a', '\0', 'ก', '臺', '😀']);
 }
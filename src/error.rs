@@ -0,0 +1,75 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::io;
+use std::time::Duration;
+
+/// Errors returned by Thongna.
+#[derive(Debug)]
+pub enum ThongnaError {
+    /// Reading or writing a file failed
+    Io(io::Error),
+    /// A line of a dictionary file cannot be read
+    InvalidDictionaryLine { line_number: usize, reason: String },
+    /// A compiled dictionary file is corrupt or has an unsupported format version
+    InvalidCompiledDictionary(String),
+    /// A line of a subword vocabulary file cannot be read
+    InvalidVocabularyLine { line_number: usize, reason: String },
+    /// A regular expression cannot be converted to the custom string representation
+    UnsupportedRegex { pattern: String, reason: String },
+    /// No segmentation of a text could be found
+    Segmentation(String),
+    /// Segmenting a text took longer than the time limit
+    Timeout(Duration),
+}
+
+pub type ThongnaResult<T> = Result<T, ThongnaError>;
+
+impl Display for ThongnaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThongnaError::Io(error) => write!(f, "{}", error),
+            ThongnaError::InvalidDictionaryLine {
+                line_number,
+                reason,
+            } => write!(f, "Invalid dictionary line {}: {}", line_number, reason),
+            ThongnaError::InvalidCompiledDictionary(reason) => {
+                write!(f, "Invalid compiled dictionary: {}", reason)
+            }
+            ThongnaError::InvalidVocabularyLine {
+                line_number,
+                reason,
+            } => write!(f, "Invalid vocabulary line {}: {}", line_number, reason),
+            ThongnaError::UnsupportedRegex { pattern, reason } => {
+                write!(f, "Unsupported regex {:?}: {}", pattern, reason)
+            }
+            ThongnaError::Segmentation(reason) => write!(f, "Segmentation failed: {}", reason),
+            ThongnaError::Timeout(timeout) => {
+                write!(f, "Segmentation took longer than {:?}", timeout)
+            }
+        }
+    }
+}
+
+impl Error for ThongnaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ThongnaError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ThongnaError {
+    fn from(error: io::Error) -> Self {
+        ThongnaError::Io(error)
+    }
+}
+
+impl From<fst::Error> for ThongnaError {
+    fn from(error: fst::Error) -> Self {
+        match error {
+            fst::Error::Io(error) => ThongnaError::Io(error),
+            error => ThongnaError::InvalidCompiledDictionary(error.to_string()),
+        }
+    }
+}
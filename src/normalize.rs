@@ -0,0 +1,588 @@
+use lazy_static::lazy_static;
+use rayon::prelude::*;
+use regex::{Captures, Regex};
+use std::fmt::Display;
+use std::str::FromStr;
+use unicode_normalization::char::{canonical_combining_class, compose};
+use unicode_normalization::UnicodeNormalization;
+
+/// Marks that are never written twice in a row
+static DUPLICATE_MARKS: [&str; 20] = [
+    "ะ", "ั", "็", "า", "ิ", "ี", "ึ", "่", "ํ", "ุ", "ู", "ใ", "ไ", "โ", "ื", "้", "๋", "๊", "์", "ำ",
+];
+
+/// (pattern, replacement) pairs putting marks into their canonical order,
+/// `t` standing for any tone mark
+static REORDER_RULES: [(&str, &str); 8] = [
+    ("เเ", "แ"),
+    ("ํา", "ำ"),
+    ("ํ(t)า", "${1}ำ"),
+    ("ํา(t)", "${1}ำ"),
+    ("([่-๋])([ัิ-ื])", "${2}${1}"),
+    ("([่-๋])([ูุ])", "${2}${1}"),
+    ("ำ([่-๋])", "${1}ำ"),
+    ("(์)([ัิ-ู])", "${2}${1}"),
+];
+
+/// Zero-width spaces, which mark word breaks in some Thai text
+static ZERO_WIDTH_CHARS: [char; 1] = ['\u{200b}'];
+
+/// Other characters of zero width that carry no meaning in Thai text:
+/// non-joiners, word joiners and byte order marks.
+/// The zero-width joiner is kept, as emoji sequences need it.
+static INVISIBLE_CHARS: [char; 3] = ['\u{200c}', '\u{2060}', '\u{feff}'];
+
+lazy_static! {
+    static ref WHITESPACE_NUMBER_RE: Regex = Regex::new(r"([0-9]+)").unwrap();
+    static ref MULTIPLE_SPACES_RE: Regex = Regex::new(r" {2,}").unwrap();
+    static ref MULTIPLE_TABS_RE: Regex = Regex::new(r"\t{2,}").unwrap();
+    static ref MULTIPLE_NEWLINES_RE: Regex = Regex::new(r"\n{2,}").unwrap();
+    static ref REORDER_RES: Vec<(Regex, &'static str)> = REORDER_RULES
+        .iter()
+        .map(|(pattern, replacement)| {
+            (
+                Regex::new(&pattern.replace('t', "[่้๊๋]")).unwrap(),
+                *replacement,
+            )
+        })
+        .collect();
+    static ref DUPLICATE_MARKS_RE: Regex = Regex::new(
+        &DUPLICATE_MARKS
+            .iter()
+            .map(|mark| format!("{}{{2,}}", mark))
+            .collect::<Vec<_>>()
+            .join("|")
+    )
+    .unwrap();
+    static ref DEFAULT_NORMALIZER: Normalizer = Normalizer::default();
+    static ref WHITESPACE_NUMBER_NORMALIZER: Normalizer =
+        Normalizer::default().with_step(NormalizeStep::SpaceNumbers);
+}
+
+/// A step of [`Normalizer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NormalizeStep {
+    /// Unicode canonical composition (NFC)
+    Nfc,
+    /// Spaces around numbers: `ราคา100บาท` to `ราคา 100 บาท`
+    SpaceNumbers,
+    /// Zero-width spaces
+    RemoveZeroWidth,
+    /// Zero-width non-joiners, word joiners and byte order marks,
+    /// which the default steps keep
+    RemoveInvisibleChars,
+    /// Runs of spaces, tabs or line breaks to a single one
+    CollapseWhitespace,
+    /// Tone marks after the vowels above or below them, `ำ` after tone marks,
+    /// `เเ` to `แ` and `ํา` to `ำ`
+    ReorderMarks,
+    /// Repeated vowels, tone marks and signs to a single one: `นานาาา` to `นานา`
+    RemoveDuplicateMarks,
+}
+
+impl NormalizeStep {
+    /// Every step, in the order they are applied
+    pub const ALL: [NormalizeStep; 7] = [
+        NormalizeStep::Nfc,
+        NormalizeStep::SpaceNumbers,
+        NormalizeStep::RemoveZeroWidth,
+        NormalizeStep::RemoveInvisibleChars,
+        NormalizeStep::CollapseWhitespace,
+        NormalizeStep::ReorderMarks,
+        NormalizeStep::RemoveDuplicateMarks,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NormalizeStep::Nfc => "nfc",
+            NormalizeStep::SpaceNumbers => "space_numbers",
+            NormalizeStep::RemoveZeroWidth => "remove_zero_width",
+            NormalizeStep::RemoveInvisibleChars => "remove_invisible_chars",
+            NormalizeStep::CollapseWhitespace => "collapse_whitespace",
+            NormalizeStep::ReorderMarks => "reorder_marks",
+            NormalizeStep::RemoveDuplicateMarks => "remove_duplicate_marks",
+        }
+    }
+
+    fn apply(&self, text: String) -> String {
+        match self {
+            NormalizeStep::Nfc => text.nfc().collect(),
+            NormalizeStep::SpaceNumbers => {
+                WHITESPACE_NUMBER_RE.replace_all(&text, " $1 ").into_owned()
+            }
+            NormalizeStep::RemoveZeroWidth => text.replace(&ZERO_WIDTH_CHARS[..], ""),
+            NormalizeStep::RemoveInvisibleChars => text.replace(&INVISIBLE_CHARS[..], ""),
+            NormalizeStep::CollapseWhitespace => {
+                let text = MULTIPLE_SPACES_RE.replace_all(&text, " ");
+                let text = MULTIPLE_TABS_RE.replace_all(&text, "\t");
+                MULTIPLE_NEWLINES_RE.replace_all(&text, "\n").into_owned()
+            }
+            NormalizeStep::ReorderMarks => {
+                REORDER_RES.iter().fold(text, |text, (re, replacement)| {
+                    re.replace_all(&text, *replacement).into_owned()
+                })
+            }
+            NormalizeStep::RemoveDuplicateMarks => DUPLICATE_MARKS_RE
+                .replace_all(&text, |caps: &Captures| {
+                    caps[0].chars().next().unwrap().to_string()
+                })
+                .into_owned(),
+        }
+    }
+
+    fn apply_aligned(&self, text: NormalizedText) -> NormalizedText {
+        match self {
+            NormalizeStep::Nfc => text.nfc(),
+            NormalizeStep::SpaceNumbers => text.space_around(&WHITESPACE_NUMBER_RE),
+            NormalizeStep::RemoveZeroWidth => text.remove_chars(&ZERO_WIDTH_CHARS),
+            NormalizeStep::RemoveInvisibleChars => text.remove_chars(&INVISIBLE_CHARS),
+            NormalizeStep::CollapseWhitespace => text
+                .replace_all(&MULTIPLE_SPACES_RE, |_| " ".to_string())
+                .replace_all(&MULTIPLE_TABS_RE, |_| "\t".to_string())
+                .replace_all(&MULTIPLE_NEWLINES_RE, |_| "\n".to_string()),
+            NormalizeStep::ReorderMarks => {
+                REORDER_RES.iter().fold(text, |text, (re, replacement)| {
+                    text.replace_all(re, |caps| {
+                        let mut expanded = String::new();
+                        caps.expand(replacement, &mut expanded);
+                        expanded
+                    })
+                })
+            }
+            NormalizeStep::RemoveDuplicateMarks => text.replace_all(&DUPLICATE_MARKS_RE, |caps| {
+                caps[0].chars().next().unwrap().to_string()
+            }),
+        }
+    }
+}
+
+/// Text after normalization, with the characters of the original text
+/// each of its characters came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NormalizedText {
+    pub text: String,
+    /// For each character of `text`, the range of characters of the original
+    /// text it came from.
+    ///
+    /// Characters that were reordered keep their own range. Characters
+    /// replacing several others, like `แ` for `เเ` or a space for a run of
+    /// spaces, have the range of all of them. Characters added by
+    /// normalization, like the spaces around numbers, have an empty range.
+    pub alignment: Vec<(usize, usize)>,
+}
+
+impl NormalizedText {
+    fn unchanged(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            alignment: (0..text.chars().count())
+                .map(|index| (index, index + 1))
+                .collect(),
+        }
+    }
+
+    /// Range of characters of the original text that characters
+    /// `start..end` of the normalized text came from, empty if they were
+    /// all added by normalization.
+    pub fn original_span(&self, start: usize, end: usize) -> (usize, usize) {
+        let ranges = &self.alignment[start..end];
+        let position = match self.alignment[..start].last() {
+            Some(&(_, previous_end)) => previous_end,
+            None => 0,
+        };
+        ranges
+            .iter()
+            .filter(|(range_start, range_end)| range_start < range_end)
+            .fold(
+                None,
+                |span: Option<(usize, usize)>, &(range_start, range_end)| {
+                    Some(match span {
+                        Some((span_start, span_end)) => {
+                            (span_start.min(range_start), span_end.max(range_end))
+                        }
+                        None => (range_start, range_end),
+                    })
+                },
+            )
+            .unwrap_or_else(|| {
+                let position = ranges
+                    .first()
+                    .map_or(position, |&(range_start, _)| range_start);
+                (position, position)
+            })
+    }
+
+    /// Replace every match of `re`, see [`replacement_alignment`]
+    fn replace_all(self, re: &Regex, replace: impl Fn(&Captures) -> String) -> Self {
+        let mut text = String::with_capacity(self.text.len());
+        let mut alignment = Vec::with_capacity(self.alignment.len());
+        let (mut last_byte, mut last_char) = (0, 0);
+        for caps in re.captures_iter(&self.text) {
+            let found = caps.get(0).unwrap();
+            let start_char = last_char + self.text[last_byte..found.start()].chars().count();
+            let end_char = start_char + found.as_str().chars().count();
+            text.push_str(&self.text[last_byte..found.start()]);
+            alignment.extend_from_slice(&self.alignment[last_char..start_char]);
+
+            let replacement = replace(&caps);
+            alignment.extend(replacement_alignment(
+                found.as_str(),
+                &self.alignment[start_char..end_char],
+                &replacement,
+            ));
+            text.push_str(&replacement);
+            (last_byte, last_char) = (found.end(), end_char);
+        }
+        text.push_str(&self.text[last_byte..]);
+        alignment.extend_from_slice(&self.alignment[last_char..]);
+        Self { text, alignment }
+    }
+
+    /// Put a space, coming from nowhere, before and after every match of `re`
+    fn space_around(self, re: &Regex) -> Self {
+        let mut text = String::with_capacity(self.text.len());
+        let mut alignment = Vec::with_capacity(self.alignment.len());
+        let (mut last_byte, mut last_char) = (0, 0);
+        for found in re.find_iter(&self.text) {
+            let start_char = last_char + self.text[last_byte..found.start()].chars().count();
+            let end_char = start_char + found.as_str().chars().count();
+            text.push_str(&self.text[last_byte..found.start()]);
+            alignment.extend_from_slice(&self.alignment[last_char..start_char]);
+
+            let (start, _) = self.alignment[start_char];
+            let (_, end) = self.alignment[end_char - 1];
+            text.push(' ');
+            alignment.push((start, start));
+            text.push_str(found.as_str());
+            alignment.extend_from_slice(&self.alignment[start_char..end_char]);
+            text.push(' ');
+            alignment.push((end, end));
+            (last_byte, last_char) = (found.end(), end_char);
+        }
+        text.push_str(&self.text[last_byte..]);
+        alignment.extend_from_slice(&self.alignment[last_char..]);
+        Self { text, alignment }
+    }
+
+    fn remove_chars(self, removed: &[char]) -> Self {
+        let (text, alignment) = self
+            .text
+            .chars()
+            .zip(self.alignment)
+            .filter(|(c, _)| !removed.contains(c))
+            .unzip();
+        Self { text, alignment }
+    }
+
+    /// NFC of every base character with the marks that follow it.
+    /// Falls back to aligning the whole text at once in the rare case
+    /// this differs from the NFC of the whole text.
+    fn nfc(self) -> Self {
+        let composed: String = self.text.nfc().collect();
+        if composed == self.text {
+            return self;
+        }
+
+        let chars: Vec<(usize, char)> = self.text.char_indices().collect();
+        let mut text = String::with_capacity(composed.len());
+        let mut alignment = Vec::with_capacity(self.alignment.len());
+        let mut chunk_start = 0;
+        for end in 1..=chars.len() {
+            let chunk_ends = end == chars.len() || {
+                let (_, c) = chars[end];
+                canonical_combining_class(c) == 0 && compose(chars[end - 1].1, c).is_none()
+            };
+            if !chunk_ends {
+                continue;
+            }
+            let byte_end = chars.get(end).map_or(self.text.len(), |&(byte, _)| byte);
+            let chunk = &self.text[chars[chunk_start].0..byte_end];
+            let chunk_composed: String = chunk.nfc().collect();
+            alignment.extend(replacement_alignment(
+                chunk,
+                &self.alignment[chunk_start..end],
+                &chunk_composed,
+            ));
+            text.push_str(&chunk_composed);
+            chunk_start = end;
+        }
+
+        if text != composed {
+            let whole = replacement_alignment(&self.text, &self.alignment, &composed);
+            return Self {
+                text: composed,
+                alignment: whole,
+            };
+        }
+        Self { text, alignment }
+    }
+}
+
+/// Alignment of `replacement`, replacing `source` whose alignment is `ranges`:
+/// one to one if `replacement` only reorders `source`, otherwise every
+/// character of `replacement` comes from the whole of `source`.
+fn replacement_alignment(
+    source: &str,
+    ranges: &[(usize, usize)],
+    replacement: &str,
+) -> Vec<(usize, usize)> {
+    let source_chars: Vec<char> = source.chars().collect();
+    let mut used = vec![false; source_chars.len()];
+    let reordered: Option<Vec<(usize, usize)>> = replacement
+        .chars()
+        .map(|c| {
+            let index =
+                (0..source_chars.len()).find(|&index| !used[index] && source_chars[index] == c)?;
+            used[index] = true;
+            Some(ranges[index])
+        })
+        .collect();
+    match reordered {
+        Some(alignment) if used.iter().all(|&is_used| is_used) => alignment,
+        _ => {
+            let start = ranges.iter().map(|&(start, _)| start).min().unwrap_or(0);
+            let end = ranges.iter().map(|&(_, end)| end).max().unwrap_or(0);
+            vec![(start, end); replacement.chars().count()]
+        }
+    }
+}
+
+impl Display for NormalizeStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for NormalizeStep {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        NormalizeStep::ALL
+            .into_iter()
+            .find(|step| step.name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown normalization step {:?}, expected one of {}",
+                    name,
+                    NormalizeStep::ALL.map(|step| step.name()).join(", ")
+                )
+            })
+    }
+}
+
+/// Normalizes Thai text with a chosen set of steps.
+///
+/// Steps run in the order of [`NormalizeStep::ALL`], whatever the order
+/// they were chosen in. The default steps are those of
+/// `normalize(text, false)`: every step but [`NormalizeStep::Nfc`] and
+/// [`NormalizeStep::SpaceNumbers`].
+///
+/// ```
+/// use thongna::{NormalizeStep, Normalizer};
+///
+/// let normalizer = Normalizer::default().with_step(NormalizeStep::SpaceNumbers);
+/// assert_eq!(normalizer.normalize("เเปลก  100"), "แปลก 100 ");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Normalizer {
+    /// In the order of [`NormalizeStep::ALL`], without duplicates
+    steps: Vec<NormalizeStep>,
+}
+
+impl Normalizer {
+    /// Use exactly `steps`
+    pub fn new(steps: &[NormalizeStep]) -> Self {
+        Self {
+            steps: NormalizeStep::ALL
+                .into_iter()
+                .filter(|step| steps.contains(step))
+                .collect(),
+        }
+    }
+
+    /// Add a step
+    pub fn with_step(self, step: NormalizeStep) -> Self {
+        let mut steps = self.steps;
+        steps.push(step);
+        Self::new(&steps)
+    }
+
+    /// Remove a step
+    pub fn without_step(mut self, step: NormalizeStep) -> Self {
+        self.steps.retain(|&kept| kept != step);
+        self
+    }
+
+    /// The steps, in the order they are applied
+    pub fn steps(&self) -> &[NormalizeStep] {
+        &self.steps
+    }
+
+    pub fn normalize(&self, text: &str) -> String {
+        self.steps
+            .iter()
+            .fold(text.to_string(), |text, step| step.apply(text))
+    }
+
+    /// Normalize text, keeping for every normalized character
+    /// the characters of `text` it came from.
+    ///
+    /// The normalized text is the same as that of [`Normalizer::normalize`].
+    pub fn normalize_with_alignment(&self, text: &str) -> NormalizedText {
+        self.steps
+            .iter()
+            .fold(NormalizedText::unchanged(text), |text, step| {
+                step.apply_aligned(text)
+            })
+    }
+
+    /// Normalize every text of `texts`, in parallel.
+    pub fn normalize_batch<S: AsRef<str> + Sync>(&self, texts: &[S]) -> Vec<String> {
+        texts
+            .par_iter()
+            .map(|text| self.normalize(text.as_ref()))
+            .collect()
+    }
+}
+
+impl Default for Normalizer {
+    fn default() -> Self {
+        Self::new(&[
+            NormalizeStep::RemoveZeroWidth,
+            NormalizeStep::CollapseWhitespace,
+            NormalizeStep::ReorderMarks,
+            NormalizeStep::RemoveDuplicateMarks,
+        ])
+    }
+}
+
+/// Normalize Thai text.
+///
+/// Removes zero-width spaces, collapses repeated whitespace,
+/// reorders tone marks and vowels into their canonical order and
+/// removes duplicated marks. If `whitespace_number` is true,
+/// spaces are added around numbers.
+pub fn normalize(text: &str, whitespace_number: bool) -> String {
+    if whitespace_number {
+        WHITESPACE_NUMBER_NORMALIZER.normalize(text)
+    } else {
+        DEFAULT_NORMALIZER.normalize(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_whitespace() {
+        assert_eq!(normalize("ก\u{200b}ข  ค\n\n\nง", false), "กข ค\nง");
+        // only zero-width spaces are removed by default
+        let invisible = "ก\u{200c}ข\u{2060}ค\u{feff}";
+        assert_eq!(normalize(invisible, false), invisible);
+        assert_eq!(
+            Normalizer::default()
+                .with_step(NormalizeStep::RemoveInvisibleChars)
+                .normalize(invisible),
+            "กขค"
+        );
+        assert_eq!(normalize("ราคา100บาท", true), "ราคา 100 บาท");
+    }
+
+    #[test]
+    fn test_normalize_marks() {
+        assert_eq!(normalize("เเปลก", false), "แปลก");
+        assert_eq!(normalize("นานาาา", false), "นานา");
+    }
+
+    #[test]
+    fn test_reorder_marks() {
+        // tone mark typed before the vowel above or below it
+        assert_eq!(normalize("ก\u{e48}\u{e35}", false), "กี่");
+        assert_eq!(normalize("ก\u{e49}\u{e38}ง", false), "กุ้ง");
+        // sara am typed before the tone mark, or as nikhahit and sara aa
+        assert_eq!(normalize("น\u{e33}\u{e49}", false), "น้ำ");
+        assert_eq!(normalize("น\u{e4d}\u{e49}\u{e32}", false), "น้ำ");
+        assert_eq!(normalize("น\u{e4d}\u{e32}\u{e49}", false), "น้ำ");
+    }
+
+    #[test]
+    fn test_normalizer_steps() {
+        let text = "ก\u{e48}\u{e35}  ๆ\u{200b}100";
+        let everything = Normalizer::new(&NormalizeStep::ALL);
+        assert_eq!(everything.normalize(text), "กี่ ๆ 100 ");
+        assert_eq!(
+            Normalizer::new(&[NormalizeStep::RemoveZeroWidth]).normalize(text),
+            "ก\u{e48}\u{e35}  ๆ100"
+        );
+        assert_eq!(Normalizer::new(&[]).normalize(text), text);
+        assert_eq!(
+            Normalizer::new(&[NormalizeStep::RemoveDuplicateMarks, NormalizeStep::Nfc]).steps(),
+            &[NormalizeStep::Nfc, NormalizeStep::RemoveDuplicateMarks]
+        );
+        assert_eq!(
+            Normalizer::default()
+                .without_step(NormalizeStep::ReorderMarks)
+                .normalize("เเปลก"),
+            "เเปลก"
+        );
+        // NFC puts the vowel below before the tone mark
+        assert_eq!(
+            Normalizer::new(&[NormalizeStep::Nfc]).normalize("ก\u{e49}\u{e38}"),
+            "ก\u{e38}\u{e49}"
+        );
+        for step in NormalizeStep::ALL {
+            assert_eq!(step.name().parse::<NormalizeStep>(), Ok(step));
+        }
+        assert!("lowercase".parse::<NormalizeStep>().is_err());
+        assert_eq!(
+            Normalizer::default().normalize_batch(&["เเปลก", "นานาาา"]),
+            vec!["แปลก", "นานา"]
+        );
+    }
+
+    #[test]
+    fn test_alignment_matches_normalize() {
+        let texts = [
+            "เเปลก  ราคา100\u{200b}บาท\n\n\tนํ้า นานาาา",
+            "ก\u{e48}\u{e35}\u{e35}  e\u{301}\u{feff}น\u{e33}\u{e49}12,000",
+            "\u{1100}\u{1161}\u{11a8}ก\u{e49}\u{e38}",
+            "",
+        ];
+        let normalizers = NormalizeStep::ALL
+            .iter()
+            .map(|&step| Normalizer::new(&[step]))
+            .chain([Normalizer::default(), Normalizer::new(&NormalizeStep::ALL)]);
+        for normalizer in normalizers {
+            for text in texts {
+                let normalized = normalizer.normalize_with_alignment(text);
+                assert_eq!(normalized.text, normalizer.normalize(text));
+                assert_eq!(normalized.alignment.len(), normalized.text.chars().count());
+            }
+        }
+    }
+
+    #[test]
+    fn test_alignment() {
+        let normalizer = Normalizer::new(&NormalizeStep::ALL);
+        let text = "เเปลก  ราคา100\u{200b}บาท";
+        let normalized = normalizer.normalize_with_alignment(text);
+        assert_eq!(normalized.text, "แปลก ราคา 100 บาท");
+        // แ comes from เเ, the space from both spaces
+        assert_eq!(
+            &normalized.alignment[..5],
+            &[(0, 2), (2, 3), (3, 4), (4, 5), (5, 7)]
+        );
+        assert_eq!(normalized.original_span(0, 4), (0, 5));
+        assert_eq!(normalized.original_span(10, 13), (11, 14));
+        // added spaces around the number
+        assert_eq!(normalized.alignment[9], (11, 11));
+        assert_eq!(normalized.original_span(13, 14), (14, 14));
+        assert_eq!(normalized.original_span(14, 17), (15, 18));
+
+        // reordered marks keep their own position
+        let normalized = normalizer.normalize_with_alignment("ก\u{e48}\u{e35}e\u{301}");
+        assert_eq!(normalized.text, "กี่é");
+        assert_eq!(normalized.alignment, vec![(0, 1), (2, 3), (1, 2), (3, 5)]);
+    }
+}
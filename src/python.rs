@@ -0,0 +1,1698 @@
+// pyo3 0.22 `#[pyfunction]` expansion trips this lint on every `PyResult` return.
+#![allow(clippy::useless_conversion)]
+// `create_exception!` checks pyo3's own `gil-refs` feature.
+#![allow(unexpected_cfgs)]
+
+use pyo3::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::Duration;
+use crate::normalize::{NormalizeStep, Normalizer};
+use crate::romanize::Romanizer;
+use crate::soundex::SoundexAlgorithm;
+use crate::spell::{SpellChecker, DEFAULT_MAX_DISTANCE};
+use crate::transliterate::Transliterator;
+use crate::tokenizer::bpe::{BpeTokenizer, PreTokenizer};
+use crate::tokenizer::compiled_dict;
+use crate::tokenizer::completion::Completion;
+use crate::tokenizer::dict_reader::{create_dict_trie, read_dict_entries, DictSource};
+use crate::tokenizer::newmm::{NewmmConfig, NewmmTokenizer, PathSelection};
+use crate::tokenizer::sentence::SentenceTokenizer;
+use crate::tokenizer::stream::StreamSegmenter;
+use crate::tokenizer::token::Token;
+use crate::tokenizer::token_patterns::{TokenPatternPreset, TokenPatterns, DEFAULT_TOKEN_PATTERNS};
+use crate::tokenizer::syllable::SyllableTokenizer;
+use crate::tokenizer::tcc::TccTokenizer;
+use crate::tokenizer::tokenizer_trait::Tokenizer;
+
+use pyo3::{create_exception, exceptions, wrap_pyfunction};
+use once_cell::sync::Lazy;
+
+/// (token, start, end, byte_start, byte_end)
+type TokenTuple = (String, usize, usize, usize, usize);
+
+/// (token, start, end, byte_start, byte_end, cost, in_dictionary)
+type LatticeEdgeTuple = (String, usize, usize, usize, usize, f64, bool);
+
+/// (word, distance, frequency)
+type CompletionTuple = (String, usize, u64);
+
+create_exception!(
+    thongna,
+    ThongnaError,
+    exceptions::PyRuntimeError,
+    "Base class of the errors raised by Thongna, other than OSError."
+);
+create_exception!(
+    thongna,
+    DictionaryError,
+    ThongnaError,
+    "A dictionary or subword vocabulary file, or a line of it, cannot be read."
+);
+create_exception!(
+    thongna,
+    RegexError,
+    ThongnaError,
+    "A regular expression is not supported."
+);
+create_exception!(
+    thongna,
+    SegmentationError,
+    ThongnaError,
+    "No segmentation of a text could be found."
+);
+create_exception!(
+    thongna,
+    SegmentationTimeoutError,
+    SegmentationError,
+    "Segmenting a text took longer than the timeout of its NewmmConfig."
+);
+
+impl From<crate::error::ThongnaError> for PyErr {
+    fn from(error: crate::error::ThongnaError) -> Self {
+        use crate::error::ThongnaError as Error;
+        match error {
+            Error::Io(error) => error.into(),
+            Error::InvalidDictionaryLine { .. }
+            | Error::InvalidCompiledDictionary(_)
+            | Error::InvalidVocabularyLine { .. } => {
+                DictionaryError::new_err(error.to_string())
+            }
+            Error::UnsupportedRegex { .. } => RegexError::new_err(error.to_string()),
+            Error::Segmentation(_) => SegmentationError::new_err(error.to_string()),
+            Error::Timeout(_) => SegmentationTimeoutError::new_err(error.to_string()),
+        }
+    }
+}
+
+static SYLLABLE_TOKENIZER: Lazy<SyllableTokenizer> = Lazy::new(SyllableTokenizer::new);
+
+static ROMANIZER: Lazy<Romanizer> = Lazy::new(Romanizer::new);
+
+static DICT_COLLECTION: Lazy<RwLock<HashMap<String, Box<NewmmTokenizer>>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+#[pyfunction]
+#[pyo3(text_signature = "(text, whitespace_number=True)")]
+fn normalize(text: &str, whitespace_number: bool) -> PyResult<String> {
+    // Normalize Thai text.
+    //
+    // This function normalizes Thai text by applying various rules to standardize
+    // the text representation.
+    //
+    // Args:
+    //     text (str): Input text to be normalized
+    //     whitespace_number (bool, optional): If True, adds spaces around numbers.
+    //                                         Defaults to True.
+    //
+    // Returns:
+    //     str: Normalized text
+    Ok(crate::normalize::normalize(text, whitespace_number))
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, dict_name, safe=None, parallel=None))]
+#[pyo3(text_signature = "(text, dict_name, safe=None, parallel=None)")]
+fn newmm(text: &str, dict_name: &str, safe: Option<bool>, parallel: Option<bool>) -> PyResult<Vec<String>> {
+    // Break text into tokens.
+    //
+    // This method is an implementation of newmm segmentation.
+    // Supports multithread mode - set by parallel flag.
+    //
+    // Args:
+    //     text (str): Input text
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //     safe (bool, optional): Use safe mode to avoid long waiting time in
+    //         a text with lots of ambiguous word boundaries.
+    //         Defaults to the safe mode of the dictionary's config.
+    //     parallel (bool, optional): Use multithread mode.
+    //         Defaults to the parallel mode of the dictionary's config.
+    //
+    // Returns:
+    //     List[str]: List of tokens
+    let dicts = DICT_COLLECTION.read().unwrap();
+    let loaded_dict = dicts.get(dict_name).ok_or_else(|| dict_not_found(dict_name))?;
+    let (safe, parallel) = modes_of(loaded_dict, safe, parallel);
+    Ok(loaded_dict.segment(text, safe, parallel)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, dict_name, safe=None, parallel=None))]
+#[pyo3(text_signature = "(text, dict_name, safe=None, parallel=None)")]
+fn newmm_with_offsets(
+    text: &str,
+    dict_name: &str,
+    safe: Option<bool>,
+    parallel: Option<bool>,
+) -> PyResult<Vec<TokenTuple>> {
+    // Break text into tokens, keeping the position of every token.
+    //
+    // Args:
+    //     text (str): Input text
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //     safe (bool, optional): Use safe mode. Defaults to the dictionary's config.
+    //     parallel (bool, optional): Use multithread mode. Defaults to the dictionary's config.
+    //
+    // Returns:
+    //     List[Tuple[str, int, int, int, int]]: (token, start, end, byte_start, byte_end)
+    //         for each token, where start/end are character offsets and
+    //         byte_start/byte_end are UTF-8 byte offsets into text
+    let dicts = DICT_COLLECTION.read().unwrap();
+    let loaded_dict = dicts.get(dict_name).ok_or_else(|| dict_not_found(dict_name))?;
+    let (safe, parallel) = modes_of(loaded_dict, safe, parallel);
+    let tokens = loaded_dict
+        .segment_with_offsets(text, safe, parallel)
+        .map_err(PyErr::from)?;
+    Ok(tokens
+        .into_iter()
+        .map(token_tuple)
+        .collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, dict_name, normalizer=None, safe=None, parallel=None))]
+#[pyo3(text_signature = "(text, dict_name, normalizer=None, safe=None, parallel=None)")]
+fn newmm_normalized_with_offsets(
+    py: Python<'_>,
+    text: &str,
+    dict_name: &str,
+    normalizer: Option<PyRef<'_, PyNormalizer>>,
+    safe: Option<bool>,
+    parallel: Option<bool>,
+) -> PyResult<Vec<TokenTuple>> {
+    // Normalize text, then break it into tokens with their position in the original text.
+    //
+    // Args:
+    //     text (str): Input text
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //     normalizer (Normalizer, optional): Defaults to Normalizer()
+    //     safe (bool, optional): Use safe mode. Defaults to the dictionary's config.
+    //     parallel (bool, optional): Use multithread mode. Defaults to the dictionary's config.
+    //
+    // Returns:
+    //     List[Tuple[str, int, int, int, int]]: (token, start, end, byte_start, byte_end)
+    //         for each token, where token is the part of the original text
+    //         the normalized token came from
+    let normalizer = normalizer.map_or_else(Normalizer::default, |normalizer| normalizer.inner.clone());
+    let tokens = py.allow_threads(|| {
+        let dicts = DICT_COLLECTION.read().unwrap();
+        let loaded_dict = dicts.get(dict_name).ok_or_else(|| dict_not_found(dict_name))?;
+        let (safe, parallel) = modes_of(loaded_dict, safe, parallel);
+        loaded_dict
+            .segment_normalized_with_offsets(text, &normalizer, safe, parallel)
+            .map_err(PyErr::from)
+    })?;
+    Ok(tokens.into_iter().map(token_tuple).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, dict_name, safe=false))]
+#[pyo3(text_signature = "(text, dict_name, safe=False)")]
+fn sentences_with_offsets(text: &str, dict_name: &str, safe: bool) -> PyResult<Vec<TokenTuple>> {
+    // Break text into sentences, keeping the position of every sentence.
+    //
+    // Sentences end at line breaks, terminal punctuation, sentence-final
+    // particles and spaces between clauses, found on the newmm tokens.
+    //
+    // Args:
+    //     text (str): Input text
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //     safe (bool, optional): Use safe mode. Defaults to False.
+    //
+    // Returns:
+    //     List[Tuple[str, int, int, int, int]]: (sentence, start, end, byte_start, byte_end)
+    //         for each sentence, see newmm_with_offsets()
+    let dicts = DICT_COLLECTION.read().unwrap();
+    let word_tokenizer = dicts.get(dict_name).ok_or_else(|| dict_not_found(dict_name))?;
+    let sentences = SentenceTokenizer::new(word_tokenizer).segment_with_offsets(text, safe, false)?;
+    Ok(sentences.into_iter().map(token_tuple).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (texts, dict_name, safe=None))]
+#[pyo3(text_signature = "(texts, dict_name, safe=None)")]
+fn newmm_batch(py: Python<'_>, texts: Vec<String>, dict_name: &str, safe: Option<bool>) -> PyResult<Vec<Vec<String>>> {
+    // Break a list of texts into tokens.
+    //
+    // The texts are segmented in parallel, with the GIL released,
+    // so other Python threads keep running meanwhile.
+    //
+    // Args:
+    //     texts (List[str]): Input texts
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //     safe (bool, optional): Use safe mode to avoid long waiting time in
+    //         a text with lots of ambiguous word boundaries.
+    //         Defaults to the safe mode of the dictionary's config.
+    //
+    // Returns:
+    //     List[List[str]]: List of tokens of each text
+    py.allow_threads(|| {
+        let dicts = DICT_COLLECTION.read().unwrap();
+        let loaded_dict = dicts.get(dict_name).ok_or_else(|| dict_not_found(dict_name))?;
+        let (safe, _) = modes_of(loaded_dict, safe, None);
+        loaded_dict.segment_batch(&texts, safe).map_err(PyErr::from)
+    })
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(chunks, dict_name)")]
+fn newmm_stream(chunks: &Bound<'_, PyAny>, dict_name: &str) -> PyResult<PyTokenStream> {
+    // Break the text of an iterable of chunks into tokens as it is read.
+    //
+    // Args:
+    //     chunks (Iterable[Union[str, bytes]]): Pieces of the text, bytes being UTF-8
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //
+    // Returns:
+    //     Iterator[Tuple[str, int, int, int, int]]: (token, start, end, byte_start, byte_end)
+    //         for each token, as newmm_with_offsets() in safe mode on the whole text
+    if !DICT_COLLECTION.read().unwrap().contains_key(dict_name) {
+        return Err(dict_not_found(dict_name));
+    }
+    PyTokenStream::new(chunks, StreamSource::Named(dict_name.to_owned()))
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text, dict_name)")]
+fn newmm_lattice(text: &str, dict_name: &str) -> PyResult<(Vec<usize>, Vec<LatticeEdgeTuple>)> {
+    // Build the segmentation lattice of text: every candidate word newmm considers.
+    //
+    // Args:
+    //     text (str): Input text
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //
+    // Returns:
+    //     Tuple[List[int], List[Tuple[str, int, int, int, int, float, bool]]]:
+    //         Character positions (nodes) and candidate words (edges) as
+    //         (token, start, end, byte_start, byte_end, cost, in_dictionary)
+    let dicts = DICT_COLLECTION.read().unwrap();
+    let loaded_dict = dicts.get(dict_name).ok_or_else(|| dict_not_found(dict_name))?;
+    let lattice = loaded_dict
+        .lattice(text)
+        .map_err(PyErr::from)?;
+    let edges = lattice
+        .edges
+        .into_iter()
+        .map(|edge| {
+            let token = edge.token;
+            (
+                token.text,
+                token.start,
+                token.end,
+                token.byte_start,
+                token.byte_end,
+                edge.cost,
+                edge.in_dictionary,
+            )
+        })
+        .collect();
+    Ok((lattice.nodes, edges))
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text, dict_name, k)")]
+fn newmm_nbest(text: &str, dict_name: &str, k: usize) -> PyResult<Vec<(Vec<String>, f64)>> {
+    // List the k best segmentations of text, best first.
+    //
+    // Args:
+    //     text (str): Input text
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //     k (int): Maximum number of segmentations
+    //
+    // Returns:
+    //     List[Tuple[List[str], float]]: Segmentations with their total cost
+    let dicts = DICT_COLLECTION.read().unwrap();
+    let loaded_dict = dicts.get(dict_name).ok_or_else(|| dict_not_found(dict_name))?;
+    let nbest = loaded_dict
+        .segment_nbest(text, k)
+        .map_err(PyErr::from)?;
+    Ok(nbest
+        .into_iter()
+        .map(|segmentation| {
+            let tokens = segmentation.tokens.into_iter().map(|token| token.text).collect();
+            (tokens, segmentation.cost)
+        })
+        .collect())
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text)")]
+fn tcc(text: &str) -> PyResult<Vec<String>> {
+    // Split text into Thai Character Clusters (TCC).
+    //
+    // Args:
+    //     text (str): Input text
+    //
+    // Returns:
+    //     List[str]: List of clusters
+    Ok(TccTokenizer::new().segment(text, false, false)?)
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text)")]
+fn syllables(text: &str) -> PyResult<Vec<String>> {
+    // Split text into written Thai syllables.
+    //
+    // Args:
+    //     text (str): Input text
+    //
+    // Returns:
+    //     List[str]: List of syllables
+    Ok(SYLLABLE_TOKENIZER.segment(text, false, false)?)
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text)")]
+fn syllables_with_offsets(text: &str) -> Vec<TokenTuple> {
+    // Split text into written Thai syllables, keeping the position of every syllable.
+    //
+    // Args:
+    //     text (str): Input text
+    //
+    // Returns:
+    //     List[Tuple[str, int, int, int, int]]: (syllable, start, end, byte_start, byte_end)
+    //         for each syllable, see newmm_with_offsets()
+    SYLLABLE_TOKENIZER
+        .segment_with_offsets(text)
+        .into_iter()
+        .map(token_tuple)
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, dict_name=None))]
+#[pyo3(text_signature = "(text, dict_name=None)")]
+fn romanize(py: Python<'_>, text: &str, dict_name: Option<&str>) -> PyResult<String> {
+    // Transcribe Thai text into Latin script with the Royal Thai General
+    // System of Transcription (RTGS), syllable by syllable.
+    //
+    // Args:
+    //     text (str): Input text
+    //     dict_name (str, optional): Dictionary name, as assigned in load_dict().
+    //         If given, the text is broken into words first so that no
+    //         syllable straddles two words.
+    //
+    // Returns:
+    //     str: Romanized text
+    let Some(dict_name) = dict_name else {
+        return Ok(crate::romanize::romanize(text));
+    };
+    py.allow_threads(|| {
+        let dicts = DICT_COLLECTION.read().unwrap();
+        let loaded_dict = dicts.get(dict_name).ok_or_else(|| dict_not_found(dict_name))?;
+        let (safe, _) = modes_of(loaded_dict, None, None);
+        let words = loaded_dict.segment(text, safe, false)?;
+        Ok(ROMANIZER.romanize_words(&words).concat())
+    })
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(words)")]
+fn romanize_words(py: Python<'_>, words: Vec<String>) -> Vec<String> {
+    // Romanize every word of a list with RTGS, in parallel.
+    //
+    // Args:
+    //     words (List[str]): Input words
+    //
+    // Returns:
+    //     List[str]: Romanized words
+    py.allow_threads(|| ROMANIZER.romanize_words(&words))
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(word)")]
+fn transliterate(word: &str) -> String {
+    // Transcribe a Thai word into IPA, with a Chao tone letter after every
+    // syllable and syllables separated by ".".
+    //
+    // Args:
+    //     word (str): Input word, e.g. a token of newmm()
+    //
+    // Returns:
+    //     str: IPA of the word
+    crate::transliterate::transliterate(word)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, engine="udom83"))]
+#[pyo3(text_signature = "(text, engine='udom83')")]
+fn soundex(text: &str, engine: &str) -> PyResult<String> {
+    // Phonetic key of a Thai name, for matching names spelled in different ways.
+    //
+    // Every whitespace-separated part of the text has its own key,
+    // and keys are joined by a space.
+    //
+    // Args:
+    //     text (str): Input text
+    //     engine (str, optional): "lk82", "udom83" or "metasound". Defaults to "udom83".
+    //
+    // Returns:
+    //     str: Phonetic key
+    //
+    // Raises:
+    //     ValueError: If engine is not a known algorithm
+    let algorithm: SoundexAlgorithm = engine.parse().map_err(exceptions::PyValueError::new_err)?;
+    Ok(crate::soundex::soundex(text, algorithm))
+}
+
+#[pyfunction]
+#[pyo3(signature = (texts, engine="udom83"))]
+#[pyo3(text_signature = "(texts, engine='udom83')")]
+fn soundex_batch(py: Python<'_>, texts: Vec<String>, engine: &str) -> PyResult<Vec<String>> {
+    // Phonetic keys of a list of names, computed in parallel with the GIL released.
+    //
+    // Args:
+    //     texts (List[str]): Input texts
+    //     engine (str, optional): "lk82", "udom83" or "metasound". Defaults to "udom83".
+    //
+    // Returns:
+    //     List[str]: Phonetic key of each text
+    let algorithm: SoundexAlgorithm = engine.parse().map_err(exceptions::PyValueError::new_err)?;
+    Ok(py.allow_threads(|| crate::soundex::soundex_batch(&texts, algorithm)))
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, length=crate::soundex::METASOUND_LENGTH))]
+#[pyo3(text_signature = "(text, length=4)")]
+fn metasound(text: &str, length: usize) -> String {
+    // MetaSound key of a Thai name, of a chosen length.
+    //
+    // Args:
+    //     text (str): Input text
+    //     length (int, optional): Length of the key of each part. Defaults to 4.
+    //
+    // Returns:
+    //     str: Phonetic key
+    crate::soundex::metasound(text, length)
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text)")]
+fn tcc_pos(text: &str) -> PyResult<HashSet<usize>> {
+    // Find the end position of every Thai Character Cluster (TCC).
+    //
+    // Args:
+    //     text (str): Input text
+    //
+    // Returns:
+    //     Set[int]: Character offsets at which a cluster ends
+    Ok(TccTokenizer::new().end_positions(text).into_iter().collect())
+}
+
+fn token_tuple(token: Token) -> TokenTuple {
+    (token.text, token.start, token.end, token.byte_start, token.byte_end)
+}
+
+fn completion_tuple(completion: Completion) -> CompletionTuple {
+    (completion.word, completion.distance, completion.frequency)
+}
+
+fn dict_not_found(dict_name: &str) -> PyErr {
+    DictionaryError::new_err(format!("Dictionary name {} does not exist.", dict_name))
+}
+
+fn path_selection_of(weighted: bool) -> PathSelection {
+    if weighted {
+        PathSelection::LowestCost
+    } else {
+        PathSelection::FewestTokens
+    }
+}
+
+/// The safe and parallel modes of a call, defaulting to those of the tokenizer's config
+fn modes_of(tokenizer: &NewmmTokenizer, safe: Option<bool>, parallel: Option<bool>) -> (bool, bool) {
+    let config = tokenizer.config();
+    (safe.unwrap_or(config.safe()), parallel.unwrap_or(config.parallel()))
+}
+
+/// Custom patterns first, then the patterns of the presets, then the default ones
+fn token_patterns_of(presets: Option<Vec<String>>, patterns: Option<Vec<String>>) -> PyResult<TokenPatterns> {
+    let mut all_patterns = patterns.unwrap_or_default();
+    for name in presets.unwrap_or_default() {
+        let preset: TokenPatternPreset = name.parse().map_err(exceptions::PyValueError::new_err)?;
+        all_patterns.push(preset.pattern().to_string());
+    }
+    all_patterns.extend(DEFAULT_TOKEN_PATTERNS.iter().map(|pattern| pattern.to_string()));
+    Ok(TokenPatterns::new(&all_patterns)?)
+}
+
+/// Builds a tokenizer with `build` and stores it as `dict_name`.
+/// The tokenizer is built before taking the write lock, so a replaced
+/// dictionary keeps serving other threads until the new one is ready.
+fn insert_dict(
+    dict_name: &str,
+    source: &str,
+    replace: bool,
+    build: impl FnOnce() -> PyResult<NewmmTokenizer>,
+) -> PyResult<(String, bool)> {
+    let already_exists = || {
+        Ok((
+            format!(
+                "Failed: dictionary name {} already exists, please use another name.",
+                dict_name
+            ),
+            false,
+        ))
+    };
+    if !replace && DICT_COLLECTION.read().unwrap().contains_key(dict_name) {
+        return already_exists();
+    }
+    let tokenizer = build()?;
+
+    let mut dict_col_lock = DICT_COLLECTION.write().unwrap();
+    if !replace && dict_col_lock.contains_key(dict_name) {
+        return already_exists();
+    }
+    dict_col_lock.insert(dict_name.to_owned(), Box::new(tokenizer));
+    Ok((
+        format!(
+            "Successful: {} has been successfully loaded to dictionary name {}.",
+            source, dict_name
+        ),
+        true,
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, dict_name, weighted=false, replace=false))]
+#[pyo3(text_signature = "(file_path, dict_name, weighted=False, replace=False)")]
+fn load_dict(file_path: &str, dict_name: &str, weighted: bool, replace: bool) -> PyResult<(String, bool)> {
+    // Load dictionary from a file.
+    //
+    // Load a dictionary file into an in-memory dictionary collection,
+    // and assign dict_name to it.
+    // This function does not override an existing dict name, unless replace is True.
+    //
+    // Args:
+    //     file_path (str): Path to a dictionary file, one word per line,
+    //         optionally followed by a tab and the word frequency
+    //     dict_name (str): A unique dictionary name, used for reference
+    //     weighted (bool, optional): Choose the most probable segmentation
+    //         using word frequencies instead of the one with the fewest tokens.
+    //         Defaults to False.
+    //     replace (bool, optional): Replace the dictionary already loaded as
+    //         dict_name, if any. Defaults to False.
+    //
+    // Returns:
+    //     Tuple[str, bool]: A tuple containing a human-readable result string and a boolean
+    insert_dict(dict_name, &format!("file {}", file_path), replace, || {
+        Ok(NewmmTokenizer::new(file_path)?.with_path_selection(path_selection_of(weighted)))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (words, dict_name, replace=false))]
+#[pyo3(text_signature = "(words, dict_name, replace=False)")]
+fn load_dict_from_list(words: Vec<String>, dict_name: &str, replace: bool) -> PyResult<(String, bool)> {
+    // Load dictionary from a list of words.
+    //
+    // This function does not override an existing dict name, unless replace is True.
+    //
+    // Args:
+    //     words (List[str]): Words of the dictionary
+    //     dict_name (str): A unique dictionary name, used for reference
+    //     replace (bool, optional): Replace the dictionary already loaded as
+    //         dict_name, if any. Defaults to False.
+    //
+    // Returns:
+    //     Tuple[str, bool]: A tuple containing a human-readable result string and a boolean
+    let source = format!("a list of {} words", words.len());
+    insert_dict(dict_name, &source, replace, || {
+        Ok(NewmmTokenizer::from_word_list(words))
+    })
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(dict_name)")]
+fn unload_dict(dict_name: &str) -> PyResult<bool> {
+    // Remove a dictionary from the dictionary collection.
+    //
+    // Args:
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //
+    // Returns:
+    //     bool: True if the dictionary was loaded, False otherwise
+    Ok(DICT_COLLECTION.write().unwrap().remove(dict_name).is_some())
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "()")]
+fn list_dicts() -> PyResult<Vec<String>> {
+    // List the names of the loaded dictionaries.
+    //
+    // Returns:
+    //     List[str]: Dictionary names, sorted
+    let mut dict_names: Vec<String> = DICT_COLLECTION.read().unwrap().keys().cloned().collect();
+    dict_names.sort();
+    Ok(dict_names)
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(dict_name)")]
+fn dict_word_count(dict_name: &str) -> PyResult<usize> {
+    // Count the words of a loaded dictionary.
+    //
+    // Args:
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //
+    // Returns:
+    //     int: Number of words
+    DICT_COLLECTION
+        .read()
+        .unwrap()
+        .get(dict_name)
+        .map(|loaded_dict| loaded_dict.amount_of_words())
+        .ok_or_else(|| dict_not_found(dict_name))
+}
+
+#[pyfunction]
+#[pyo3(signature = (prefix, dict_name, limit=10))]
+#[pyo3(text_signature = "(prefix, dict_name, limit=10)")]
+fn complete(prefix: &str, dict_name: &str, limit: usize) -> PyResult<Vec<CompletionTuple>> {
+    // Words of a loaded dictionary starting with a prefix, the most frequent first.
+    //
+    // Args:
+    //     prefix (str): Beginning of the words
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //     limit (int, optional): Largest number of words. Defaults to 10.
+    //
+    // Returns:
+    //     List[Tuple[str, int, int]]: (word, distance, frequency) of every word
+    let dicts = DICT_COLLECTION.read().unwrap();
+    let tokenizer = dicts.get(dict_name).ok_or_else(|| dict_not_found(dict_name))?;
+    Ok(tokenizer.complete(prefix, limit).into_iter().map(completion_tuple).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (prefix, dict_name, max_distance=1, limit=10))]
+#[pyo3(text_signature = "(prefix, dict_name, max_distance=1, limit=10)")]
+fn complete_fuzzy(
+    py: Python<'_>,
+    prefix: &str,
+    dict_name: &str,
+    max_distance: usize,
+    limit: usize,
+) -> PyResult<Vec<CompletionTuple>> {
+    // Words of a loaded dictionary starting with a prefix give or take some edits,
+    // the closest first, then the most frequent. The GIL is released while searching.
+    //
+    // Args:
+    //     prefix (str): Beginning of the words, possibly with typos
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //     max_distance (int, optional): Largest number of edits. Defaults to 1.
+    //     limit (int, optional): Largest number of words. Defaults to 10.
+    //
+    // Returns:
+    //     List[Tuple[str, int, int]]: (word, distance, frequency) of every word
+    let completions = py.allow_threads(|| {
+        DICT_COLLECTION
+            .read()
+            .unwrap()
+            .get(dict_name)
+            .map(|tokenizer| tokenizer.complete_fuzzy(prefix, max_distance, limit))
+            .ok_or_else(|| dict_not_found(dict_name))
+    })?;
+    Ok(completions.into_iter().map(completion_tuple).collect())
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(words, dict_name)")]
+fn add_words(words: Vec<String>, dict_name: &str) -> PyResult<()> {
+    // Add words to a loaded dictionary.
+    //
+    // Words already in the dictionary keep their frequency.
+    // Segmentation calls running on other threads wait for the update.
+    //
+    // Args:
+    //     words (List[str]): Words to add
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    let word_list: Vec<&str> = words.iter().map(String::as_str).collect();
+    DICT_COLLECTION
+        .write()
+        .unwrap()
+        .get_mut(dict_name)
+        .map(|loaded_dict| loaded_dict.add_word(&word_list))
+        .ok_or_else(|| dict_not_found(dict_name))
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(words, dict_name)")]
+fn remove_words(words: Vec<String>, dict_name: &str) -> PyResult<()> {
+    // Remove words from a loaded dictionary.
+    //
+    // Words not in the dictionary are ignored.
+    //
+    // Args:
+    //     words (List[str]): Words to remove
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    let word_list: Vec<&str> = words.iter().map(String::as_str).collect();
+    DICT_COLLECTION
+        .write()
+        .unwrap()
+        .get_mut(dict_name)
+        .map(|loaded_dict| loaded_dict.remove_word(&word_list))
+        .ok_or_else(|| dict_not_found(dict_name))
+}
+
+#[pyfunction]
+#[pyo3(signature = (dict_name, presets=None, patterns=None))]
+#[pyo3(text_signature = "(dict_name, presets=None, patterns=None)")]
+fn set_token_patterns(
+    dict_name: &str,
+    presets: Option<Vec<String>>,
+    patterns: Option<Vec<String>>,
+) -> PyResult<()> {
+    // Choose the tokens a loaded dictionary takes whole, before any dictionary lookup.
+    //
+    // Latin words, numbers, Thai numbers, spaces and line breaks are always
+    // matched, after the custom patterns and the presets.
+    //
+    // Args:
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //     presets (List[str], optional): Built-in patterns among "url", "email",
+    //         "mention", "hashtag", "emoji" and "number_with_unit"
+    //     patterns (List[str], optional): Regular expressions of tokens,
+    //         tried before the presets
+    let token_patterns = token_patterns_of(presets, patterns)?;
+    DICT_COLLECTION
+        .write()
+        .unwrap()
+        .get_mut(dict_name)
+        .map(|loaded_dict| loaded_dict.set_token_patterns(token_patterns))
+        .ok_or_else(|| dict_not_found(dict_name))
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(dict_name, config)")]
+fn set_config(dict_name: &str, config: PyNewmmConfig) -> PyResult<()> {
+    // Set the segmentation limits of a loaded dictionary.
+    //
+    // Args:
+    //     dict_name (str): Dictionary name, as assigned in load_dict()
+    //     config (NewmmConfig): Limits, and the safe and parallel modes
+    //         used when a call does not choose them
+    DICT_COLLECTION
+        .write()
+        .unwrap()
+        .get_mut(dict_name)
+        .map(|loaded_dict| loaded_dict.set_config(config.inner))
+        .ok_or_else(|| dict_not_found(dict_name))
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(file_path, output_path)")]
+fn compile_dict(file_path: &str, output_path: &str) -> PyResult<()> {
+    // Compile a dictionary file into a binary file for load_compiled_dict().
+    //
+    // Args:
+    //     file_path (str): Path to a dictionary file, one word per line,
+    //         optionally followed by a tab and the word frequency
+    //     output_path (str): Path of the compiled dictionary to write
+    compiled_dict::compile_dict(DictSource::FilePath(PathBuf::from(file_path)), Path::new(output_path))
+        .map_err(PyErr::from)
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, dict_name, weighted=false, replace=false))]
+#[pyo3(text_signature = "(file_path, dict_name, weighted=False, replace=False)")]
+fn load_compiled_dict(file_path: &str, dict_name: &str, weighted: bool, replace: bool) -> PyResult<(String, bool)> {
+    // Load a dictionary compiled by compile_dict().
+    //
+    // The file is memory-mapped, so loading takes milliseconds and
+    // processes loading the same file share its memory.
+    // This function does not override an existing dict name, unless replace is True.
+    //
+    // Args:
+    //     file_path (str): Path to a compiled dictionary file
+    //     dict_name (str): A unique dictionary name, used for reference
+    //     weighted (bool, optional): Choose the most probable segmentation
+    //         using word frequencies instead of the one with the fewest tokens.
+    //         Defaults to False.
+    //     replace (bool, optional): Replace the dictionary already loaded as
+    //         dict_name, if any. Defaults to False.
+    //
+    // Returns:
+    //     Tuple[str, bool]: A tuple containing a human-readable result string and a boolean
+    insert_dict(dict_name, &format!("file {}", file_path), replace, || {
+        let dict = compiled_dict::CompiledDictionary::open(Path::new(file_path))
+            .map_err(PyErr::from)?;
+        Ok(NewmmTokenizer::from_dictionary(Box::new(dict)).with_path_selection(path_selection_of(weighted)))
+    })
+}
+
+/// Limits of newmm segmentation, with the safe and parallel modes used
+/// when a call does not choose them.
+#[pyclass(name = "NewmmConfig", module = "thongna", frozen, eq)]
+#[derive(Clone, PartialEq)]
+struct PyNewmmConfig {
+    inner: NewmmConfig,
+}
+
+#[pymethods]
+impl PyNewmmConfig {
+    #[new]
+    #[pyo3(signature = (
+        max_graph_size=None,
+        text_scan_point=None,
+        text_scan_left=None,
+        text_scan_right=None,
+        safe=false,
+        parallel=false,
+        timeout=None
+    ))]
+    #[pyo3(text_signature = "(max_graph_size=50, text_scan_point=120, text_scan_left=20, text_scan_right=20, safe=False, parallel=False, timeout=None)")]
+    fn new(
+        max_graph_size: Option<usize>,
+        text_scan_point: Option<usize>,
+        text_scan_left: Option<usize>,
+        text_scan_right: Option<usize>,
+        safe: bool,
+        parallel: bool,
+        timeout: Option<f64>,
+    ) -> PyResult<Self> {
+        // Create segmentation limits.
+        //
+        // Args:
+        //     max_graph_size (int, optional): Number of candidate words after which
+        //         the graph between two cut points stops growing. Defaults to 50.
+        //     text_scan_point (int, optional): Usual length of a safe mode part,
+        //         in characters. Defaults to 120.
+        //     text_scan_left (int, optional): How far before text_scan_point a safe
+        //         mode cut may be. Defaults to 20.
+        //     text_scan_right (int, optional): How far after text_scan_point a safe
+        //         mode cut may be. Defaults to 20.
+        //     safe (bool, optional): Safe mode when a call does not choose. Defaults to False.
+        //     parallel (bool, optional): Multithread mode when a call does not choose.
+        //         Defaults to False.
+        //     timeout (float, optional): Seconds segmenting one text may take before
+        //         SegmentationTimeoutError is raised. Defaults to None, no limit.
+        let default = NewmmConfig::default();
+        let timeout = timeout
+            .map(|seconds| {
+                Duration::try_from_secs_f64(seconds).map_err(|_| {
+                    exceptions::PyValueError::new_err(format!(
+                        "timeout must be a non-negative number of seconds, got {}",
+                        seconds
+                    ))
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            inner: default
+                .with_max_graph_size(max_graph_size.unwrap_or(default.max_graph_size()))
+                .with_text_scan_point(text_scan_point.unwrap_or(default.text_scan_point()))
+                .with_text_scan_left(text_scan_left.unwrap_or(default.text_scan_left()))
+                .with_text_scan_right(text_scan_right.unwrap_or(default.text_scan_right()))
+                .with_safe(safe)
+                .with_parallel(parallel)
+                .with_timeout(timeout),
+        })
+    }
+
+    #[getter]
+    fn max_graph_size(&self) -> usize {
+        self.inner.max_graph_size()
+    }
+
+    #[getter]
+    fn text_scan_point(&self) -> usize {
+        self.inner.text_scan_point()
+    }
+
+    #[getter]
+    fn text_scan_left(&self) -> usize {
+        self.inner.text_scan_left()
+    }
+
+    #[getter]
+    fn text_scan_right(&self) -> usize {
+        self.inner.text_scan_right()
+    }
+
+    #[getter]
+    fn safe(&self) -> bool {
+        self.inner.safe()
+    }
+
+    #[getter]
+    fn parallel(&self) -> bool {
+        self.inner.parallel()
+    }
+
+    #[getter]
+    fn timeout(&self) -> Option<f64> {
+        self.inner.timeout().map(|timeout| timeout.as_secs_f64())
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn __getnewargs__(&self) -> (usize, usize, usize, usize, bool, bool, Option<f64>) {
+        (
+            self.max_graph_size(),
+            self.text_scan_point(),
+            self.text_scan_left(),
+            self.text_scan_right(),
+            self.safe(),
+            self.parallel(),
+            self.timeout(),
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "NewmmConfig(max_graph_size={}, text_scan_point={}, text_scan_left={}, text_scan_right={}, safe={}, parallel={}, timeout={})",
+            self.max_graph_size(),
+            self.text_scan_point(),
+            self.text_scan_left(),
+            self.text_scan_right(),
+            if self.safe() { "True" } else { "False" },
+            if self.parallel() { "True" } else { "False" },
+            self.timeout().map_or("None".to_string(), |timeout| timeout.to_string()),
+        )
+    }
+}
+
+/// A newmm tokenizer owning its dictionary.
+///
+/// Unlike the functions taking a dict_name, it does not go through the
+/// global dictionary collection; every instance has its own lock.
+#[pyclass(name = "Tokenizer", module = "thongna", frozen)]
+struct PyTokenizer {
+    inner: RwLock<NewmmTokenizer>,
+}
+
+impl PyTokenizer {
+    fn from_tokenizer(tokenizer: NewmmTokenizer) -> Self {
+        Self {
+            inner: RwLock::new(tokenizer),
+        }
+    }
+}
+
+#[pymethods]
+impl PyTokenizer {
+    #[new]
+    #[pyo3(signature = (dict_path=None, words=None, weighted=false, presets=None, patterns=None, config=None))]
+    #[pyo3(text_signature = "(dict_path=None, words=None, weighted=False, presets=None, patterns=None, config=None)")]
+    fn new(
+        dict_path: Option<&str>,
+        words: Option<Vec<String>>,
+        weighted: bool,
+        presets: Option<Vec<String>>,
+        patterns: Option<Vec<String>>,
+        config: Option<PyNewmmConfig>,
+    ) -> PyResult<Self> {
+        // Create a tokenizer from a dictionary file and/or a list of words.
+        //
+        // Args:
+        //     dict_path (str, optional): Path to a dictionary file, one word per line,
+        //         optionally followed by a tab and the word frequency
+        //     words (List[str], optional): Words to add to the dictionary
+        //     weighted (bool, optional): Choose the most probable segmentation
+        //         using word frequencies instead of the one with the fewest tokens.
+        //         Defaults to False.
+        //     presets (List[str], optional): Built-in token patterns, see set_token_patterns()
+        //     patterns (List[str], optional): Regular expressions of tokens taken whole
+        //     config (NewmmConfig, optional): Segmentation limits
+        let source = match dict_path {
+            Some(dict_path) => DictSource::FilePath(PathBuf::from(dict_path)),
+            None => DictSource::WordList(vec![]),
+        };
+        let dict = create_dict_trie(source)?;
+        let mut tokenizer = NewmmTokenizer::from_dictionary(Box::new(dict))
+            .with_path_selection(path_selection_of(weighted))
+            .with_token_patterns(token_patterns_of(presets, patterns)?)
+            .with_config(config.map(|config| config.inner).unwrap_or_default());
+        if let Some(words) = words {
+            tokenizer.add_word(&words.iter().map(String::as_str).collect::<Vec<&str>>());
+        }
+        Ok(Self::from_tokenizer(tokenizer))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (file_path, weighted=false))]
+    #[pyo3(text_signature = "(file_path, weighted=False)")]
+    fn from_compiled_dict(file_path: &str, weighted: bool) -> PyResult<Self> {
+        // Create a tokenizer from a dictionary compiled by compile_dict().
+        //
+        // Args:
+        //     file_path (str): Path to a compiled dictionary file
+        //     weighted (bool, optional): Choose the most probable segmentation
+        //         using word frequencies. Defaults to False.
+        let dict = compiled_dict::CompiledDictionary::open(Path::new(file_path))
+            .map_err(PyErr::from)?;
+        Ok(Self::from_tokenizer(
+            NewmmTokenizer::from_dictionary(Box::new(dict)).with_path_selection(path_selection_of(weighted)),
+        ))
+    }
+
+    #[pyo3(signature = (text, safe=None, parallel=None))]
+    #[pyo3(text_signature = "(self, text, safe=None, parallel=None)")]
+    fn segment(
+        &self,
+        py: Python<'_>,
+        text: &str,
+        safe: Option<bool>,
+        parallel: Option<bool>,
+    ) -> PyResult<Vec<String>> {
+        // Break text into tokens, see newmm().
+        //
+        // The GIL is released while segmenting.
+        py.allow_threads(|| {
+            let tokenizer = self.inner.read().unwrap();
+            let (safe, parallel) = modes_of(&tokenizer, safe, parallel);
+            tokenizer.segment(text, safe, parallel).map_err(PyErr::from)
+        })
+    }
+
+    #[pyo3(signature = (texts, safe=None))]
+    #[pyo3(text_signature = "(self, texts, safe=None)")]
+    fn segment_batch(&self, py: Python<'_>, texts: Vec<String>, safe: Option<bool>) -> PyResult<Vec<Vec<String>>> {
+        // Break a list of texts into tokens in parallel, see newmm_batch().
+        py.allow_threads(|| {
+            let tokenizer = self.inner.read().unwrap();
+            let (safe, _) = modes_of(&tokenizer, safe, None);
+            tokenizer.segment_batch(&texts, safe).map_err(PyErr::from)
+        })
+    }
+
+    #[pyo3(signature = (text, safe=None, parallel=None))]
+    #[pyo3(text_signature = "(self, text, safe=None, parallel=None)")]
+    fn segment_with_offsets(
+        &self,
+        py: Python<'_>,
+        text: &str,
+        safe: Option<bool>,
+        parallel: Option<bool>,
+    ) -> PyResult<Vec<TokenTuple>> {
+        // Break text into tokens with their offsets, see newmm_with_offsets().
+        let tokens = py
+            .allow_threads(|| {
+                let tokenizer = self.inner.read().unwrap();
+                let (safe, parallel) = modes_of(&tokenizer, safe, parallel);
+                tokenizer.segment_with_offsets(text, safe, parallel)
+            })
+            .map_err(PyErr::from)?;
+        Ok(tokens
+            .into_iter()
+            .map(token_tuple)
+            .collect())
+    }
+
+    #[pyo3(signature = (text, normalizer=None, safe=None, parallel=None))]
+    #[pyo3(text_signature = "(self, text, normalizer=None, safe=None, parallel=None)")]
+    fn segment_normalized_with_offsets(
+        &self,
+        py: Python<'_>,
+        text: &str,
+        normalizer: Option<PyRef<'_, PyNormalizer>>,
+        safe: Option<bool>,
+        parallel: Option<bool>,
+    ) -> PyResult<Vec<TokenTuple>> {
+        // Normalize text, then break it into tokens with their position in the original text,
+        // see newmm_normalized_with_offsets().
+        let normalizer = normalizer.map_or_else(Normalizer::default, |normalizer| normalizer.inner.clone());
+        let tokens = py
+            .allow_threads(|| {
+                let tokenizer = self.inner.read().unwrap();
+                let (safe, parallel) = modes_of(&tokenizer, safe, parallel);
+                tokenizer.segment_normalized_with_offsets(text, &normalizer, safe, parallel)
+            })
+            .map_err(PyErr::from)?;
+        Ok(tokens.into_iter().map(token_tuple).collect())
+    }
+
+    #[pyo3(signature = (text, safe=false))]
+    #[pyo3(text_signature = "(self, text, safe=False)")]
+    fn sentences_with_offsets(&self, py: Python<'_>, text: &str, safe: bool) -> PyResult<Vec<TokenTuple>> {
+        // Break text into sentences with their offsets, see sentences_with_offsets().
+        let sentences = py.allow_threads(|| {
+            SentenceTokenizer::new(&self.inner.read().unwrap()).segment_with_offsets(text, safe, false)
+        })?;
+        Ok(sentences.into_iter().map(token_tuple).collect())
+    }
+
+    #[pyo3(text_signature = "(self, chunks)")]
+    fn segment_stream(slf: Py<Self>, chunks: &Bound<'_, PyAny>) -> PyResult<PyTokenStream> {
+        // Break the text of an iterable of chunks into tokens as it is read, see newmm_stream().
+        PyTokenStream::new(chunks, StreamSource::Owned(slf))
+    }
+
+    #[pyo3(signature = (prefix, limit=10))]
+    #[pyo3(text_signature = "(self, prefix, limit=10)")]
+    fn complete(&self, prefix: &str, limit: usize) -> Vec<CompletionTuple> {
+        // Words starting with a prefix, the most frequent first, see complete().
+        let tokenizer = self.inner.read().unwrap();
+        tokenizer.complete(prefix, limit).into_iter().map(completion_tuple).collect()
+    }
+
+    #[pyo3(signature = (prefix, max_distance=1, limit=10))]
+    #[pyo3(text_signature = "(self, prefix, max_distance=1, limit=10)")]
+    fn complete_fuzzy(&self, py: Python<'_>, prefix: &str, max_distance: usize, limit: usize) -> Vec<CompletionTuple> {
+        // Words starting with a prefix give or take some edits, see complete_fuzzy().
+        let completions = py.allow_threads(|| {
+            self.inner
+                .read()
+                .unwrap()
+                .complete_fuzzy(prefix, max_distance, limit)
+        });
+        completions.into_iter().map(completion_tuple).collect()
+    }
+
+    #[pyo3(text_signature = "(self, words)")]
+    fn add_words(&self, words: Vec<String>) {
+        // Add words to the dictionary, keeping the frequency of existing words.
+        let word_list: Vec<&str> = words.iter().map(String::as_str).collect();
+        self.inner.write().unwrap().add_word(&word_list);
+    }
+
+    #[pyo3(text_signature = "(self, words)")]
+    fn remove_words(&self, words: Vec<String>) {
+        // Remove words from the dictionary. Words not in it are ignored.
+        let word_list: Vec<&str> = words.iter().map(String::as_str).collect();
+        self.inner.write().unwrap().remove_word(&word_list);
+    }
+
+    #[pyo3(signature = (presets=None, patterns=None))]
+    #[pyo3(text_signature = "(self, presets=None, patterns=None)")]
+    fn set_token_patterns(&self, presets: Option<Vec<String>>, patterns: Option<Vec<String>>) -> PyResult<()> {
+        // Choose the tokens taken whole, see set_token_patterns().
+        let token_patterns = token_patterns_of(presets, patterns)?;
+        self.inner.write().unwrap().set_token_patterns(token_patterns);
+        Ok(())
+    }
+
+    #[getter]
+    fn weighted(&self) -> bool {
+        self.inner.read().unwrap().path_selection() == PathSelection::LowestCost
+    }
+
+    #[pyo3(text_signature = "(self, config)")]
+    fn set_config(&self, config: PyNewmmConfig) {
+        // Set the segmentation limits, see set_config().
+        self.inner.write().unwrap().set_config(config.inner);
+    }
+
+    #[getter]
+    fn config(&self) -> PyNewmmConfig {
+        PyNewmmConfig {
+            inner: *self.inner.read().unwrap().config(),
+        }
+    }
+
+    #[getter]
+    fn token_patterns(&self) -> Vec<String> {
+        self.inner.read().unwrap().token_patterns().patterns().to_vec()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.read().unwrap().amount_of_words()
+    }
+
+    fn __getstate__(&self) -> (Vec<(String, u64)>, bool, Vec<String>, PyNewmmConfig) {
+        // The dictionary is pickled as (word, frequency) pairs, so a tokenizer
+        // from a compiled dictionary is restored as an in-memory one.
+        let tokenizer = self.inner.read().unwrap();
+        (
+            tokenizer.words(),
+            tokenizer.path_selection() == PathSelection::LowestCost,
+            tokenizer.token_patterns().patterns().to_vec(),
+            PyNewmmConfig {
+                inner: *tokenizer.config(),
+            },
+        )
+    }
+
+    fn __setstate__(&self, state: (Vec<(String, u64)>, bool, Vec<String>, PyNewmmConfig)) -> PyResult<()> {
+        let (words, weighted, patterns, config) = state;
+        *self.inner.write().unwrap() = NewmmTokenizer::from_weighted_word_list(words)
+            .with_path_selection(path_selection_of(weighted))
+            .with_token_patterns(TokenPatterns::new(&patterns)?)
+            .with_config(config.inner);
+        Ok(())
+    }
+}
+
+/// Where a token stream finds its tokenizer
+enum StreamSource {
+    Owned(Py<PyTokenizer>),
+    Named(String),
+}
+
+/// Tokens of an iterable of text chunks, segmented as the chunks are read.
+#[pyclass(name = "TokenStream", module = "thongna")]
+struct PyTokenStream {
+    chunks: PyObject,
+    source: StreamSource,
+    segmenter: StreamSegmenter,
+    tokens: VecDeque<Token>,
+    done: bool,
+}
+
+impl PyTokenStream {
+    fn new(chunks: &Bound<'_, PyAny>, source: StreamSource) -> PyResult<Self> {
+        Ok(Self {
+            chunks: chunks.iter()?.into_any().unbind(),
+            source,
+            segmenter: StreamSegmenter::new(),
+            tokens: Default::default(),
+            done: false,
+        })
+    }
+
+    /// Runs `f` on the tokenizer of the stream, without the GIL
+    fn with_tokenizer<T: Send>(
+        &mut self,
+        py: Python<'_>,
+        f: impl FnOnce(&mut StreamSegmenter, &NewmmTokenizer) -> PyResult<T> + Send,
+    ) -> PyResult<T> {
+        let Self {
+            source, segmenter, ..
+        } = self;
+        py.allow_threads(|| match source {
+            StreamSource::Owned(tokenizer) => f(segmenter, &tokenizer.get().inner.read().unwrap()),
+            StreamSource::Named(dict_name) => {
+                let dicts = DICT_COLLECTION.read().unwrap();
+                let tokenizer = dicts.get(dict_name.as_str()).ok_or_else(|| dict_not_found(dict_name))?;
+                f(segmenter, tokenizer)
+            }
+        })
+    }
+}
+
+#[pymethods]
+impl PyTokenStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<TokenTuple>> {
+        loop {
+            if let Some(token) = self.tokens.pop_front() {
+                return Ok(Some(token_tuple(token)));
+            }
+            if self.done {
+                return Ok(None);
+            }
+            let chunk = self.chunks.bind(py).call_method0("__next__");
+            let result = match chunk {
+                Ok(chunk) => {
+                    let bytes: Vec<u8> = match chunk.extract::<String>() {
+                        Ok(text) => text.into_bytes(),
+                        Err(_) => chunk.extract::<Vec<u8>>().map_err(|_| {
+                            exceptions::PyTypeError::new_err(format!(
+                                "Chunks must be str or bytes, not {}",
+                                chunk.get_type()
+                            ))
+                        })?,
+                    };
+                    self.with_tokenizer(py, |segmenter, tokenizer| {
+                        Ok(segmenter.push(tokenizer, &bytes)?)
+                    })
+                }
+                Err(error) if error.is_instance_of::<exceptions::PyStopIteration>(py) => {
+                    self.done = true;
+                    self.with_tokenizer(py, |segmenter, tokenizer| Ok(segmenter.finish(tokenizer)?))
+                }
+                Err(error) => Err(error),
+            };
+            match result {
+                Ok(tokens) => self.tokens.extend(tokens),
+                Err(error) => {
+                    self.done = true;
+                    return Err(error);
+                }
+            }
+        }
+    }
+}
+
+#[pyclass(name = "SyllableTokenizer", module = "thongna", frozen)]
+struct PySyllableTokenizer {
+    inner: SyllableTokenizer,
+}
+
+#[pymethods]
+impl PySyllableTokenizer {
+    #[new]
+    #[pyo3(signature = (dict_path=None, words=None))]
+    #[pyo3(text_signature = "(dict_path=None, words=None)")]
+    fn new(dict_path: Option<&str>, words: Option<Vec<String>>) -> PyResult<Self> {
+        // Create a syllable tokenizer that also accepts the syllables of a dictionary.
+        //
+        // Args:
+        //     dict_path (str, optional): Path to a syllable dictionary file, one syllable per line
+        //     words (List[str], optional): Syllables to add to the dictionary
+        let mut entries = match dict_path {
+            Some(dict_path) => read_dict_entries(DictSource::FilePath(PathBuf::from(dict_path)))?,
+            None => vec![],
+        };
+        entries.extend(words.unwrap_or_default().into_iter().map(|word| (word, 0)));
+        Ok(Self {
+            inner: SyllableTokenizer::from_dictionary(DictSource::WeightedWordList(entries))?,
+        })
+    }
+
+    #[pyo3(text_signature = "(self, text)")]
+    fn segment(&self, text: &str) -> PyResult<Vec<String>> {
+        // Split text into syllables, see syllables().
+        Ok(self.inner.segment(text, false, false)?)
+    }
+
+    #[pyo3(text_signature = "(self, text)")]
+    fn segment_with_offsets(&self, text: &str) -> Vec<TokenTuple> {
+        // Split text into syllables with their offsets, see syllables_with_offsets().
+        self.inner
+            .segment_with_offsets(text)
+            .into_iter()
+            .map(token_tuple)
+            .collect()
+    }
+}
+
+#[pyclass(name = "Transliterator", module = "thongna", frozen)]
+struct PyTransliterator {
+    inner: Transliterator,
+}
+
+#[pymethods]
+impl PyTransliterator {
+    #[new]
+    #[pyo3(signature = (dict_path=None, exceptions=None))]
+    #[pyo3(text_signature = "(dict_path=None, exceptions=None)")]
+    fn new(dict_path: Option<&str>, exceptions: Option<HashMap<String, String>>) -> PyResult<Self> {
+        // Create a transliterator that reads the words of an exceptions dictionary as given there.
+        //
+        // Args:
+        //     dict_path (str, optional): Path to an exceptions dictionary file,
+        //         one word and its IPA separated by a tab per line
+        //     exceptions (Dict[str, str], optional): IPA of more words
+        let transliterator = match dict_path {
+            Some(dict_path) => {
+                Transliterator::from_exceptions(DictSource::FilePath(PathBuf::from(dict_path)))?
+            }
+            None => Transliterator::new(),
+        };
+        Ok(Self {
+            inner: transliterator.with_exceptions(exceptions.unwrap_or_default()),
+        })
+    }
+
+    #[pyo3(text_signature = "(self, word)")]
+    fn transliterate(&self, word: &str) -> String {
+        // Transcribe a word into IPA, see transliterate().
+        self.inner.transliterate(word)
+    }
+
+    #[pyo3(text_signature = "(self, words)")]
+    fn transliterate_words(&self, py: Python<'_>, words: Vec<String>) -> Vec<String> {
+        // Transcribe every word into IPA in parallel, with the GIL released.
+        py.allow_threads(|| self.inner.transliterate_words(&words))
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.exceptions_len()
+    }
+}
+
+#[pyclass(name = "SpellChecker", module = "thongna", frozen)]
+struct PySpellChecker {
+    inner: SpellChecker,
+}
+
+#[pymethods]
+impl PySpellChecker {
+    #[new]
+    #[pyo3(signature = (dict_path=None, words=None, max_distance=DEFAULT_MAX_DISTANCE))]
+    #[pyo3(text_signature = "(dict_path=None, words=None, max_distance=2)")]
+    fn new(dict_path: Option<&str>, words: Option<Vec<String>>, max_distance: usize) -> PyResult<Self> {
+        // Create a spell checker from the words of a dictionary, which also segments text.
+        //
+        // Args:
+        //     dict_path (str, optional): Path to a dictionary file, one word per line,
+        //         optionally followed by a tab and the word frequency
+        //     words (List[str], optional): Words to add to the dictionary
+        //     max_distance (int, optional): Largest number of edits between a word
+        //         and its suggestions. Defaults to 2.
+        let source = match dict_path {
+            Some(dict_path) => DictSource::FilePath(PathBuf::from(dict_path)),
+            None => DictSource::WordList(vec![]),
+        };
+        let mut tokenizer = NewmmTokenizer::from_dictionary(Box::new(create_dict_trie(source)?));
+        if let Some(words) = words {
+            tokenizer.add_word(&words.iter().map(String::as_str).collect::<Vec<&str>>());
+        }
+        Ok(Self {
+            inner: SpellChecker::new(tokenizer).with_max_distance(max_distance),
+        })
+    }
+
+    #[pyo3(text_signature = "(self, word)")]
+    fn suggest(&self, py: Python<'_>, word: &str) -> Vec<(String, f64, u64)> {
+        // Dictionary words close to a word as (word, distance, frequency) tuples,
+        // the closest and most frequent first, with the GIL released.
+        py.allow_threads(|| {
+            self.inner
+                .suggest(word)
+                .into_iter()
+                .map(|suggestion| (suggestion.word, suggestion.distance, suggestion.frequency))
+                .collect()
+        })
+    }
+
+    #[pyo3(text_signature = "(self, word)")]
+    fn correct(&self, py: Python<'_>, word: &str) -> String {
+        // The best suggestion for a word, or the word itself.
+        py.allow_threads(|| self.inner.correct(word))
+    }
+
+    #[pyo3(text_signature = "(self, text)")]
+    fn correct_text(&self, py: Python<'_>, text: &str) -> PyResult<String> {
+        // Segment text with newmm and correct the words not in the dictionary.
+        Ok(py.allow_threads(|| self.inner.correct_text(text))?)
+    }
+
+    fn __contains__(&self, word: &str) -> bool {
+        self.inner.contains(word)
+    }
+}
+
+/// Runs `f` with the words of a loaded dictionary as pre-tokens,
+/// or with runs of whitespace and non-whitespace without a dictionary
+fn with_pre_tokenizer<T>(
+    dict_name: Option<&str>,
+    f: impl FnOnce(&PreTokenizer) -> crate::error::ThongnaResult<T>,
+) -> PyResult<T> {
+    match dict_name {
+        Some(dict_name) => {
+            let dicts = DICT_COLLECTION.read().unwrap();
+            let word_tokenizer = dicts.get(dict_name).ok_or_else(|| dict_not_found(dict_name))?;
+            Ok(f(&PreTokenizer::Newmm(word_tokenizer))?)
+        }
+        None => Ok(f(&PreTokenizer::Whitespace)?),
+    }
+}
+
+#[pyclass(name = "BpeTokenizer", module = "thongna", frozen)]
+struct PyBpeTokenizer {
+    inner: BpeTokenizer,
+}
+
+#[pymethods]
+impl PyBpeTokenizer {
+    #[staticmethod]
+    #[pyo3(signature = (texts, vocab_size, dict_name=None, min_frequency=2))]
+    #[pyo3(text_signature = "(texts, vocab_size, dict_name=None, min_frequency=2)")]
+    fn train(
+        py: Python<'_>,
+        texts: Vec<String>,
+        vocab_size: usize,
+        dict_name: Option<&str>,
+        min_frequency: u64,
+    ) -> PyResult<Self> {
+        // Learn a BPE vocabulary over character clusters from texts.
+        //
+        // The GIL is released while training.
+        //
+        // Args:
+        //     texts (List[str]): Training texts
+        //     vocab_size (int): Number of tokens to reach, unknown token and clusters included
+        //     dict_name (str, optional): Dictionary whose newmm words are the pre-tokens,
+        //         as assigned in load_dict(). Defaults to runs of whitespace and non-whitespace.
+        //     min_frequency (int, optional): Stop when no pair of symbols occurs
+        //         this many times. Defaults to 2.
+        let inner = py.allow_threads(|| {
+            with_pre_tokenizer(dict_name, |pre_tokenizer| {
+                BpeTokenizer::train(&texts, pre_tokenizer, vocab_size, min_frequency)
+            })
+        })?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    #[pyo3(text_signature = "(file_path)")]
+    fn load(file_path: &str) -> PyResult<Self> {
+        // Read a vocabulary written by save().
+        Ok(Self {
+            inner: BpeTokenizer::load(file_path)?,
+        })
+    }
+
+    #[pyo3(text_signature = "(self, file_path)")]
+    fn save(&self, file_path: &str) -> PyResult<()> {
+        // Write the vocabulary to a file.
+        Ok(self.inner.save(file_path)?)
+    }
+
+    #[pyo3(signature = (text, dict_name=None))]
+    #[pyo3(text_signature = "(self, text, dict_name=None)")]
+    fn encode(&self, text: &str, dict_name: Option<&str>) -> PyResult<Vec<String>> {
+        // Split text into subwords, using the pre-tokens used in training.
+        with_pre_tokenizer(dict_name, |pre_tokenizer| self.inner.encode(text, pre_tokenizer))
+    }
+
+    #[pyo3(signature = (text, dict_name=None))]
+    #[pyo3(text_signature = "(self, text, dict_name=None)")]
+    fn encode_ids(&self, text: &str, dict_name: Option<&str>) -> PyResult<Vec<u32>> {
+        // Split text into subword ids, using the pre-tokens used in training.
+        with_pre_tokenizer(dict_name, |pre_tokenizer| self.inner.encode_ids(text, pre_tokenizer))
+    }
+
+    #[pyo3(text_signature = "(self, ids)")]
+    fn decode(&self, ids: Vec<u32>) -> String {
+        // Join the tokens of ids.
+        self.inner.decode(&ids)
+    }
+
+    #[pyo3(text_signature = "(self, token)")]
+    fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.inner.token_to_id(token)
+    }
+
+    #[pyo3(text_signature = "(self, id)")]
+    fn id_to_token(&self, id: u32) -> Option<&str> {
+        self.inner.id_to_token(id)
+    }
+
+    #[getter]
+    fn vocab_size(&self) -> usize {
+        self.inner.vocab_size()
+    }
+}
+
+#[pyclass(name = "Normalizer", module = "thongna", frozen)]
+struct PyNormalizer {
+    inner: Normalizer,
+}
+
+#[pymethods]
+impl PyNormalizer {
+    #[new]
+    #[pyo3(signature = (steps=None))]
+    #[pyo3(text_signature = "(steps=None)")]
+    fn new(steps: Option<Vec<String>>) -> PyResult<Self> {
+        // Create a normalizer from a set of steps.
+        //
+        // Args:
+        //     steps (List[str], optional): Names of the steps, among "nfc",
+        //         "space_numbers", "remove_zero_width", "remove_invisible_chars",
+        //         "collapse_whitespace", "reorder_marks" and "remove_duplicate_marks".
+        //         They always run in that order. Defaults to every step but "nfc",
+        //         "space_numbers" and "remove_invisible_chars".
+        let inner = match steps {
+            Some(names) => Normalizer::new(
+                &names
+                    .iter()
+                    .map(|name| name.parse::<NormalizeStep>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(exceptions::PyValueError::new_err)?,
+            ),
+            None => Normalizer::default(),
+        };
+        Ok(Self { inner })
+    }
+
+    #[pyo3(text_signature = "(self, text)")]
+    fn normalize(&self, text: &str) -> String {
+        self.inner.normalize(text)
+    }
+
+    #[pyo3(text_signature = "(self, text)")]
+    fn normalize_with_alignment(&self, text: &str) -> (String, Vec<(usize, usize)>) {
+        // Normalize text, with the range of characters of text
+        // each normalized character came from.
+        let normalized = self.inner.normalize_with_alignment(text);
+        (normalized.text, normalized.alignment)
+    }
+
+    #[pyo3(text_signature = "(self, texts)")]
+    fn normalize_batch(&self, py: Python<'_>, texts: Vec<String>) -> Vec<String> {
+        // Normalize a list of texts in parallel, with the GIL released.
+        py.allow_threads(|| self.inner.normalize_batch(&texts))
+    }
+
+    #[getter]
+    fn steps(&self) -> Vec<&'static str> {
+        self.inner.steps().iter().map(NormalizeStep::name).collect()
+    }
+
+    fn __getnewargs__(&self) -> (Vec<&'static str>,) {
+        (self.steps(),)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Normalizer(steps={:?})", self.steps())
+    }
+}
+
+#[pymodule]
+fn thongna(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(normalize, m)?)?;
+    m.add_function(wrap_pyfunction!(newmm, m)?)?;
+    m.add_function(wrap_pyfunction!(newmm_with_offsets, m)?)?;
+    m.add_function(wrap_pyfunction!(newmm_normalized_with_offsets, m)?)?;
+    m.add_function(wrap_pyfunction!(newmm_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(sentences_with_offsets, m)?)?;
+    m.add_function(wrap_pyfunction!(newmm_stream, m)?)?;
+    m.add_function(wrap_pyfunction!(newmm_lattice, m)?)?;
+    m.add_function(wrap_pyfunction!(newmm_nbest, m)?)?;
+    m.add_function(wrap_pyfunction!(tcc, m)?)?;
+    m.add_function(wrap_pyfunction!(tcc_pos, m)?)?;
+    m.add_function(wrap_pyfunction!(syllables, m)?)?;
+    m.add_function(wrap_pyfunction!(syllables_with_offsets, m)?)?;
+    m.add_function(wrap_pyfunction!(romanize, m)?)?;
+    m.add_function(wrap_pyfunction!(romanize_words, m)?)?;
+    m.add_function(wrap_pyfunction!(transliterate, m)?)?;
+    m.add_function(wrap_pyfunction!(soundex, m)?)?;
+    m.add_function(wrap_pyfunction!(soundex_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(metasound, m)?)?;
+    m.add_function(wrap_pyfunction!(load_dict, m)?)?;
+    m.add_function(wrap_pyfunction!(compile_dict, m)?)?;
+    m.add_function(wrap_pyfunction!(load_compiled_dict, m)?)?;
+    m.add_function(wrap_pyfunction!(load_dict_from_list, m)?)?;
+    m.add_function(wrap_pyfunction!(unload_dict, m)?)?;
+    m.add_function(wrap_pyfunction!(list_dicts, m)?)?;
+    m.add_function(wrap_pyfunction!(dict_word_count, m)?)?;
+    m.add_function(wrap_pyfunction!(complete, m)?)?;
+    m.add_function(wrap_pyfunction!(complete_fuzzy, m)?)?;
+    m.add_function(wrap_pyfunction!(add_words, m)?)?;
+    m.add_function(wrap_pyfunction!(remove_words, m)?)?;
+    m.add_function(wrap_pyfunction!(set_token_patterns, m)?)?;
+    m.add_function(wrap_pyfunction!(set_config, m)?)?;
+    m.add_class::<PyNormalizer>()?;
+    m.add_class::<PyNewmmConfig>()?;
+    m.add_class::<PyTokenizer>()?;
+    m.add_class::<PyTokenStream>()?;
+    m.add_class::<PySyllableTokenizer>()?;
+    m.add_class::<PyTransliterator>()?;
+    m.add_class::<PySpellChecker>()?;
+    m.add_class::<PyBpeTokenizer>()?;
+    m.add("ThongnaError", m.py().get_type_bound::<ThongnaError>())?;
+    m.add("DictionaryError", m.py().get_type_bound::<DictionaryError>())?;
+    m.add("RegexError", m.py().get_type_bound::<RegexError>())?;
+    m.add("SegmentationError", m.py().get_type_bound::<SegmentationError>())?;
+    m.add(
+        "SegmentationTimeoutError",
+        m.py().get_type_bound::<SegmentationTimeoutError>(),
+    )?;
+    Ok(())
+}
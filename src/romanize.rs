@@ -0,0 +1,232 @@
+//! Romanization of Thai in the Royal Thai General System of Transcription
+//! (RTGS), syllable by syllable.
+
+use lazy_static::lazy_static;
+use rayon::prelude::*;
+
+use crate::tokenizer::syllable::syllable_parts::{
+    is_consonant, parse_syllable, FinalSound, SyllableParts, VowelSound,
+};
+use crate::tokenizer::syllable::SyllableTokenizer;
+
+lazy_static! {
+    static ref DEFAULT_ROMANIZER: Romanizer = Romanizer::new();
+}
+
+/// RTGS of a consonant starting a syllable, empty for อ
+fn initial_rtgs(consonant: char) -> &'static str {
+    match consonant {
+        'ก' => "k",
+        'ข' | 'ฃ' | 'ค' | 'ฅ' | 'ฆ' => "kh",
+        'ง' => "ng",
+        'จ' | 'ฉ' | 'ช' | 'ฌ' => "ch",
+        'ซ' | 'ศ' | 'ษ' | 'ส' => "s",
+        'ญ' | 'ย' => "y",
+        'ฎ' | 'ด' => "d",
+        'ฏ' | 'ต' => "t",
+        'ฐ' | 'ฑ' | 'ฒ' | 'ถ' | 'ท' | 'ธ' => "th",
+        'ณ' | 'น' => "n",
+        'บ' => "b",
+        'ป' => "p",
+        'ผ' | 'พ' | 'ภ' => "ph",
+        'ฝ' | 'ฟ' => "f",
+        'ม' => "m",
+        'ร' => "r",
+        'ล' | 'ฬ' => "l",
+        'ว' => "w",
+        'ห' | 'ฮ' => "h",
+        _ => "",
+    }
+}
+
+fn vowel_rtgs(vowel: VowelSound) -> &'static str {
+    match vowel {
+        VowelSound::A | VowelSound::Aa => "a",
+        VowelSound::I | VowelSound::Ii => "i",
+        VowelSound::Ue | VowelSound::Uee => "ue",
+        VowelSound::U | VowelSound::Uu => "u",
+        VowelSound::E | VowelSound::Ee => "e",
+        VowelSound::Ae | VowelSound::Aee => "ae",
+        VowelSound::O | VowelSound::Oo | VowelSound::Or | VowelSound::Orr => "o",
+        VowelSound::Oe | VowelSound::Oee => "oe",
+        VowelSound::Ia | VowelSound::Iia => "ia",
+        VowelSound::Uea | VowelSound::Ueea => "uea",
+        VowelSound::Ua | VowelSound::Uua => "ua",
+    }
+}
+
+fn final_rtgs(final_sound: FinalSound) -> &'static str {
+    match final_sound {
+        FinalSound::K => "k",
+        FinalSound::T => "t",
+        FinalSound::P => "p",
+        FinalSound::Ng => "ng",
+        FinalSound::N => "n",
+        FinalSound::M => "m",
+        FinalSound::Y => "i",
+        FinalSound::W => "o",
+    }
+}
+
+fn syllable_rtgs(parts: &SyllableParts) -> String {
+    let initial: String = parts
+        .initial
+        .iter()
+        .map(|&consonant| initial_rtgs(consonant))
+        .collect();
+    let final_sound = parts.final_sound.map_or("", final_rtgs);
+    format!("{}{}{}", initial, vowel_rtgs(parts.vowel), final_sound)
+}
+
+/// RTGS of a syllable or other token cut by [`SyllableTokenizer`].
+/// Thai digits become Arabic digits, other non-Thai text is kept.
+fn token_rtgs(token: &str) -> String {
+    if let Some(parts) = parse_syllable(token) {
+        return syllable_rtgs(&parts);
+    }
+    token
+        .chars()
+        .filter_map(|character| match character {
+            '๐'..='๙' => char::from_digit(character as u32 - '๐' as u32, 10).map(String::from),
+            // a cluster the rules do not read: keep the sound of its consonants
+            _ if is_consonant(character) => Some(initial_rtgs(character).to_string()),
+            '\u{0E01}'..='\u{0E4F}' => None,
+            _ => Some(character.to_string()),
+        })
+        .collect()
+}
+
+/// Transcribes Thai text into Latin script with the Royal Thai General
+/// System of Transcription (RTGS), without tones or vowel length.
+///
+/// Text is cut into written syllables by a [`SyllableTokenizer`], and each
+/// syllable is read from its initial consonants, vowel form and final
+/// consonant; letters under a thanthakhat (`์`) are silent.
+/// Vowels that are not written, like the "cha" of "ราชสีมา" (ratchasima),
+/// are not restored, except the "a" of a lone consonant and the "o"
+/// between two consonants ("คน", khon).
+///
+/// ```
+/// use thongna::Romanizer;
+///
+/// let romanizer = Romanizer::new();
+/// assert_eq!(romanizer.romanize("เชียงใหม่"), "chiangmai");
+/// assert_eq!(romanizer.romanize_words(&["ขอน", "แก่น"]), vec!["khon", "kaen"]);
+/// ```
+#[derive(Debug, Default)]
+pub struct Romanizer {
+    syllable_tokenizer: SyllableTokenizer,
+}
+
+impl Romanizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Romanize with the syllables cut by `syllable_tokenizer`,
+    /// e.g. one that knows the syllables of loanwords
+    pub fn with_syllable_tokenizer(mut self, syllable_tokenizer: SyllableTokenizer) -> Self {
+        self.syllable_tokenizer = syllable_tokenizer;
+        self
+    }
+
+    /// Romanize text, syllable by syllable.
+    pub fn romanize(&self, text: &str) -> String {
+        self.syllable_tokenizer
+            .segment_with_offsets(text)
+            .iter()
+            .map(|syllable| token_rtgs(&syllable.text))
+            .collect()
+    }
+
+    /// Romanize every word of `words`, in parallel.
+    ///
+    /// Syllables are cut within each word, so romanizing the words of
+    /// [`NewmmTokenizer`](crate::NewmmTokenizer) avoids syllables straddling two words.
+    pub fn romanize_words<S: AsRef<str> + Sync>(&self, words: &[S]) -> Vec<String> {
+        words
+            .par_iter()
+            .map(|word| self.romanize(word.as_ref()))
+            .collect()
+    }
+}
+
+/// Romanize text with the default [`Romanizer`].
+pub fn romanize(text: &str) -> String {
+    DEFAULT_ROMANIZER.romanize(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NewmmTokenizer, Tokenizer};
+
+    const DICT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/dataset/words_th.txt");
+
+    #[test]
+    fn test_place_names() {
+        let tokenizer = NewmmTokenizer::new(DICT_PATH).unwrap();
+        let romanizer = Romanizer::new();
+        for (name, expected) in [
+            ("กรุงเทพ", "krungthep"),
+            ("เชียงใหม่", "chiangmai"),
+            ("เชียงราย", "chiangrai"),
+            ("ภูเก็ต", "phuket"),
+            ("ขอนแก่น", "khonkaen"),
+            ("ลำปาง", "lampang"),
+            ("สุโขทัย", "sukhothai"),
+            ("น่าน", "nan"),
+            ("แพร่", "phrae"),
+            ("ตราด", "trat"),
+            ("ระยอง", "rayong"),
+            ("ชลบุรี", "chonburi"),
+            ("สงขลา", "songkhla"),
+            ("ปัตตานี", "pattani"),
+            ("หนองคาย", "nongkhai"),
+            ("อุดรธานี", "udonthani"),
+            ("เลย", "loei"),
+            ("ยโสธร", "yasothon"),
+            ("พังงา", "phangnga"),
+            ("ลพบุรี", "lopburi"),
+            ("ปทุมธานี", "pathumthani"),
+            ("แม่ฮ่องสอน", "maehongson"),
+            ("นราธิวาส", "narathiwat"),
+            ("บึงกาฬ", "buengkan"),
+            ("สุรินทร์", "surin"),
+            ("มุกดาหาร", "mukdahan"),
+            ("ลำพูน", "lamphun"),
+            ("พะเยา", "phayao"),
+        ] {
+            let words = tokenizer.segment(name, false, false).unwrap();
+            assert_eq!(
+                romanizer.romanize_words(&words).concat(),
+                expected,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_syllables() {
+        for (syllable, expected) in [
+            ("ความ", "khwam"),
+            ("ขวด", "khuat"),
+            ("หมด", "mot"),
+            ("กรรม", "kam"),
+            ("จันทร์", "chan"),
+            ("ทราย", "sai"),
+            ("สวัสดี", "sawatdi"),
+            ("ไทย", "thai"),
+            ("เปล่า", "plao"),
+            ("เดิน", "doen"),
+            ("ด้วย", "duai"),
+            ("ก็", "ko"),
+            ("ฤๅ", "rue"),
+        ] {
+            assert_eq!(romanize(syllable), expected, "{}", syllable);
+        }
+        assert_eq!(romanize("ปี ๒๕๖๗ AD"), "pi 2567 AD");
+        assert_eq!(romanize(""), "");
+    }
+}
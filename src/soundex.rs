@@ -0,0 +1,428 @@
+//! Thai phonetic keys (soundex) for matching names spelled in different ways:
+//! LK82, Udom83 and MetaSound.
+//!
+//! Names are read as [`CustomString`]s, so the spelling rules run as regexes
+//! over the four-byte representation, like the TCC and syllable rules, and
+//! Thai and mixed-script names are cut into characters the same way.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use rayon::prelude::*;
+use regex::bytes::{Captures, Regex};
+
+use crate::bytes_str::custom_regex::regex_pattern_to_custom_pattern;
+use crate::bytes_str::custom_string::{decode_custom_char, CustomString, BYTES_PER_CHAR};
+
+/// Length of a MetaSound key by default
+pub const METASOUND_LENGTH: usize = 4;
+
+const LK82_LENGTH: usize = 5;
+const UDOM83_LENGTH: usize = 7;
+
+/// Letters silenced by a thanthakhat (`์`), with the vowel written on them
+const SILENT_LETTERS: &str = r"จน์|มณ์|ณฑ์|ทร์|ตร์|[ก-ฮ]์|[ก-ฮ][ะ-ู]์";
+
+/// A regex over custom strings and the text around its first group
+/// that replaces each match
+struct Rewrite {
+    pattern: Regex,
+    prefix: Vec<u8>,
+    suffix: Vec<u8>,
+}
+
+impl Rewrite {
+    fn new(pattern: &str, prefix: &str, suffix: &str) -> Self {
+        Self {
+            pattern: Regex::new(&regex_pattern_to_custom_pattern(pattern).unwrap()).unwrap(),
+            prefix: CustomString::new(prefix).raw_content().to_vec(),
+            suffix: CustomString::new(suffix).raw_content().to_vec(),
+        }
+    }
+
+    fn apply(&self, content: &[u8]) -> Vec<u8> {
+        self.pattern
+            .replace_all(content, |caps: &Captures| {
+                let group = caps.get(1).map_or(&[][..], |group| group.as_bytes());
+                [&self.prefix[..], group, &self.suffix[..]].concat()
+            })
+            .into_owned()
+    }
+}
+
+lazy_static! {
+    static ref LK82_REWRITES: Vec<Rewrite> = vec![
+        Rewrite::new(SILENT_LETTERS, "", ""),
+        // tone marks and signs that do not change the sound of a letter
+        Rewrite::new(r"[็-๋ํฺๆฯ]", "", ""),
+    ];
+
+    static ref UDOM83_REWRITES: Vec<Rewrite> = vec![
+        Rewrite::new(r"รร([เ-ไ])", "ัน", ""),
+        Rewrite::new(r"รร([ก-ฮ][ก-ฮเ-ไ])", "ั", ""),
+        Rewrite::new(r"รร([ก-ฮ][ะ-ู่-์])", "ัน", ""),
+        Rewrite::new(r"รร", "ัน", ""),
+        Rewrite::new(r"ไ([ก-ฮ]ย)", "", ""),
+        Rewrite::new(r"[ไใ]([ก-ฮ])", "", "ย"),
+        Rewrite::new(r"ำ(ม[ะ-ู])", "ม", ""),
+        Rewrite::new(r"ำม", "ม", ""),
+        Rewrite::new(r"ำ", "ม", ""),
+        Rewrite::new(SILENT_LETTERS, "", ""),
+        // every vowel and sign, leaving the consonants
+        Rewrite::new(r"[ะ-์]", "", ""),
+    ];
+
+    static ref METASOUND_REWRITES: Vec<Rewrite> = vec![
+        Rewrite::new(SILENT_LETTERS, "", ""),
+        Rewrite::new(r"[ะ-๛]", "", ""),
+    ];
+}
+
+/// Phonetic key algorithms
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SoundexAlgorithm {
+    /// Lorchirachoonkul (1982): the first letter, then up to four codes
+    /// of consonants and vowels
+    Lk82,
+    /// Udompanich (1983): the first consonant, then six codes of consonants
+    Udom83,
+    /// After MetaSound, Snae and Brückner (2009): the first consonant, then
+    /// codes of consonants by their sound as a final. Silent letters are
+    /// dropped with the same rule as for the other algorithms.
+    Metasound,
+}
+
+impl SoundexAlgorithm {
+    pub const ALL: [SoundexAlgorithm; 3] = [
+        SoundexAlgorithm::Lk82,
+        SoundexAlgorithm::Udom83,
+        SoundexAlgorithm::Metasound,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SoundexAlgorithm::Lk82 => "lk82",
+            SoundexAlgorithm::Udom83 => "udom83",
+            SoundexAlgorithm::Metasound => "metasound",
+        }
+    }
+
+    fn encode_word(&self, word: &CustomString) -> String {
+        match self {
+            SoundexAlgorithm::Lk82 => lk82_word(word),
+            SoundexAlgorithm::Udom83 => udom83_word(word),
+            SoundexAlgorithm::Metasound => metasound_word(word, METASOUND_LENGTH),
+        }
+    }
+}
+
+impl Display for SoundexAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for SoundexAlgorithm {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        SoundexAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown soundex algorithm {:?}, expected one of {}",
+                    name,
+                    SoundexAlgorithm::ALL
+                        .map(|algorithm| algorithm.name())
+                        .join(", ")
+                )
+            })
+    }
+}
+
+/// Characters of `word` once every rewrite has been applied in order.
+/// Letters of other scripts are lowercased.
+fn rewrite_chars(word: &CustomString, rewrites: &[Rewrite]) -> Vec<char> {
+    let content = rewrites
+        .iter()
+        .fold(word.raw_content().to_vec(), |content, rewrite| {
+            rewrite.apply(&content)
+        });
+    content
+        .chunks(BYTES_PER_CHAR)
+        .filter_map(decode_custom_char)
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn pad_key(mut key: String, length: usize) -> String {
+    let padding = length.saturating_sub(key.chars().count());
+    key.extend(std::iter::repeat_n('0', padding));
+    key.chars().take(length).collect()
+}
+
+fn is_consonant(character: char) -> bool {
+    ('ก'..='ฮ').contains(&character)
+}
+
+fn lk82_first(character: char) -> char {
+    match character {
+        'ข' | 'ฃ' | 'ค' | 'ฅ' | 'ฆ' => 'ก',
+        'ฉ' | 'ฌ' => 'ช',
+        'ศ' | 'ษ' | 'ส' => 'ซ',
+        'ญ' => 'ย',
+        'ฎ' => 'ด',
+        'ฏ' => 'ต',
+        'ฐ' | 'ฑ' | 'ฒ' | 'ถ' | 'ธ' => 'ท',
+        'ณ' => 'น',
+        'ผ' | 'ภ' => 'พ',
+        'ฝ' => 'ฟ',
+        'ล' | 'ฬ' | 'ฤ' | 'ฦ' => 'ร',
+        'ฮ' => 'ห',
+        _ => character,
+    }
+}
+
+fn lk82_code(character: char) -> char {
+    match character {
+        'ก' | 'ข' | 'ฃ' | 'ค' | 'ฅ' | 'ฆ' => '1',
+        'ง' => '2',
+        'จ' | 'ฉ' | 'ช' | 'ซ' | 'ฌ' | 'ฎ' | 'ฏ' | 'ฐ' | 'ฑ' | 'ฒ' | 'ด' | 'ต' | 'ถ' | 'ท' | 'ธ'
+        | 'ศ' | 'ษ' | 'ส' => '3',
+        'ญ' | 'ณ' | 'น' | 'ร' | 'ล' | 'ฬ' | 'ฤ' | 'ฦ' => '4',
+        'บ' | 'ป' | 'พ' | 'ฟ' | 'ภ' | 'ผ' | 'ฝ' => '5',
+        'ม' | 'ำ' => '6',
+        'ย' | 'ว' | 'ไ' | 'ใ' => '7',
+        'ห' | 'ฮ' => '8',
+        'า' => '9',
+        'ๅ' | 'ึ' | 'ื' => 'A',
+        'เ' => 'B',
+        'แ' => 'C',
+        'โ' => 'D',
+        'ุ' | 'ู' => 'E',
+        'อ' => 'F',
+        _ => character,
+    }
+}
+
+fn lk82_word(word: &CustomString) -> String {
+    let chars = rewrite_chars(word, &LK82_REWRITES);
+    let mut codes = vec![];
+    let rest = match chars[..] {
+        [] => return String::new(),
+        // a leading vowel comes after the consonant it is read with
+        [vowel @ ('เ' | 'แ' | 'โ' | 'ใ' | 'ไ'), consonant, ref rest @ ..] => {
+            codes.push(lk82_first(consonant));
+            codes.push(lk82_code(vowel));
+            rest
+        }
+        [first, ref rest @ ..] => {
+            codes.push(lk82_first(first));
+            rest
+        }
+    };
+
+    let mut last_vowel = None;
+    for (index, &character) in rest.iter().enumerate() {
+        let before_vowel = rest.get(index + 1).is_some_and(|next| "ึืุู".contains(*next));
+        match character {
+            // short vowels
+            'ะ' | 'ั' | 'ิ' | 'ี' => last_vowel = Some(index),
+            'า' | 'ึ' | 'ื' | 'ู' | 'ๅ' | 'ุ' => {
+                last_vowel = Some(index);
+                codes.push(lk82_code(character));
+            }
+            // a leading ห or อ is silent
+            'ห' | 'อ' if !before_vowel => {}
+            // a ย, ร or ว is read only after a vowel or before one,
+            // not as the second consonant of a cluster
+            'ย' | 'ร' | 'ฤ' | 'ฦ' | 'ว'
+                if !before_vowel && last_vowel.is_none_or(|vowel| vowel + 1 != index) => {}
+            _ => codes.push(lk82_code(character)),
+        }
+    }
+    codes.dedup();
+    pad_key(codes.into_iter().collect(), LK82_LENGTH)
+}
+
+fn udom83_first(character: char) -> char {
+    match character {
+        'ฃ' | 'ค' | 'ฅ' | 'ฆ' => 'ข',
+        'ฉ' | 'ฌ' => 'ช',
+        'ซ' | 'ศ' | 'ษ' => 'ส',
+        'ฎ' => 'ด',
+        'ฏ' => 'ต',
+        'ฐ' | 'ฑ' | 'ฒ' | 'ถ' | 'ธ' => 'ท',
+        'ณ' => 'น',
+        'ผ' | 'ภ' => 'พ',
+        'ฝ' => 'ฟ',
+        'ญ' => 'ย',
+        'ล' | 'ฬ' => 'ร',
+        'ห' => 'ฮ',
+        _ => character,
+    }
+}
+
+fn udom83_code(character: char) -> char {
+    match character {
+        'ม' | 'ว' | 'ำ' => '0',
+        'ก' | 'ข' | 'ฃ' | 'ค' | 'ฅ' | 'ฆ' => '1',
+        'ง' | 'ย' => '2',
+        'ญ' | 'ณ' | 'น' => '3',
+        'ฎ' | 'ฏ' | 'ด' | 'ต' | 'ศ' | 'ษ' | 'ส' => '4',
+        'บ' | 'ป' | 'พ' | 'ภ' => '5',
+        'ผ' | 'ฝ' | 'ฟ' | 'ห' | 'อ' | 'ฮ' => '6',
+        'จ' | 'ฉ' | 'ช' | 'ซ' | 'ฌ' => '7',
+        'ฐ' | 'ฑ' | 'ฒ' | 'ถ' | 'ท' | 'ธ' => '8',
+        'ร' | 'ฤ' | 'ล' | 'ฦ' => '9',
+        _ => character,
+    }
+}
+
+fn udom83_word(word: &CustomString) -> String {
+    let chars = rewrite_chars(word, &UDOM83_REWRITES);
+    let Some((&first, rest)) = chars.split_first() else {
+        return String::new();
+    };
+    let key = std::iter::once(udom83_first(first))
+        .chain(rest.iter().map(|&character| udom83_code(character)))
+        .collect();
+    pad_key(key, UDOM83_LENGTH)
+}
+
+/// Code of a consonant by its sound at the end of a syllable
+fn metasound_code(character: char) -> char {
+    match character {
+        'ก' | 'ข' | 'ฃ' | 'ค' | 'ฆ' | 'ฅ' => '1',
+        'จ' | 'ฉ' | 'ช' | 'ฌ' | 'ซ' | 'ฐ' | 'ท' | 'ฒ' | 'ด' | 'ฎ' | 'ต' | 'ส' | 'ศ' | 'ษ' => {
+            '2'
+        }
+        'ฟ' | 'ฝ' | 'พ' | 'ผ' | 'ภ' | 'บ' | 'ป' => '3',
+        'ง' => '4',
+        'ล' | 'ฬ' | 'ร' | 'น' | 'ณ' | 'ฦ' | 'ญ' => '5',
+        'ม' => '6',
+        'ย' => '7',
+        'ว' => '8',
+        _ if is_consonant(character) => '0',
+        _ => character,
+    }
+}
+
+fn metasound_word(word: &CustomString, length: usize) -> String {
+    let chars = rewrite_chars(word, &METASOUND_REWRITES);
+    let Some((&first, rest)) = chars.split_first() else {
+        return String::new();
+    };
+    let key = std::iter::once(first)
+        .chain(rest.iter().map(|&character| metasound_code(character)))
+        .collect();
+    pad_key(key, length)
+}
+
+/// Key of every whitespace-separated part of `text`, joined by a space,
+/// so that the first and last names of a full name are keyed separately
+fn encode_parts(text: &str, encode_word: impl Fn(&CustomString) -> String) -> String {
+    text.split_whitespace()
+        .map(|word| encode_word(&CustomString::new(word)))
+        .filter(|key| !key.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// LK82 key of `text`: its first consonant, then four codes.
+///
+/// ```
+/// use thongna::soundex::lk82;
+///
+/// assert_eq!(lk82("รักษ์"), lk82("ลัก"));
+/// ```
+pub fn lk82(text: &str) -> String {
+    encode_parts(text, lk82_word)
+}
+
+/// Udom83 key of `text`: its first consonant, then six codes.
+pub fn udom83(text: &str) -> String {
+    encode_parts(text, udom83_word)
+}
+
+/// MetaSound key of `text`, `length` characters long:
+/// its first consonant, then codes of the other consonants.
+pub fn metasound(text: &str, length: usize) -> String {
+    encode_parts(text, |word| metasound_word(word, length))
+}
+
+/// Key of `text` with `algorithm`, MetaSound keys being
+/// [`METASOUND_LENGTH`] characters long.
+///
+/// Every whitespace-separated part of the text has its own key, and keys
+/// are joined by a space. Letters of other scripts are lowercased and
+/// kept, so names in Thai and Latin script can be keyed together.
+pub fn soundex(text: &str, algorithm: SoundexAlgorithm) -> String {
+    encode_parts(text, |word| algorithm.encode_word(word))
+}
+
+/// Key of every text of `texts` with `algorithm`, in parallel.
+pub fn soundex_batch<S: AsRef<str> + Sync>(
+    texts: &[S],
+    algorithm: SoundexAlgorithm,
+) -> Vec<String> {
+    texts
+        .par_iter()
+        .map(|text| soundex(text.as_ref(), algorithm))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_sound_same_key() {
+        for algorithm in SoundexAlgorithm::ALL {
+            for names in [
+                &["รัก", "รั้ก", "รักษ์"][..],
+                &["จันทร์", "จันท์", "จัน"],
+                &["เพชร", "เพ็ชร"],
+                &["พิศมัย", "พิสมัย"],
+            ] {
+                let keys = soundex_batch(names, algorithm);
+                assert!(
+                    keys.iter().all(|key| key == &keys[0]),
+                    "{} {:?}",
+                    algorithm,
+                    keys
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_keys() {
+        assert_eq!(lk82("รัก"), "ร1000");
+        assert_eq!(lk82("ลัก"), lk82("รัก"));
+        assert_eq!(lk82("ใจดี"), "จ7300");
+        assert_eq!(lk82("บุณย์"), lk82("บูรณะ"));
+        assert_eq!(udom83("รัก"), "ร100000");
+        assert_eq!(udom83("สมชาย"), "ส072000");
+        assert_eq!(udom83("ซมชาย"), udom83("สมชาย"));
+        assert_eq!(udom83("กรรม"), "ก300000");
+        assert_eq!(metasound("รัก", 4), "ร100");
+        assert_eq!(metasound("บูรณะ", 6), "บ55000");
+        assert_eq!(
+            soundex("สมชาย  ใจดี", SoundexAlgorithm::Udom83),
+            "ส072000 จ240000"
+        );
+        assert_eq!(
+            soundex("Somchai", SoundexAlgorithm::Metasound),
+            soundex("SOMCHAI", SoundexAlgorithm::Metasound)
+        );
+        assert_eq!(soundex(" ", SoundexAlgorithm::Lk82), "");
+        assert_eq!(
+            "udom83".parse::<SoundexAlgorithm>(),
+            Ok(SoundexAlgorithm::Udom83)
+        );
+        assert!("nysiis".parse::<SoundexAlgorithm>().is_err());
+    }
+}
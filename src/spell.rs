@@ -0,0 +1,315 @@
+//! Spelling suggestions and correction from the words of a newmm dictionary.
+
+use crate::bytes_str::custom_string::CustomString;
+use crate::error::ThongnaResult;
+use crate::tokenizer::completion::{EditCosts, EditQuery};
+use crate::tokenizer::newmm::NewmmTokenizer;
+use crate::tokenizer::tokenizer_trait::Tokenizer;
+
+/// Largest number of edits between a word and its suggestions by default
+pub const DEFAULT_MAX_DISTANCE: usize = 2;
+
+/// Costs in half edits, so that confusions can cost half an edit
+/// while distances stay integers
+const EDIT_COST: u32 = 2;
+const CONFUSION_COST: u32 = 1;
+
+/// Number of tokens before and after the unknown tokens of a text
+/// that a correction may take in
+const CONTEXT_TOKENS: usize = 2;
+
+const TONE_MARKS: &str = "่้๊๋";
+/// Vowels typed for one another, mostly short and long forms
+const VOWEL_CONFUSIONS: [&str; 6] = ["ะัา", "ิี", "ึื", "ุู", "เแ", "ใไ"];
+
+fn is_mark(character: char) -> bool {
+    TONE_MARKS.contains(character) || matches!(character, '็' | '์')
+}
+
+/// Costs of the typing errors of Thai, in half edits
+struct TypingCosts;
+
+impl EditCosts for TypingCosts {
+    fn substitution(&self, typed: char, expected: char) -> u32 {
+        if typed == expected {
+            0
+        } else if (TONE_MARKS.contains(typed) && TONE_MARKS.contains(expected))
+            || VOWEL_CONFUSIONS
+                .iter()
+                .any(|group| group.contains(typed) && group.contains(expected))
+        {
+            CONFUSION_COST
+        } else {
+            EDIT_COST
+        }
+    }
+
+    fn insertion(&self, character: char) -> u32 {
+        if is_mark(character) {
+            CONFUSION_COST
+        } else {
+            EDIT_COST
+        }
+    }
+
+    /// A tone mark typed before the vowel written above the same consonant
+    /// looks the same
+    fn swap(&self, first: char, second: char) -> u32 {
+        if is_mark(first) || is_mark(second) {
+            CONFUSION_COST
+        } else {
+            EDIT_COST
+        }
+    }
+}
+
+/// A word of the dictionary close to a misspelled word.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub word: String,
+    /// Weighted number of edits from the misspelled word: insertions,
+    /// deletions, substitutions and swaps of adjacent characters cost 1,
+    /// and tone mark or vowel confusions cost 0.5
+    pub distance: f64,
+    /// Frequency of the word in the dictionary, 0 if it has none
+    pub frequency: u64,
+}
+
+/// Suggests and corrects the spelling of Thai words from a dictionary.
+///
+/// Words are searched in the tokenizer's dictionary within a bounded
+/// Damerau-Levenshtein distance, pruning every branch of the dictionary
+/// that is already too far from the word, so that words added to or
+/// removed from the tokenizer are suggested or not. Suggestions are ranked by
+/// distance, then by frequency when the dictionary has frequencies.
+///
+/// ```
+/// use thongna::{NewmmTokenizer, SpellChecker};
+///
+/// let tokenizer = NewmmTokenizer::from_weighted_word_list(vec![
+///     ("ไข่".to_string(), 10),
+///     ("ไก่".to_string(), 50),
+///     ("คน".to_string(), 5),
+/// ]);
+/// let spell_checker = SpellChecker::new(tokenizer);
+/// assert_eq!(spell_checker.correct("ไก"), "ไก่");
+/// assert_eq!(spell_checker.correct_text("ไข่ คร").unwrap(), "ไข่ คน");
+/// ```
+#[derive(Debug)]
+pub struct SpellChecker {
+    tokenizer: NewmmTokenizer,
+    max_distance: usize,
+}
+
+impl SpellChecker {
+    /// Creates a spell checker from the words of `tokenizer`'s dictionary,
+    /// which also segments text for [`correct_text`](Self::correct_text).
+    pub fn new(tokenizer: NewmmTokenizer) -> Self {
+        Self {
+            tokenizer,
+            max_distance: DEFAULT_MAX_DISTANCE,
+        }
+    }
+
+    /// Suggest words at most `max_distance` edits away
+    pub fn with_max_distance(mut self, max_distance: usize) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    pub fn max_distance(&self) -> usize {
+        self.max_distance
+    }
+
+    pub fn tokenizer(&self) -> &NewmmTokenizer {
+        &self.tokenizer
+    }
+
+    /// Whether `word` is in the dictionary.
+    pub fn contains(&self, word: &str) -> bool {
+        self.tokenizer.dictionary().contain(&CustomString::new(word))
+    }
+
+    /// Dictionary words within the maximum distance of `word`, the closest
+    /// and most frequent first. A word of the dictionary is its own first
+    /// suggestion.
+    pub fn suggest(&self, word: &str) -> Vec<Suggestion> {
+        if word.is_empty() {
+            return vec![];
+        }
+        let query = EditQuery::words(word, &TypingCosts, self.max_distance as u32 * EDIT_COST);
+        let mut suggestions: Vec<Suggestion> = self
+            .tokenizer
+            .dictionary()
+            .fuzzy_search(&query)
+            .into_iter()
+            .map(|found| Suggestion {
+                word: found.word,
+                distance: found.cost as f64 / EDIT_COST as f64,
+                frequency: found.frequency,
+            })
+            .collect();
+        suggestions.sort_by(|a, b| {
+            a.distance
+                .total_cmp(&b.distance)
+                .then(b.frequency.cmp(&a.frequency))
+        });
+        suggestions
+    }
+
+    /// The best suggestion for `word`, or `word` itself if it is in the
+    /// dictionary or has no suggestion.
+    pub fn correct(&self, word: &str) -> String {
+        self.suggest(word)
+            .into_iter()
+            .next()
+            .map_or_else(|| word.to_string(), |suggestion| suggestion.word)
+    }
+
+    /// Segments `text` with newmm and corrects the Thai tokens that are not
+    /// dictionary words, keeping the other tokens.
+    ///
+    /// A misspelled word is often cut into several tokens, some of which
+    /// can be words, so unknown tokens at most one token apart are corrected
+    /// together, along with up to two Thai tokens before or after them when
+    /// that gives a closer word. Misspellings cut only into dictionary words
+    /// are left as they are.
+    pub fn correct_text(&self, text: &str) -> ThongnaResult<String> {
+        let tokens = self.tokenizer.segment(text, false, false)?;
+        let is_thai: Vec<bool> = tokens
+            .iter()
+            .map(|token| {
+                token
+                    .chars()
+                    .any(|character| ('ก'..='๛').contains(&character))
+            })
+            .collect();
+        let is_unknown: Vec<bool> = tokens
+            .iter()
+            .zip(&is_thai)
+            .map(|(token, &is_thai)| is_thai && !self.contains(token))
+            .collect();
+
+        let mut corrected: Vec<String> = Vec::with_capacity(tokens.len());
+        // number of Thai tokens at the end of `corrected`, as written,
+        // which the next correction may take in
+        let mut free = 0;
+        let mut index = 0;
+        while index < tokens.len() {
+            if !is_unknown[index] {
+                corrected.push(tokens[index].clone());
+                free = if is_thai[index] { free + 1 } else { 0 };
+                index += 1;
+                continue;
+            }
+            let mut end = index + 1;
+            while end < tokens.len()
+                && (is_unknown[end]
+                    || (is_thai[end] && end + 1 < tokens.len() && is_unknown[end + 1]))
+            {
+                end += 1;
+            }
+            let starts = (index - free.min(CONTEXT_TOKENS))..=index;
+            let following = tokens[end..]
+                .iter()
+                .zip(&is_thai[end..])
+                .take(CONTEXT_TOKENS)
+                .take_while(|(_, &is_thai)| is_thai)
+                .count();
+            let ends = end..=end + following;
+            let best = starts
+                .flat_map(|start| ends.clone().map(move |end| (start, end)))
+                .filter_map(|(start, end)| {
+                    let suggestion = self
+                        .suggest(&tokens[start..end].concat())
+                        .into_iter()
+                        .next()?;
+                    Some((start, end, suggestion))
+                })
+                // the closest word, then the one taking in the most tokens
+                .min_by(|(start_a, end_a, a), (start_b, end_b, b)| {
+                    a.distance
+                        .total_cmp(&b.distance)
+                        .then((end_b - start_b).cmp(&(end_a - start_a)))
+                });
+            match best {
+                Some((start, end, suggestion)) => {
+                    corrected.truncate(corrected.len() - (index - start));
+                    corrected.push(suggestion.word);
+                    index = end;
+                }
+                None => {
+                    corrected.extend(tokens[index..end].iter().cloned());
+                    index = end;
+                }
+            }
+            free = 0;
+        }
+        Ok(corrected.concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DICT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/dataset/words_th.txt");
+
+    fn spell_checker() -> SpellChecker {
+        SpellChecker::new(NewmmTokenizer::from_weighted_word_list(vec![
+            ("ไก่".to_string(), 50),
+            ("ไข่".to_string(), 10),
+            ("ไข้".to_string(), 20),
+            ("เก่ง".to_string(), 5),
+            ("แก่ง".to_string(), 1),
+            ("สวัสดี".to_string(), 0),
+        ]))
+    }
+
+    #[test]
+    fn test_suggest() {
+        let spell_checker = spell_checker();
+        let suggestions = spell_checker.suggest("ไข");
+        let words: Vec<&str> = suggestions.iter().map(|s| s.word.as_str()).collect();
+        // a missing tone mark costs half an edit, the most frequent word first
+        assert_eq!(words[..2], ["ไข้", "ไข่"]);
+        assert_eq!(suggestions[0].distance, 0.5);
+        assert_eq!(suggestions[0].frequency, 20);
+        assert_eq!(words[2], "ไก่");
+        assert_eq!(suggestions[2].distance, 1.5);
+
+        assert_eq!(spell_checker.suggest("ไข่")[0].distance, 0.0);
+        // a swapped tone mark and vowel, a confused vowel
+        assert_eq!(spell_checker.suggest("เก่ง")[0].word, "เก่ง");
+        assert_eq!(spell_checker.suggest("แก่ง")[0].word, "แก่ง");
+        assert_eq!(spell_checker.suggest("แก่ง")[1].distance, 0.5);
+        assert_eq!(spell_checker.suggest("สวสัดี")[0].distance, 1.0);
+        assert!(spell_checker.with_max_distance(0).suggest("ไข").is_empty());
+    }
+
+    #[test]
+    fn test_correct() {
+        let spell_checker = spell_checker();
+        assert_eq!(spell_checker.correct("ไก"), "ไก่");
+        assert_eq!(spell_checker.correct("สวสดี"), "สวัสดี");
+        assert_eq!(spell_checker.correct("ไข่"), "ไข่");
+        assert_eq!(spell_checker.correct("คอมพิวเตอร์"), "คอมพิวเตอร์");
+        assert_eq!(spell_checker.correct(""), "");
+    }
+
+    #[test]
+    fn test_correct_text() {
+        let spell_checker = SpellChecker::new(NewmmTokenizer::new(DICT_PATH).unwrap());
+        for (text, expected) in [
+            ("ฉันไปโรงพยาบานเมื่อวาน", "ฉันไปโรงพยาบาลเมื่อวาน"),
+            // "ประเทษ" is cut "ประ|เท|ษ", only "ษ" is unknown
+            ("ประเทษไทย 2567", "ประเทศไทย 2567"),
+            ("มหาวิทยาลัยเกษตศาสตร์", "มหาวิทยาลัยเกษตรศาสตร์"),
+            ("คอมพิวเตอ", "คอมพิวเตอร์"),
+            ("ภาษาไทยง่ายนิดเดียว", "ภาษาไทยง่ายนิดเดียว"),
+            ("", ""),
+        ] {
+            assert_eq!(spell_checker.correct_text(text).unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,524 @@
+//! Byte-pair encoding (BPE) subword tokenization over Thai character clusters.
+//!
+//! Text is first split into pre-tokens, newmm words or runs of whitespace
+//! and non-whitespace, and every pre-token into character clusters (TCC).
+//! Clusters are the initial symbols, and merges only join neighbouring
+//! symbols of a pre-token, so no subword splits a cluster or spans two words.
+
+use super::newmm::NewmmTokenizer;
+use super::tcc::TccTokenizer;
+use super::tokenizer_trait::Tokenizer;
+use crate::error::{ThongnaError, ThongnaResult};
+
+use rustc_hash::FxHashMap as HashMap;
+use rustc_hash::FxHashSet as HashSet;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Token of the symbols not seen in training, with id 0
+pub const UNKNOWN_TOKEN: &str = "[UNK]";
+
+/// First line of a vocabulary file
+const VOCABULARY_HEADER: &str = "#thongna-bpe 1";
+
+type SymbolId = u32;
+
+/// How text is split into pre-tokens, which subwords never cross.
+#[derive(Clone, Copy)]
+pub enum PreTokenizer<'w> {
+    /// Words of a newmm tokenizer
+    Newmm(&'w NewmmTokenizer),
+    /// Runs of whitespace and runs of other characters
+    Whitespace,
+}
+
+impl PreTokenizer<'_> {
+    pub fn pre_tokenize(&self, text: &str) -> ThongnaResult<Vec<String>> {
+        match self {
+            PreTokenizer::Newmm(word_tokenizer) => word_tokenizer.segment(text, false, false),
+            PreTokenizer::Whitespace => Ok(whitespace_runs(text)),
+        }
+    }
+}
+
+fn whitespace_runs(text: &str) -> Vec<String> {
+    let mut runs: Vec<String> = vec![];
+    let mut previous_is_whitespace = None;
+    for character in text.chars() {
+        let is_whitespace = character.is_whitespace();
+        match runs.last_mut() {
+            Some(run) if previous_is_whitespace == Some(is_whitespace) => run.push(character),
+            _ => runs.push(character.to_string()),
+        }
+        previous_is_whitespace = Some(is_whitespace);
+    }
+    runs
+}
+
+/// Byte ranges of the character clusters of a pre-token
+fn cluster_ranges(pre_token: &str) -> Vec<(usize, usize)> {
+    let byte_offsets: Vec<usize> = pre_token
+        .char_indices()
+        .map(|(byte_index, _)| byte_index)
+        .chain(std::iter::once(pre_token.len()))
+        .collect();
+    let mut begin = 0;
+    TccTokenizer::new()
+        .end_positions(pre_token)
+        .into_iter()
+        .map(|end| {
+            let range = (byte_offsets[begin], byte_offsets[end]);
+            begin = end;
+            range
+        })
+        .collect()
+}
+
+/// Escapes the characters that separate the fields and lines of a vocabulary file
+fn escape_symbol(symbol: &str) -> String {
+    let mut escaped = String::with_capacity(symbol.len());
+    for character in symbol.chars() {
+        match character {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn unescape_symbol(escaped: &str) -> Option<String> {
+    let mut symbol = String::with_capacity(escaped.len());
+    let mut characters = escaped.chars();
+    while let Some(character) = characters.next() {
+        if character == '\\' {
+            symbol.push(match characters.next()? {
+                '\\' => '\\',
+                't' => '\t',
+                'n' => '\n',
+                'r' => '\r',
+                _ => return None,
+            });
+        } else {
+            symbol.push(character);
+        }
+    }
+    Some(symbol)
+}
+
+/// Replaces every `left`, `right` pair of `word` by `merged`.
+fn merge_word(word: &mut Vec<SymbolId>, left: SymbolId, right: SymbolId, merged: SymbolId) {
+    let mut index = 0;
+    let mut merged_word = Vec::with_capacity(word.len());
+    while index < word.len() {
+        if index + 1 < word.len() && word[index] == left && word[index + 1] == right {
+            merged_word.push(merged);
+            index += 2;
+        } else {
+            merged_word.push(word[index]);
+            index += 1;
+        }
+    }
+    *word = merged_word;
+}
+
+/// A BPE subword tokenizer whose initial symbols are character clusters.
+///
+/// ```no_run
+/// use thongna::tokenizer::bpe::{BpeTokenizer, PreTokenizer};
+/// use thongna::NewmmTokenizer;
+///
+/// let words = NewmmTokenizer::new("dataset/words_th.txt")?;
+/// let pre_tokenizer = PreTokenizer::Newmm(&words);
+/// let corpus = ["ภาษาไทยง่ายนิดเดียว", "ภาษาไทยเป็นภาษาที่สวยงาม"];
+/// let bpe = BpeTokenizer::train(&corpus, &pre_tokenizer, 1000, 2)?;
+/// bpe.save("thai_bpe.txt")?;
+/// let ids = BpeTokenizer::load("thai_bpe.txt")?.encode_ids("ภาษาไทย", &pre_tokenizer)?;
+/// # Ok::<(), thongna::ThongnaError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct BpeTokenizer {
+    /// Token of every id, starting with the unknown token and the clusters
+    vocabulary: Vec<String>,
+    /// Number of initial symbols, not counting the unknown token
+    alphabet_size: usize,
+    token_ids: HashMap<String, SymbolId>,
+    /// Merges in the order they were learned
+    merges: Vec<(SymbolId, SymbolId)>,
+    /// Rank and resulting id of every merge
+    merge_ranks: HashMap<(SymbolId, SymbolId), (usize, SymbolId)>,
+}
+
+impl BpeTokenizer {
+    /// Builds a tokenizer from its initial symbols and merges.
+    /// Returns the index of the first merge whose symbols are unknown, if any.
+    fn from_parts(alphabet: Vec<String>, merges: &[(String, String)]) -> Result<Self, usize> {
+        let alphabet_size = alphabet.len();
+        let mut tokenizer = Self {
+            vocabulary: vec![],
+            alphabet_size,
+            token_ids: HashMap::default(),
+            merges: Vec::with_capacity(merges.len()),
+            merge_ranks: HashMap::default(),
+        };
+        tokenizer.add_token(UNKNOWN_TOKEN.to_string());
+        for symbol in alphabet {
+            tokenizer.add_token(symbol);
+        }
+        for (index, (left, right)) in merges.iter().enumerate() {
+            let (Some(&left_id), Some(&right_id)) = (
+                tokenizer.token_ids.get(left),
+                tokenizer.token_ids.get(right),
+            ) else {
+                return Err(index);
+            };
+            tokenizer.add_merge(left_id, right_id);
+        }
+        Ok(tokenizer)
+    }
+
+    /// Returns the id of `token`, adding it to the vocabulary if needed
+    fn add_token(&mut self, token: String) -> SymbolId {
+        if let Some(&id) = self.token_ids.get(&token) {
+            return id;
+        }
+        let id = self.vocabulary.len() as SymbolId;
+        self.token_ids.insert(token.clone(), id);
+        self.vocabulary.push(token);
+        id
+    }
+
+    fn add_merge(&mut self, left: SymbolId, right: SymbolId) -> SymbolId {
+        let merged = self.add_token(format!(
+            "{}{}",
+            self.vocabulary[left as usize], self.vocabulary[right as usize]
+        ));
+        self.merge_ranks
+            .entry((left, right))
+            .or_insert((self.merges.len(), merged));
+        self.merges.push((left, right));
+        merged
+    }
+
+    /// Learns merges from the pre-tokens of `texts` until the vocabulary,
+    /// unknown token and clusters included, has `vocab_size` tokens or no pair
+    /// of symbols occurs at least `min_frequency` times.
+    ///
+    /// The most frequent pair is merged first; ties go to the pair of
+    /// earliest symbols, so training is deterministic.
+    pub fn train<S: AsRef<str>>(
+        texts: &[S],
+        pre_tokenizer: &PreTokenizer,
+        vocab_size: usize,
+        min_frequency: u64,
+    ) -> ThongnaResult<Self> {
+        let mut pre_token_counts: HashMap<String, u64> = HashMap::default();
+        for text in texts {
+            for pre_token in pre_tokenizer.pre_tokenize(text.as_ref())? {
+                *pre_token_counts.entry(pre_token).or_insert(0) += 1;
+            }
+        }
+        let mut pre_tokens: Vec<(String, u64)> = pre_token_counts.into_iter().collect();
+        pre_tokens.sort_unstable();
+
+        let mut alphabet: Vec<String> = pre_tokens
+            .iter()
+            .flat_map(|(pre_token, _)| {
+                cluster_ranges(pre_token)
+                    .into_iter()
+                    .map(|(start, end)| pre_token[start..end].to_string())
+            })
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        alphabet.sort_unstable();
+        let mut tokenizer = Self::from_parts(alphabet, &[]).unwrap_or_else(|_| unreachable!());
+
+        let mut words: Vec<(Vec<SymbolId>, u64)> = pre_tokens
+            .iter()
+            .map(|(pre_token, count)| {
+                let symbols = cluster_ranges(pre_token)
+                    .into_iter()
+                    .map(|(start, end)| tokenizer.token_ids[&pre_token[start..end]])
+                    .collect();
+                (symbols, *count)
+            })
+            .collect();
+
+        let mut pair_counts: HashMap<(SymbolId, SymbolId), u64> = HashMap::default();
+        let mut pair_words: HashMap<(SymbolId, SymbolId), HashSet<usize>> = HashMap::default();
+        for (word_index, (symbols, count)) in words.iter().enumerate() {
+            for pair in symbols.windows(2) {
+                let pair = (pair[0], pair[1]);
+                *pair_counts.entry(pair).or_insert(0) += count;
+                pair_words.entry(pair).or_default().insert(word_index);
+            }
+        }
+        // Entries whose count is out of date are skipped when popped
+        let mut queue: BinaryHeap<(u64, Reverse<(SymbolId, SymbolId)>)> = pair_counts
+            .iter()
+            .map(|(&pair, &count)| (count, Reverse(pair)))
+            .collect();
+
+        while tokenizer.vocabulary.len() < vocab_size {
+            let Some((count, Reverse(pair))) = queue.pop() else {
+                break;
+            };
+            if pair_counts.get(&pair) != Some(&count) {
+                continue;
+            }
+            if count < min_frequency {
+                break;
+            }
+            let (left, right) = pair;
+            let merged = tokenizer.add_merge(left, right);
+
+            let mut changed_pairs = HashSet::default();
+            for word_index in pair_words.remove(&pair).unwrap_or_default() {
+                let (symbols, word_count) = &mut words[word_index];
+                for old_pair in symbols.windows(2) {
+                    let old_pair = (old_pair[0], old_pair[1]);
+                    *pair_counts.get_mut(&old_pair).unwrap() -= *word_count;
+                    changed_pairs.insert(old_pair);
+                }
+                merge_word(symbols, left, right, merged);
+                for new_pair in symbols.windows(2) {
+                    let new_pair = (new_pair[0], new_pair[1]);
+                    *pair_counts.entry(new_pair).or_insert(0) += *word_count;
+                    pair_words.entry(new_pair).or_default().insert(word_index);
+                    changed_pairs.insert(new_pair);
+                }
+            }
+            for changed_pair in changed_pairs {
+                match pair_counts[&changed_pair] {
+                    0 => {
+                        pair_counts.remove(&changed_pair);
+                    }
+                    count => queue.push((count, Reverse(changed_pair))),
+                }
+            }
+        }
+        Ok(tokenizer)
+    }
+
+    /// Number of tokens, the unknown token included
+    pub fn vocab_size(&self) -> usize {
+        self.vocabulary.len()
+    }
+
+    /// Token of every id
+    pub fn vocabulary(&self) -> &[String] {
+        &self.vocabulary
+    }
+
+    pub fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.token_ids.get(token).copied()
+    }
+
+    pub fn id_to_token(&self, id: u32) -> Option<&str> {
+        self.vocabulary.get(id as usize).map(String::as_str)
+    }
+
+    /// Subwords of a pre-token, as (id, byte start, byte end)
+    fn encode_pre_token(&self, pre_token: &str) -> Vec<(SymbolId, usize, usize)> {
+        let mut symbols: Vec<(SymbolId, usize, usize)> = cluster_ranges(pre_token)
+            .into_iter()
+            .map(|(start, end)| {
+                let id = self.token_ids.get(&pre_token[start..end]).copied();
+                (id.unwrap_or(0), start, end)
+            })
+            .collect();
+        loop {
+            let best_merge = symbols
+                .windows(2)
+                .enumerate()
+                .filter_map(|(index, pair)| {
+                    self.merge_ranks
+                        .get(&(pair[0].0, pair[1].0))
+                        .map(|&(rank, merged)| (rank, index, merged))
+                })
+                .min();
+            let Some((_, index, merged)) = best_merge else {
+                break;
+            };
+            symbols[index] = (merged, symbols[index].1, symbols[index + 1].2);
+            symbols.remove(index + 1);
+        }
+        symbols
+    }
+
+    /// Split text into subwords. Clusters not seen in training are kept as
+    /// they are, so the subwords always join back into `text`.
+    pub fn encode(&self, text: &str, pre_tokenizer: &PreTokenizer) -> ThongnaResult<Vec<String>> {
+        Ok(pre_tokenizer
+            .pre_tokenize(text)?
+            .iter()
+            .flat_map(|pre_token| {
+                self.encode_pre_token(pre_token)
+                    .into_iter()
+                    .map(|(_, start, end)| pre_token[start..end].to_string())
+            })
+            .collect())
+    }
+
+    /// Split text into subword ids. Clusters not seen in training get id 0,
+    /// the id of [`UNKNOWN_TOKEN`].
+    pub fn encode_ids(&self, text: &str, pre_tokenizer: &PreTokenizer) -> ThongnaResult<Vec<u32>> {
+        Ok(pre_tokenizer
+            .pre_tokenize(text)?
+            .iter()
+            .flat_map(|pre_token| {
+                self.encode_pre_token(pre_token)
+                    .into_iter()
+                    .map(|(id, _, _)| id)
+            })
+            .collect())
+    }
+
+    /// Joins the tokens of `ids`. Ids outside the vocabulary are
+    /// written as [`UNKNOWN_TOKEN`].
+    pub fn decode(&self, ids: &[u32]) -> String {
+        ids.iter()
+            .map(|&id| self.id_to_token(id).unwrap_or(UNKNOWN_TOKEN))
+            .collect()
+    }
+
+    /// Writes the clusters, one per line, then the merges, one per line as
+    /// `left<TAB>right`, after a header line. Backslashes, tabs and line
+    /// breaks in symbols are escaped as `\\`, `\t`, `\n` and `\r`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> ThongnaResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", VOCABULARY_HEADER)?;
+        for symbol in &self.vocabulary[1..=self.alphabet_size] {
+            writeln!(writer, "{}", escape_symbol(symbol))?;
+        }
+        for &(left, right) in &self.merges {
+            writeln!(
+                writer,
+                "{}\t{}",
+                escape_symbol(&self.vocabulary[left as usize]),
+                escape_symbol(&self.vocabulary[right as usize])
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a vocabulary written by [`BpeTokenizer::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> ThongnaResult<Self> {
+        let invalid_line = |line_number: usize, reason: &str| ThongnaError::InvalidVocabularyLine {
+            line_number,
+            reason: reason.to_string(),
+        };
+        let mut alphabet = vec![];
+        let mut merges = vec![];
+        let mut merge_line_numbers = vec![];
+        let mut has_header = false;
+        for (index, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.map_err(|error| match error.kind() {
+                std::io::ErrorKind::InvalidData => invalid_line(line_number, "not valid UTF-8"),
+                _ => error.into(),
+            })?;
+            if index == 0 {
+                if line != VOCABULARY_HEADER {
+                    return Err(invalid_line(line_number, "not a thongna BPE vocabulary"));
+                }
+                has_header = true;
+                continue;
+            }
+            let fields: Option<Vec<String>> = line.split('\t').map(unescape_symbol).collect();
+            match fields.as_deref() {
+                Some([symbol]) if merges.is_empty() && !symbol.is_empty() => {
+                    alphabet.push(symbol.clone())
+                }
+                Some([left, right]) if !left.is_empty() && !right.is_empty() => {
+                    merges.push((left.clone(), right.clone()));
+                    merge_line_numbers.push(line_number);
+                }
+                _ => return Err(invalid_line(line_number, "expected a symbol or a merge")),
+            }
+        }
+        if !has_header {
+            return Err(invalid_line(1, "not a thongna BPE vocabulary"));
+        }
+        Self::from_parts(alphabet, &merges)
+            .map_err(|index| invalid_line(merge_line_numbers[index], "merges an unknown symbol"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DICT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/dataset/words_th.txt");
+
+    #[test]
+    fn test_train_and_encode() {
+        let corpus = ["ประเทศไทย ประเทศไทย ประเทศลาว", "ไทยไทย"];
+        let bpe = BpeTokenizer::train(&corpus, &PreTokenizer::Whitespace, 100, 2).unwrap();
+        assert_eq!(bpe.vocabulary()[0], UNKNOWN_TOKEN);
+        assert_eq!(
+            &bpe.vocabulary()[1..10],
+            &[" ", "ป", "ย", "ระ", "ลา", "ว", "ศ", "เท", "ไท"]
+        );
+        assert!(bpe.token_to_id("ประเทศไทย").is_some());
+        assert!(bpe.token_to_id("ประเทศลาว").is_none());
+
+        let pre_tokenizer = PreTokenizer::Whitespace;
+        assert_eq!(
+            bpe.encode("ประเทศไทย ไทยลาว", &pre_tokenizer).unwrap(),
+            vec!["ประเทศไทย", " ", "ไทย", "ลา", "ว"]
+        );
+        let ids = bpe.encode_ids("ประเทศจีน", &pre_tokenizer).unwrap();
+        assert_eq!(ids[0], bpe.token_to_id("ประเทศ").unwrap());
+        assert_eq!(&ids[1..], &[0, 0]);
+        assert_eq!(bpe.decode(&ids[..1]), "ประเทศ");
+        assert!(bpe.encode("", &pre_tokenizer).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_newmm_pre_tokens() {
+        let words = NewmmTokenizer::new(DICT_PATH).unwrap();
+        let pre_tokenizer = PreTokenizer::Newmm(&words);
+        let corpus = ["แมวกินปลา", "แมวกินปลา", "หมากินปลา"];
+        let bpe = BpeTokenizer::train(&corpus, &pre_tokenizer, 1000, 1).unwrap();
+        // subwords stay within the words แมว, กิน, ปลา and หมา
+        assert!(bpe.token_to_id("ปลา").is_some());
+        assert!(bpe.token_to_id("กินปลา").is_none());
+        assert_eq!(
+            bpe.encode("แมวกินปลา", &pre_tokenizer).unwrap(),
+            vec!["แมว", "กิน", "ปลา"]
+        );
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let corpus = ["a\tb\\c a\tb\\c", "ภาษาไทย\nภาษาไทย"];
+        let bpe = BpeTokenizer::train(&corpus, &PreTokenizer::Whitespace, 50, 2).unwrap();
+        let path = std::env::temp_dir().join(format!("thongna_bpe_{}.txt", std::process::id()));
+        bpe.save(&path).unwrap();
+        let loaded = BpeTokenizer::load(&path).unwrap();
+        assert_eq!(loaded.vocabulary(), bpe.vocabulary());
+        let text = "a\tb\\c ภาษาไทย";
+        assert_eq!(
+            loaded.encode_ids(text, &PreTokenizer::Whitespace).unwrap(),
+            bpe.encode_ids(text, &PreTokenizer::Whitespace).unwrap()
+        );
+
+        std::fs::write(&path, "#thongna-bpe 1\nก\nก\tข\n").unwrap();
+        let error = BpeTokenizer::load(&path).unwrap_err();
+        assert!(matches!(
+            error,
+            ThongnaError::InvalidVocabularyLine { line_number: 3, .. }
+        ));
+        std::fs::write(&path, "not a vocabulary\n").unwrap();
+        assert!(BpeTokenizer::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
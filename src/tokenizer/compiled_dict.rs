@@ -0,0 +1,528 @@
+//! A precompiled dictionary: words and frequencies stored in a finite
+//! state transducer (FST) that is memory-mapped on load, so opening it
+//! does no parsing and worker processes share the same pages.
+//!
+//! File layout: an 8-byte magic, a little-endian u32 format version,
+//! the u64 total frequency, the u64 word count, then the FST bytes
+//! mapping UTF-8 words to frequencies.
+
+use super::completion::{EditMatch, EditQuery};
+use super::dict_reader::{read_dict_entries, DictSource};
+use super::dictionary::Dictionary;
+use super::trie_char::TrieChar;
+use crate::bytes_str::custom_string::{
+    CustomString, CustomStringBytesSlice, CustomStringBytesVec, FixedCharsLengthByteSlice,
+};
+use crate::error::{ThongnaError, ThongnaResult};
+
+use fst::raw::Output;
+use fst::{Automaton, IntoStreamer, Map, MapBuilder, Streamer};
+use memmap2::Mmap;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+const MAGIC: &[u8; 8] = b"THONGNA\0";
+const FORMAT_VERSION: u32 = 1;
+const HEADER_LEN: usize = MAGIC.len() + 4 + 8 + 8;
+
+/// Bytes of a compiled dictionary after its header,
+/// either memory-mapped from a file or owned.
+#[derive(Clone)]
+enum FstBytes {
+    Mapped(Arc<Mmap>),
+    Owned(Arc<Vec<u8>>),
+}
+
+impl AsRef<[u8]> for FstBytes {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            FstBytes::Mapped(mmap) => &mmap[HEADER_LEN..],
+            FstBytes::Owned(bytes) => &bytes[HEADER_LEN..],
+        }
+    }
+}
+
+/// Compile a dictionary into the binary format read by [`CompiledDictionary::open`].
+///
+/// Words are trimmed like [`TrieChar`] does; when a word appears more than once,
+/// the last frequency wins.
+///
+/// The dictionary is written to a temporary file next to `output_path`, then
+/// renamed over it, so recompiling a dictionary that processes have open is
+/// safe: their memory maps keep reading the old file until they reopen it.
+pub fn compile_dict(source: DictSource, output_path: &Path) -> ThongnaResult<()> {
+    let mut entries: HashMap<String, u64> = HashMap::default();
+    for (word, frequency) in read_dict_entries(source)? {
+        let trimmed = CustomString::new(&word).trim();
+        if !trimmed.is_empty() {
+            entries.insert(
+                CustomString::convert_raw_bytes_to_std_string(trimmed.raw_content()),
+                frequency,
+            );
+        }
+    }
+    let mut entries: Vec<(String, u64)> = entries.into_iter().collect();
+    entries.sort_unstable();
+
+    let file_name = output_path.file_name().unwrap_or_default().to_string_lossy();
+    let temporary_path =
+        output_path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let written = write_compiled_dict(entries, &temporary_path)
+        .and_then(|()| Ok(std::fs::rename(&temporary_path, output_path)?));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temporary_path);
+    }
+    written
+}
+
+/// Write sorted `entries` to a new file at `path`, synced to disk
+fn write_compiled_dict(entries: Vec<(String, u64)>, path: &Path) -> ThongnaResult<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    let total_frequency: u64 = entries.iter().map(|(_, frequency)| frequency).sum();
+    writer.write_all(&total_frequency.to_le_bytes())?;
+    writer.write_all(&(entries.len() as u64).to_le_bytes())?;
+
+    let mut builder = MapBuilder::new(writer)?;
+    for (word, frequency) in entries {
+        builder.insert(word, frequency)?;
+    }
+    let file = builder
+        .into_inner()?
+        .into_inner()
+        .map_err(|error| error.into_error())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// A dictionary loaded from a file written by [`compile_dict`].
+///
+/// The compiled words are read-only; words added or removed at runtime
+/// are kept in memory on top of them.
+pub struct CompiledDictionary {
+    map: Map<FstBytes>,
+    /// words added, or given a new frequency, at runtime
+    added: TrieChar,
+    /// compiled words removed at runtime, as UTF-8
+    removed: HashSet<Vec<u8>>,
+    total_frequency: u64,
+    amount_of_words: usize,
+}
+
+impl Debug for CompiledDictionary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompiledDictionary")
+            .field("compiled_words", &self.map.len())
+            .field("added", &self.added.amount_of_words())
+            .field("removed", &self.removed.len())
+            .finish()
+    }
+}
+
+impl CompiledDictionary {
+    /// Memory-map a compiled dictionary file.
+    pub fn open(path: &Path) -> ThongnaResult<Self> {
+        let file = File::open(path)?;
+        // Safety: the file is only read. As with any memory map, it must not be
+        // truncated or rewritten by another process while the dictionary is in use;
+        // compile_dict replaces the file instead, leaving this one as it is.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Self::from_fst_bytes(FstBytes::Mapped(Arc::new(mmap)))
+    }
+
+    /// Load a compiled dictionary from bytes already in memory.
+    pub fn from_bytes(bytes: Vec<u8>) -> ThongnaResult<Self> {
+        Self::from_fst_bytes(FstBytes::Owned(Arc::new(bytes)))
+    }
+
+    fn from_fst_bytes(bytes: FstBytes) -> ThongnaResult<Self> {
+        let raw: &[u8] = match &bytes {
+            FstBytes::Mapped(mmap) => mmap,
+            FstBytes::Owned(owned) => owned,
+        };
+        if raw.len() < HEADER_LEN || &raw[..MAGIC.len()] != MAGIC {
+            return Err(ThongnaError::InvalidCompiledDictionary(
+                "missing header".to_string(),
+            ));
+        }
+        let read_u64 = |offset: usize| u64::from_le_bytes(raw[offset..offset + 8].try_into().unwrap());
+        let version = u32::from_le_bytes(raw[MAGIC.len()..MAGIC.len() + 4].try_into().unwrap());
+        if version != FORMAT_VERSION {
+            return Err(ThongnaError::InvalidCompiledDictionary(format!(
+                "unsupported format version {}",
+                version
+            )));
+        }
+        let total_frequency = read_u64(MAGIC.len() + 4);
+        let amount_of_words = read_u64(MAGIC.len() + 12) as usize;
+
+        Ok(Self {
+            map: Map::new(bytes)?,
+            added: TrieChar::new(&[]),
+            removed: HashSet::default(),
+            total_frequency,
+            amount_of_words,
+        })
+    }
+
+    /// Frequency of a compiled word, ignoring runtime changes.
+    fn compiled_frequency(&self, utf8_word: &[u8]) -> Option<u64> {
+        if self.removed.contains(utf8_word) {
+            None
+        } else {
+            self.map.get(utf8_word)
+        }
+    }
+
+    /// Lengths, in characters, of the compiled words that are prefixes of `prefix`.
+    fn compiled_prefix_lengths(&self, prefix: &CustomString) -> Vec<usize> {
+        let fst = self.map.as_fst();
+        let mut node = fst.root();
+        let mut output = Output::zero();
+        let mut utf8_prefix: Vec<u8> = Vec::with_capacity(prefix.chars_len() * 3);
+        let mut lengths = vec![];
+        let mut char_buffer = [0; 4];
+
+        'chars: for (index, character) in prefix.get_chars_content().iter().enumerate() {
+            for &byte in character.encode_utf8(&mut char_buffer).as_bytes() {
+                match node.find_input(byte) {
+                    Some(transition_index) => {
+                        let transition = node.transition(transition_index);
+                        output = output.cat(transition.out);
+                        node = fst.node(transition.addr);
+                        utf8_prefix.push(byte);
+                    }
+                    None => break 'chars,
+                }
+            }
+            if node.is_final() && !self.removed.contains(&utf8_prefix) {
+                lengths.push(index + 1);
+            }
+        }
+        lengths
+    }
+
+    /// Applies a runtime change, keeping word count and total frequency in sync.
+    fn update(&mut self, word: &CustomString, change: impl FnOnce(&mut Self, &CustomString)) {
+        let stripped_word = word.trim();
+        let previous = self.frequency(stripped_word.raw_content());
+        change(self, &stripped_word);
+        let current = self.frequency(stripped_word.raw_content());
+        self.total_frequency = self.total_frequency - previous.unwrap_or_default()
+            + current.unwrap_or_default();
+        self.amount_of_words =
+            self.amount_of_words - previous.is_some() as usize + current.is_some() as usize;
+    }
+}
+
+impl Dictionary for CompiledDictionary {
+    fn prefix_ref<'p>(&self, prefix: &'p CustomString) -> Vec<&'p CustomStringBytesSlice> {
+        let mut lengths = self.compiled_prefix_lengths(prefix);
+        if self.added.amount_of_words() > 0 {
+            lengths.extend(
+                TrieChar::prefix_ref(prefix, &self.added)
+                    .iter()
+                    .map(|word| word.chars_len()),
+            );
+            lengths.sort_unstable();
+            lengths.dedup();
+        }
+        lengths
+            .into_iter()
+            .map(|length| prefix.raw_content().slice_by_char_indice(0, length))
+            .collect()
+    }
+
+    fn contain(&self, word: &CustomString) -> bool {
+        self.frequency(word.raw_content()).is_some()
+    }
+
+    fn frequency(&self, word: &CustomStringBytesSlice) -> Option<u64> {
+        self.added.frequency(word).or_else(|| {
+            self.compiled_frequency(&CustomString::convert_raw_bytes_to_utf8_bytes(word))
+        })
+    }
+
+    fn total_frequency(&self) -> u64 {
+        self.total_frequency
+    }
+
+    fn amount_of_words(&self) -> usize {
+        self.amount_of_words
+    }
+
+    fn add(&mut self, word: &CustomString) {
+        if !word.trim().is_empty() && !self.contain(&word.trim()) {
+            self.add_with_frequency(word, 0);
+        }
+    }
+
+    fn add_with_frequency(&mut self, word: &CustomString, frequency: u64) {
+        if word.trim().is_empty() {
+            return;
+        }
+        self.update(word, |dict, stripped_word| {
+            dict.added.add_with_frequency(stripped_word, frequency);
+        });
+    }
+
+    fn remove(&mut self, word: &CustomString) {
+        self.update(word, |dict, stripped_word| {
+            dict.added.remove(stripped_word);
+            let utf8_word = CustomString::convert_raw_bytes_to_utf8_bytes(stripped_word.raw_content());
+            if dict.map.contains_key(&utf8_word) {
+                dict.removed.insert(utf8_word);
+            }
+        });
+    }
+
+    fn words(&self) -> Vec<(String, u64)> {
+        let mut words = self.added.words();
+        let mut stream = self.map.stream();
+        while let Some((utf8_word, frequency)) = stream.next() {
+            if self.removed.contains(utf8_word) {
+                continue;
+            }
+            let word = String::from_utf8_lossy(utf8_word).into_owned();
+            let custom_word: CustomStringBytesVec = CustomString::new(&word).raw_content().into();
+            if self.added.frequency(&custom_word).is_none() {
+                words.push((word, frequency));
+            }
+        }
+        words
+    }
+
+    fn fuzzy_search(&self, query: &EditQuery) -> Vec<EditMatch> {
+        let mut matches = self.added.fuzzy_search(query);
+        let mut stream = self.map.search_with_state(EditAutomaton(query)).into_stream();
+        while let Some((utf8_word, frequency, state)) = stream.next() {
+            let Some(cost) = state.and_then(|state| state.matched) else {
+                continue;
+            };
+            if self.removed.contains(utf8_word) {
+                continue;
+            }
+            let word = String::from_utf8_lossy(utf8_word).into_owned();
+            let custom_word: CustomStringBytesVec = CustomString::new(&word).raw_content().into();
+            if self.added.frequency(&custom_word).is_none() {
+                matches.push(EditMatch {
+                    word,
+                    cost,
+                    frequency,
+                });
+            }
+        }
+        matches
+    }
+}
+
+/// Searches the FST for the words of an [`EditQuery`], leaving out every
+/// branch no word of which can match.
+struct EditAutomaton<'q, 'c>(&'q EditQuery<'c>);
+
+/// Costs of the characters of a key read so far; `None` when no key
+/// starting with them can match.
+#[derive(Clone)]
+struct EditState {
+    row: Rc<Vec<u32>>,
+    /// Costs of the path without its last character, and that character
+    before: Option<(Rc<Vec<u32>>, char)>,
+    matched: Option<u32>,
+    /// UTF-8 bytes of a character not read to its end yet
+    pending: [u8; 4],
+    pending_len: usize,
+}
+
+impl Automaton for EditAutomaton<'_, '_> {
+    type State = Option<EditState>;
+
+    fn start(&self) -> Self::State {
+        let row = self.0.first_row();
+        Some(EditState {
+            matched: self.0.matched(None, &row),
+            row: Rc::new(row),
+            before: None,
+            pending: [0; 4],
+            pending_len: 0,
+        })
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        state
+            .as_ref()
+            .is_some_and(|state| state.pending_len == 0 && state.matched.is_some())
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.is_some()
+    }
+
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+        let state = state.as_ref()?;
+        let mut pending = state.pending;
+        pending[state.pending_len] = byte;
+        let pending_len = state.pending_len + 1;
+        let character = match std::str::from_utf8(&pending[..pending_len]) {
+            Ok(text) => text.chars().next()?,
+            Err(error) if error.error_len().is_none() => {
+                return Some(EditState {
+                    pending,
+                    pending_len,
+                    ..state.clone()
+                });
+            }
+            Err(_) => return None,
+        };
+        let before = state
+            .before
+            .as_ref()
+            .map(|(row, before_character)| (&row[..], *before_character));
+        let row = self.0.next_row(&state.row, before, character);
+        let matched = self.0.matched(state.matched, &row);
+        if !self.0.reachable(matched, &row) {
+            return None;
+        }
+        Some(EditState {
+            row: Rc::new(row),
+            before: Some((state.row.clone(), character)),
+            matched,
+            pending: [0; 4],
+            pending_len: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::completion::{completions, UnitCosts};
+
+    fn compile_word_list(word_list: Vec<(String, u64)>) -> CompiledDictionary {
+        let path = std::env::temp_dir().join(format!(
+            "thongna-compiled-dict-test-{}-{}.bin",
+            std::process::id(),
+            word_list.len()
+        ));
+        compile_dict(DictSource::WeightedWordList(word_list), &path).unwrap();
+        let dict = CompiledDictionary::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        dict
+    }
+
+    #[test]
+    fn test_compiled_prefix_ref() {
+        let dict = compile_word_list(vec![
+            ("ศาล".to_string(), 10),
+            ("ศาลา".to_string(), 5),
+            (" ลา ".to_string(), 1),
+        ]);
+        assert_eq!(dict.amount_of_words(), 3);
+        assert_eq!(dict.total_frequency(), 16);
+
+        let text = CustomString::new("ศาลาว่าการ");
+        let prefixes: Vec<String> = dict
+            .prefix_ref(&text)
+            .into_iter()
+            .map(CustomString::convert_raw_bytes_to_std_string)
+            .collect();
+        assert_eq!(prefixes, vec!["ศาล", "ศาลา"]);
+        assert_eq!(dict.frequency(CustomString::new("ลา").raw_content()), Some(1));
+        assert!(!dict.contain(&CustomString::new("ศา")));
+    }
+
+    #[test]
+    fn test_compiled_runtime_changes() {
+        let mut dict = compile_word_list(vec![("ศาล".to_string(), 10), ("ศาลา".to_string(), 5)]);
+        dict.add(&CustomString::new("ศา"));
+        dict.add(&CustomString::new("ศาล"));
+        dict.add_with_frequency(&CustomString::new("ศาลา"), 7);
+        dict.remove(&CustomString::new("ศาล"));
+        assert_eq!(dict.amount_of_words(), 2);
+        assert_eq!(dict.total_frequency(), 7);
+
+        let text = CustomString::new("ศาลา");
+        let prefixes: Vec<usize> = dict.prefix_ref(&text).iter().map(|word| word.chars_len()).collect();
+        assert_eq!(prefixes, vec![2, 4]);
+
+        let mut words = dict.words();
+        words.sort();
+        assert_eq!(words, vec![("ศา".to_string(), 0), ("ศาลา".to_string(), 7)]);
+    }
+
+    #[test]
+    fn test_compiled_completions() {
+        let words = vec![
+            ("สวัสดี".to_string(), 10),
+            ("สวัสดิการ".to_string(), 30),
+            ("สวน".to_string(), 50),
+            ("กา".to_string(), 0),
+        ];
+        let mut dict = compile_word_list(words.clone());
+        let trie = TrieChar::new_with_frequencies(
+            &words
+                .iter()
+                .map(|(word, frequency)| (CustomString::new(word), *frequency))
+                .collect::<Vec<_>>(),
+        );
+        for (prefix, max_distance) in [("สวัส", 0), ("สว", 0), ("", 0), ("สวสด", 1), ("สัวส", 1), ("ขา", 1)] {
+            let mut expected = completions(&trie, prefix, max_distance);
+            let mut completions = completions(&dict, prefix, max_distance);
+            expected.sort_by(|a, b| a.word.cmp(&b.word));
+            completions.sort_by(|a, b| a.word.cmp(&b.word));
+            assert_eq!(completions, expected, "{} {}", prefix, max_distance);
+        }
+        for (typed, max_cost) in [("สวัสดี", 0), ("สวสดี", 1), ("สวัสดิ", 2), ("กา", 1)] {
+            let query = EditQuery::words(typed, &UnitCosts, max_cost);
+            let mut expected = trie.fuzzy_search(&query);
+            let mut found = dict.fuzzy_search(&query);
+            expected.sort_by(|a, b| a.word.cmp(&b.word));
+            found.sort_by(|a, b| a.word.cmp(&b.word));
+            assert_eq!(found, expected, "{} {}", typed, max_cost);
+        }
+
+        dict.add_with_frequency(&CustomString::new("สวัสดี"), 70);
+        dict.add(&CustomString::new("สวัสดีครับ"));
+        dict.remove(&CustomString::new("สวัสดิการ"));
+        let mut completions: Vec<(String, u64)> = completions(&dict, "สวัส", 0)
+            .into_iter()
+            .map(|completion| (completion.word, completion.frequency))
+            .collect();
+        completions.sort();
+        assert_eq!(
+            completions,
+            vec![("สวัสดี".to_string(), 70), ("สวัสดีครับ".to_string(), 0)]
+        );
+    }
+
+    #[test]
+    fn test_recompile_open_dict() {
+        let directory = std::env::temp_dir().join(format!("thongna-recompile-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("words.bin");
+        compile_dict(DictSource::WeightedWordList(vec![("ศาล".to_string(), 10)]), &path).unwrap();
+        let loaded = CompiledDictionary::open(&path).unwrap();
+
+        compile_dict(DictSource::WeightedWordList(vec![("ศาลา".to_string(), 5)]), &path).unwrap();
+        // the loaded dictionary still reads the file it mapped
+        assert_eq!(loaded.frequency(CustomString::new("ศาล").raw_content()), Some(10));
+        let reloaded = CompiledDictionary::open(&path).unwrap();
+        assert_eq!(reloaded.frequency(CustomString::new("ศาลา").raw_content()), Some(5));
+        assert!(!reloaded.contain(&CustomString::new("ศาล")));
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_compiled_dict() {
+        assert!(matches!(
+            CompiledDictionary::from_bytes(b"not a dictionary".to_vec()),
+            Err(ThongnaError::InvalidCompiledDictionary(_))
+        ));
+    }
+}
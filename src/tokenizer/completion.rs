@@ -0,0 +1,351 @@
+//! Completion of a prefix into dictionary words, allowing typos in the prefix,
+//! and the search of dictionary words near a typed text it is built on.
+
+use std::cmp::Ordering;
+
+use super::dictionary::Dictionary;
+
+/// A dictionary word starting with a prefix, or with a beginning close to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Completion {
+    pub word: String,
+    /// Number of edits (insertions, deletions, substitutions and swaps of
+    /// adjacent characters) between the prefix and the closest beginning
+    /// of the word, 0 if the word starts with the prefix
+    pub distance: usize,
+    /// Frequency of the word in the dictionary, 0 if it has none
+    pub frequency: u64,
+}
+
+/// Closest first, then most frequent, then in alphabetical order
+fn rank(a: &Completion, b: &Completion) -> Ordering {
+    a.distance
+        .cmp(&b.distance)
+        .then(b.frequency.cmp(&a.frequency))
+        .then_with(|| a.word.cmp(&b.word))
+}
+
+/// Keeps the `limit` best completions, the closest and most frequent first.
+pub(crate) fn rank_completions(mut completions: Vec<Completion>, limit: usize) -> Vec<Completion> {
+    if limit == 0 {
+        return vec![];
+    }
+    if completions.len() > limit {
+        completions.select_nth_unstable_by(limit - 1, rank);
+        completions.truncate(limit);
+    }
+    completions.sort_unstable_by(rank);
+    completions
+}
+
+/// Words of `dictionary` starting with `prefix` give or take `max_distance`
+/// edits, in no particular order.
+pub(crate) fn completions(
+    dictionary: &dyn Dictionary,
+    prefix: &str,
+    max_distance: usize,
+) -> Vec<Completion> {
+    let query = EditQuery::prefix(prefix, &UnitCosts, max_distance as u32);
+    dictionary
+        .fuzzy_search(&query)
+        .into_iter()
+        .map(|found| Completion {
+            word: found.word,
+            distance: found.cost as usize,
+            frequency: found.frequency,
+        })
+        .collect()
+}
+
+/// Costs of the edits turning a typed text into a dictionary word.
+pub trait EditCosts: Sync {
+    /// Cost of `typed` typed in place of `expected`, 0 if they are the same
+    fn substitution(&self, typed: char, expected: char) -> u32;
+
+    /// Cost of a character typed in excess, or left out
+    fn insertion(&self, character: char) -> u32;
+
+    /// Cost of two adjacent characters typed in the wrong order
+    fn swap(&self, first: char, second: char) -> u32;
+}
+
+/// Every edit costs 1, the costs of the edit distance
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UnitCosts;
+
+impl EditCosts for UnitCosts {
+    fn substitution(&self, typed: char, expected: char) -> u32 {
+        u32::from(typed != expected)
+    }
+
+    fn insertion(&self, _character: char) -> u32 {
+        1
+    }
+
+    fn swap(&self, _first: char, _second: char) -> u32 {
+        1
+    }
+}
+
+/// Dictionary words to search for: the words whose optimal string alignment
+/// distance to a typed text is at most a maximum cost, or, for a prefix, the
+/// words with a beginning that close.
+pub struct EditQuery<'c> {
+    typed: Vec<char>,
+    costs: &'c dyn EditCosts,
+    max_cost: u32,
+    prefix: bool,
+}
+
+/// A dictionary word found by an [`EditQuery`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EditMatch {
+    pub word: String,
+    /// Cost of the edits between the typed text and the word, or its
+    /// closest beginning for a prefix
+    pub cost: u32,
+    /// Frequency of the word in the dictionary, 0 if it has none
+    pub frequency: u64,
+}
+
+impl<'c> EditQuery<'c> {
+    /// Words at most `max_cost` away from `typed`
+    pub fn words(typed: &str, costs: &'c dyn EditCosts, max_cost: u32) -> Self {
+        Self {
+            typed: typed.chars().collect(),
+            costs,
+            max_cost,
+            prefix: false,
+        }
+    }
+
+    /// Words starting at most `max_cost` away from `prefix`
+    pub fn prefix(prefix: &str, costs: &'c dyn EditCosts, max_cost: u32) -> Self {
+        Self {
+            prefix: true,
+            ..Self::words(prefix, costs, max_cost)
+        }
+    }
+
+    /// Costs between the first j typed characters and no character
+    pub(crate) fn first_row(&self) -> Vec<u32> {
+        std::iter::once(0)
+            .chain(self.typed.iter().scan(0, |cost, &character| {
+                *cost += self.costs.insertion(character);
+                Some(*cost)
+            }))
+            .collect()
+    }
+
+    /// Costs between the first j typed characters and a path ending with
+    /// `character`, from the costs of the path without it, `previous`, and
+    /// of the path without its last two characters, `before`, along with the
+    /// character before `character`
+    pub(crate) fn next_row(
+        &self,
+        previous: &[u32],
+        before: Option<(&[u32], char)>,
+        character: char,
+    ) -> Vec<u32> {
+        let typed = &self.typed;
+        let mut row = Vec::with_capacity(typed.len() + 1);
+        row.push(previous[0] + self.costs.insertion(character));
+        for j in 1..=typed.len() {
+            let mut cost = (previous[j] + self.costs.insertion(character))
+                .min(row[j - 1] + self.costs.insertion(typed[j - 1]))
+                .min(previous[j - 1] + self.costs.substitution(typed[j - 1], character));
+            if let Some((before, before_character)) = before {
+                if j > 1 && typed[j - 1] == before_character && typed[j - 2] == character {
+                    cost = cost.min(before[j - 2] + self.costs.swap(typed[j - 2], typed[j - 1]));
+                }
+            }
+            row.push(cost);
+        }
+        row
+    }
+
+    /// Cost of a match on a path whose costs are `row`, given the cost of
+    /// the match on the path without its last character for a prefix
+    pub(crate) fn matched(&self, previous: Option<u32>, row: &[u32]) -> Option<u32> {
+        let cost = Some(row[self.typed.len()]).filter(|&cost| cost <= self.max_cost);
+        if self.prefix {
+            previous.into_iter().chain(cost).min()
+        } else {
+            cost
+        }
+    }
+
+    /// Whether some word starting with a path whose costs are `row`,
+    /// and whose match is `matched`, can match
+    pub(crate) fn reachable(&self, matched: Option<u32>, row: &[u32]) -> bool {
+        (self.prefix && matched.is_some())
+            || row.iter().min().is_some_and(|&cost| cost <= self.max_cost)
+    }
+}
+
+/// Matches an [`EditQuery`] on the paths of a trie, one character at a
+/// time so that shared beginnings are computed once.
+pub(crate) struct EditMatcher<'q, 'c> {
+    query: &'q EditQuery<'c>,
+    /// rows[depth][j]: cost between the first j typed characters and the
+    /// first `depth` characters of the path
+    rows: Vec<Vec<u32>>,
+    path: Vec<char>,
+    /// matched[depth]: cost of the match on the first `depth` characters
+    /// of the path, if any
+    matched: Vec<Option<u32>>,
+}
+
+impl<'q, 'c> EditMatcher<'q, 'c> {
+    pub(crate) fn new(query: &'q EditQuery<'c>) -> Self {
+        let first_row = query.first_row();
+        let matched = query.matched(None, &first_row);
+        Self {
+            query,
+            rows: vec![first_row],
+            path: vec![],
+            matched: vec![matched],
+        }
+    }
+
+    /// Number of characters of the current path
+    pub(crate) fn depth(&self) -> usize {
+        self.path.len()
+    }
+
+    /// Moves to the path made of the first `depth - 1` characters of the
+    /// current path followed by `character`. Returns whether some word
+    /// starting with the new path can match.
+    pub(crate) fn step(&mut self, depth: usize, character: char) -> bool {
+        self.rows.truncate(depth);
+        self.path.truncate(depth - 1);
+        self.matched.truncate(depth);
+        self.path.push(character);
+
+        let before = (depth > 1).then(|| (&self.rows[depth - 2][..], self.path[depth - 2]));
+        let row = self
+            .query
+            .next_row(&self.rows[depth - 1], before, character);
+        let matched = self.query.matched(self.matched[depth - 1], &row);
+        let reachable = self.query.reachable(matched, &row);
+        self.rows.push(row);
+        self.matched.push(matched);
+        reachable
+    }
+
+    /// Cost of the match on the current path, if it matches
+    pub(crate) fn cost(&self) -> Option<u32> {
+        self.matched[self.depth()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytes_str::custom_string::CustomString;
+    use crate::tokenizer::trie_char::TrieChar;
+
+    fn prefix_distance(prefix: &str, word: &str, max_distance: u32) -> Option<u32> {
+        let query = EditQuery::prefix(prefix, &UnitCosts, max_distance);
+        let mut matcher = EditMatcher::new(&query);
+        for (index, character) in word.chars().enumerate() {
+            matcher.step(index + 1, character);
+        }
+        matcher.cost()
+    }
+
+    #[test]
+    fn test_edit_matcher() {
+        assert_eq!(prefix_distance("สวัส", "สวัสดี", 0), Some(0));
+        assert_eq!(prefix_distance("", "สวัสดี", 0), Some(0));
+        assert_eq!(prefix_distance("สวส", "สวัสดี", 1), Some(1));
+        assert_eq!(prefix_distance("สวส", "สวัสดี", 0), None);
+        // a swap of adjacent characters is one edit
+        assert_eq!(prefix_distance("สัวส", "สวัสดี", 1), Some(1));
+        assert_eq!(prefix_distance("สวัสดีครับ", "สวัสดี", 2), None);
+        assert_eq!(prefix_distance("สวัสดีคะ", "สวัสดี", 2), Some(2));
+
+        // the path can move back to a shorter one
+        let query = EditQuery::prefix("กา", &UnitCosts, 0);
+        let mut matcher = EditMatcher::new(&query);
+        assert!(matcher.step(1, 'ก'));
+        assert!(matcher.step(2, 'า'));
+        assert_eq!(matcher.cost(), Some(0));
+        assert!(!matcher.step(2, 'ข'));
+        assert_eq!(matcher.depth(), 2);
+        assert_eq!(matcher.cost(), None);
+
+        // whole words only match at their end
+        let query = EditQuery::words("กา", &UnitCosts, 1);
+        let mut matcher = EditMatcher::new(&query);
+        matcher.step(1, 'ก');
+        assert_eq!(matcher.cost(), Some(1));
+        matcher.step(2, 'า');
+        matcher.step(3, 'ร');
+        assert_eq!(matcher.cost(), Some(1));
+        assert!(!matcher.step(4, 'ย'));
+        assert_eq!(matcher.cost(), None);
+    }
+
+    #[test]
+    fn test_completions() {
+        let trie = TrieChar::new_with_frequencies(&[
+            (CustomString::new("สวัสดี"), 10),
+            (CustomString::new("สวัสดิการ"), 30),
+            (CustomString::new("สวาท"), 5),
+            (CustomString::new("สวน"), 50),
+            (CustomString::new("กา"), 0),
+        ]);
+        let words = |prefix: &str, max_distance: usize, limit: usize| -> Vec<(String, usize)> {
+            rank_completions(completions(&trie, prefix, max_distance), limit)
+                .into_iter()
+                .map(|completion| (completion.word, completion.distance))
+                .collect()
+        };
+        assert_eq!(
+            words("สวัส", 0, 10),
+            vec![("สวัสดิการ".to_string(), 0), ("สวัสดี".to_string(), 0)]
+        );
+        assert_eq!(words("สว", 0, 2).len(), 2);
+        assert_eq!(words("สว", 0, 2)[0].0, "สวน");
+        assert!(words("ขา", 0, 10).is_empty());
+        assert_eq!(words("", 0, 10).len(), 5);
+
+        // a missing ั, then a swap
+        assert_eq!(
+            words("สวสด", 1, 10),
+            vec![("สวัสดิการ".to_string(), 1), ("สวัสดี".to_string(), 1)]
+        );
+        assert_eq!(words("สัวส", 1, 1)[0].0, "สวัสดิการ");
+        assert_eq!(
+            words("สวา", 1, 10)[..2],
+            [("สวาท".to_string(), 0), ("สวน".to_string(), 1)]
+        );
+        assert!(words("สวสด", 0, 10).is_empty());
+    }
+
+    #[test]
+    fn test_rank_completions() {
+        let completion = |word: &str, distance, frequency| Completion {
+            word: word.to_string(),
+            distance,
+            frequency,
+        };
+        let completions = vec![
+            completion("ก", 1, 100),
+            completion("ข", 0, 5),
+            completion("ค", 0, 50),
+            completion("ง", 0, 5),
+        ];
+        assert_eq!(
+            rank_completions(completions.clone(), 3),
+            vec![
+                completion("ค", 0, 50),
+                completion("ข", 0, 5),
+                completion("ง", 0, 5)
+            ]
+        );
+        assert_eq!(rank_completions(completions.clone(), 10).len(), 4);
+        assert!(rank_completions(completions, 0).is_empty());
+    }
+}
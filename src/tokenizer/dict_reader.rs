@@ -1,26 +1,85 @@
 use crate::bytes_str::custom_string::CustomString;
 
 use super::trie_char::TrieChar as Trie;
-use std::io::{BufRead, BufReader};
-use std::{error::Error, fs::File, path::PathBuf};
+use crate::error::{ThongnaError, ThongnaResult};
+use std::io::{self, BufRead, BufReader};
+use std::{fs::File, path::PathBuf};
 
 pub enum DictSource {
+    /// One word per line, optionally followed by a tab and its frequency
+    /// (`word<TAB>count`).
     FilePath(PathBuf),
     WordList(Vec<String>),
+    WeightedWordList(Vec<(String, u64)>),
 }
 
-pub fn create_dict_trie(source: DictSource) -> Result<Trie, Box<dyn Error>> {
+/// Splits a dictionary line, the `line_number`th of its file, into a word
+/// and its frequency.
+///
+/// Lines without a tab are taken as a whole word with frequency 0, the text
+/// after the last tab must be a non-negative integer.
+pub fn parse_dict_line(line: &str, line_number: usize) -> ThongnaResult<(&str, u64)> {
+    match line.rsplit_once('\t') {
+        Some((word, count)) => match count.trim().parse::<u64>() {
+            Ok(frequency) => Ok((word, frequency)),
+            Err(_) => Err(ThongnaError::InvalidDictionaryLine {
+                line_number,
+                reason: format!("frequency {:?} is not a non-negative integer", count.trim()),
+            }),
+        },
+        None => Ok((line, 0)),
+    }
+}
+
+/// Reads every line of a dictionary source as written, without parsing
+/// frequencies. The words of a weighted word list are taken as lines.
+pub fn read_dict_lines(source: DictSource) -> ThongnaResult<Vec<String>> {
     match source {
         DictSource::FilePath(file_path) => {
             let file = File::open(file_path)?;
             let reader = BufReader::with_capacity(8192, file);
-            let dict: Vec<CustomString> = reader
+            reader
                 .lines()
-                .filter_map(Result::ok)
-                .map(|line| CustomString::new(&line))
-                .collect();
-            Ok(Trie::new(&dict))
+                .enumerate()
+                .map(|(index, line)| match line {
+                    Ok(line) => Ok(line),
+                    Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+                        Err(ThongnaError::InvalidDictionaryLine {
+                            line_number: index + 1,
+                            reason: "not valid UTF-8".to_string(),
+                        })
+                    }
+                    Err(error) => Err(error.into()),
+                })
+                .collect()
         }
+        DictSource::WordList(word_list) => Ok(word_list),
+        DictSource::WeightedWordList(word_list) => {
+            Ok(word_list.into_iter().map(|(word, _)| word).collect())
+        }
+    }
+}
+
+/// Reads every (word, frequency) entry of a dictionary source, untrimmed.
+pub fn read_dict_entries(source: DictSource) -> ThongnaResult<Vec<(String, u64)>> {
+    match source {
+        DictSource::FilePath(file_path) => read_dict_lines(DictSource::FilePath(file_path))?
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let (word, frequency) = parse_dict_line(line, index + 1)?;
+                Ok((word.to_string(), frequency))
+            })
+            .collect(),
+        DictSource::WordList(word_list) => {
+            Ok(word_list.into_iter().map(|word| (word, 0)).collect())
+        }
+        DictSource::WeightedWordList(word_list) => Ok(word_list),
+    }
+}
+
+pub fn create_dict_trie(source: DictSource) -> ThongnaResult<Trie> {
+    match source {
         DictSource::WordList(word_list) => {
             let custom_word_list: Vec<CustomString> = word_list
                 .into_iter()
@@ -28,6 +87,13 @@ pub fn create_dict_trie(source: DictSource) -> Result<Trie, Box<dyn Error>> {
                 .collect();
             Ok(Trie::new(&custom_word_list))
         }
+        source => {
+            let custom_word_list: Vec<(CustomString, u64)> = read_dict_entries(source)?
+                .into_iter()
+                .map(|(word, frequency)| (CustomString::new(&word), frequency))
+                .collect();
+            Ok(Trie::new_with_frequencies(&custom_word_list))
+        }
     }
 }
 
@@ -44,3 +110,30 @@ fn test_trie() {
     assert!(trie.contain(&CustomString::new("กาแฟ")));
     assert_eq!(trie.amount_of_words(), 5);
 }
+
+#[test]
+fn test_parse_dict_line() {
+    assert_eq!(parse_dict_line("กาแฟ\t120", 1).unwrap(), ("กาแฟ", 120));
+    assert_eq!(parse_dict_line("กาแฟ", 1).unwrap(), ("กาแฟ", 0));
+    for line in ["กาแฟ\tเย็น", "กาแฟ\tabc", "กาแฟ\t-3"] {
+        assert!(matches!(
+            parse_dict_line(line, 7),
+            Err(ThongnaError::InvalidDictionaryLine { line_number: 7, .. })
+        ));
+    }
+}
+
+#[test]
+fn test_invalid_dict_line() {
+    let path = std::env::temp_dir().join(format!("thongna-dict-reader-{}.txt", std::process::id()));
+    std::fs::write(&path, b"\xe0\xb8\x81\n\xff\xfe\n").unwrap();
+    let result = read_dict_entries(DictSource::FilePath(path.clone()));
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        result,
+        Err(ThongnaError::InvalidDictionaryLine { line_number: 2, .. })
+    ));
+
+    let missing = read_dict_entries(DictSource::FilePath(path));
+    assert!(matches!(missing, Err(ThongnaError::Io(_))));
+}
@@ -0,0 +1,40 @@
+use super::completion::{EditMatch, EditQuery};
+use crate::bytes_str::custom_string::{CustomString, CustomStringBytesSlice};
+
+use std::fmt::Debug;
+
+/// A word dictionary newmm can segment with.
+///
+/// Words are looked up as custom four-byte strings, see
+/// [`CustomString`]. Every word carries a frequency, 0 when the
+/// dictionary has none.
+pub trait Dictionary: Debug + Send + Sync {
+    /// Returns a vec of substring (as reference) of `prefix` that are words
+    /// in the dictionary, shortest first.
+    fn prefix_ref<'p>(&self, prefix: &'p CustomString) -> Vec<&'p CustomStringBytesSlice>;
+
+    fn contain(&self, word: &CustomString) -> bool;
+
+    /// Returns the frequency of a word given as custom string bytes,
+    /// or None if the word is not in the dictionary.
+    fn frequency(&self, word: &CustomStringBytesSlice) -> Option<u64>;
+
+    /// Sum of the frequencies of all words.
+    fn total_frequency(&self) -> u64;
+
+    fn amount_of_words(&self) -> usize;
+
+    /// Adds a word, keeping its frequency if it is already in the dictionary.
+    fn add(&mut self, word: &CustomString);
+
+    /// Adds a word, replacing its frequency if it is already in the dictionary.
+    fn add_with_frequency(&mut self, word: &CustomString, frequency: u64);
+
+    fn remove(&mut self, word: &CustomString);
+
+    /// Returns every word with its frequency, in no particular order.
+    fn words(&self) -> Vec<(String, u64)>;
+
+    /// Returns the words matching `query`, in no particular order.
+    fn fuzzy_search(&self, query: &EditQuery) -> Vec<EditMatch>;
+}
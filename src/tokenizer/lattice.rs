@@ -0,0 +1,145 @@
+use super::token::Token;
+
+use rustc_hash::FxHashMap as HashMap;
+
+/// A candidate word between two character positions of the input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LatticeEdge {
+    pub token: Token,
+    /// Whether the word comes from the dictionary, as opposed to
+    /// a non-dictionary run (Latin, digits, spaces or unknown Thai text)
+    pub in_dictionary: bool,
+    pub cost: f64,
+}
+
+/// The segmentation lattice of one input: nodes are character positions
+/// at which a token may start or end, edges are candidate words.
+/// Every path from node 0 to the last node is a segmentation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Lattice {
+    /// Character positions in increasing order
+    pub nodes: Vec<usize>,
+    /// Edges sorted by start then end position
+    pub edges: Vec<LatticeEdge>,
+}
+
+/// A segmentation together with its total cost.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segmentation {
+    pub tokens: Vec<Token>,
+    pub cost: f64,
+}
+
+/// Back pointer of a ranked path: (cost, (edge index, rank of the path at the edge start))
+type RankedPath = (f64, Option<(usize, usize)>);
+
+impl Lattice {
+    /// `edges` must be sorted by start then end position.
+    pub(crate) fn new(chars_len: usize, edges: Vec<LatticeEdge>) -> Self {
+        let mut nodes: Vec<usize> = edges
+            .iter()
+            .flat_map(|edge| [edge.token.start, edge.token.end])
+            .chain([0, chars_len])
+            .collect();
+        nodes.sort_unstable();
+        nodes.dedup();
+        Self { nodes, edges }
+    }
+
+    fn last_node(&self) -> usize {
+        self.nodes.last().copied().unwrap_or_default()
+    }
+
+    /// Returns the `k` lowest-cost paths from the first to the last node, best first.
+    /// Paths with equal cost keep the order in which their edges appear.
+    pub fn nbest(&self, k: usize) -> Vec<Segmentation> {
+        if k == 0 {
+            return vec![];
+        }
+        let goal = self.last_node();
+        let mut ranked: HashMap<usize, Vec<RankedPath>> = HashMap::default();
+        ranked.insert(0, vec![(0.0, None)]);
+
+        // Edges are sorted by start position and always point forward, so every
+        // path into an edge's start is final by the time the edge is visited.
+        for (edge_index, edge) in self.edges.iter().enumerate() {
+            let Some(start_paths) = ranked.get(&edge.token.start) else {
+                continue;
+            };
+            let extended: Vec<RankedPath> = start_paths
+                .iter()
+                .enumerate()
+                .map(|(rank, (cost, _))| (cost + edge.cost, Some((edge_index, rank))))
+                .collect();
+            let end_paths = ranked.entry(edge.token.end).or_default();
+            end_paths.extend(extended);
+            end_paths.sort_by(|a, b| a.0.total_cmp(&b.0));
+            end_paths.truncate(k);
+        }
+
+        let Some(goal_paths) = ranked.get(&goal) else {
+            return vec![];
+        };
+        goal_paths
+            .iter()
+            .map(|&(cost, back_pointer)| {
+                let mut tokens = vec![];
+                let mut current = back_pointer;
+                while let Some((edge_index, rank)) = current {
+                    let edge = &self.edges[edge_index];
+                    tokens.push(edge.token.clone());
+                    current = ranked[&edge.token.start][rank].1;
+                }
+                tokens.reverse();
+                Segmentation { tokens, cost }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::token::tokens_from_spans;
+
+    fn lattice_of(text: &str, spans: &[(usize, usize)], costs: &[f64]) -> Lattice {
+        let edges = tokens_from_spans(text, spans)
+            .into_iter()
+            .zip(costs)
+            .map(|(token, &cost)| LatticeEdge {
+                token,
+                in_dictionary: true,
+                cost,
+            })
+            .collect();
+        Lattice::new(text.chars().count(), edges)
+    }
+
+    #[test]
+    fn test_nbest() {
+        // ตา|กลม, ตาก|ลม, ตา|ก|ลม
+        let lattice = lattice_of(
+            "ตากลม",
+            &[(0, 2), (0, 3), (2, 3), (2, 5), (3, 5)],
+            &[1.0, 2.5, 4.0, 1.0, 0.5],
+        );
+        assert_eq!(lattice.nodes, vec![0, 2, 3, 5]);
+
+        let nbest = lattice.nbest(5);
+        let texts: Vec<Vec<&str>> = nbest
+            .iter()
+            .map(|segmentation| segmentation.tokens.iter().map(|token| token.text.as_str()).collect())
+            .collect();
+        assert_eq!(texts, vec![vec!["ตา", "กลม"], vec!["ตาก", "ลม"], vec!["ตา", "ก", "ลม"]]);
+        assert_eq!(nbest.iter().map(|s| s.cost).collect::<Vec<_>>(), vec![2.0, 3.0, 5.5]);
+        assert_eq!(lattice.nbest(1).len(), 1);
+        assert!(lattice.nbest(0).is_empty());
+    }
+
+    #[test]
+    fn test_nbest_empty_lattice() {
+        let nbest = Lattice::default().nbest(3);
+        assert_eq!(nbest.len(), 1);
+        assert!(nbest[0].tokens.is_empty());
+    }
+}
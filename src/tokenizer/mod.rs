@@ -1,5 +1,15 @@
 pub mod newmm;
+pub mod sentence;
+pub mod stream;
+pub mod syllable;
 pub mod tcc;
+pub mod bpe;
+pub mod compiled_dict;
+pub mod completion;
 pub mod dict_reader;
+pub mod dictionary;
+pub mod lattice;
+pub mod token;
+pub mod token_patterns;
 pub mod tokenizer_trait;
 pub mod trie_char;
@@ -1,16 +1,31 @@
-use std::{collections::VecDeque, error::Error, fmt::Display, path::PathBuf};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::Display,
+    io::BufRead,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 use super::{
+    compiled_dict::CompiledDictionary,
+    completion::{completions, rank_completions, Completion},
     dict_reader::{create_dict_trie, DictSource},
+    dictionary::Dictionary,
     tcc::tcc_tokenizer,
+    lattice::{Lattice, LatticeEdge, Segmentation},
+    stream::TokenStream,
+    trie_char::TrieChar,
+    token::{tokens_from_spans, Token},
+    token_patterns::TokenPatterns,
     tokenizer_trait::Tokenizer,
-    trie_char::TrieChar as Trie,
 };
 use crate::bytes_str::custom_string::{CustomStringBytesSlice, FixedCharsLengthByteSlice};
 use crate::bytes_str::custom_regex::regex_pattern_to_custom_pattern;
-use crate::bytes_str::custom_string::{rfind_space_char_index, CustomString, BYTES_PER_CHAR};
+use crate::bytes_str::custom_string::{rfind_space_char_index, CustomString};
+use crate::error::{ThongnaError, ThongnaResult};
+use crate::normalize::Normalizer;
 
-use anyhow::Result as AnyResult;
 use binary_heap_plus::{BinaryHeap, MinComparator};
 use lazy_static::lazy_static;
 use rayon::prelude::*;
@@ -22,29 +37,11 @@ const MAX_GRAPH_SIZE: usize = 50;
 const TEXT_SCAN_POINT: usize = 120;
 const TEXT_SCAN_LEFT: usize = 20;
 const TEXT_SCAN_RIGHT: usize = 20;
-const TEXT_SCAN_BEGIN: usize = TEXT_SCAN_POINT - TEXT_SCAN_LEFT;
-const TEXT_SCAN_END: usize = TEXT_SCAN_POINT + TEXT_SCAN_RIGHT;
-
-type CharacterIndex = usize;
 
-const NON_THAI_READABLE_PATTERN: &[&str; 5] = &[
-    r"(?x)^[-a-zA-Z]+",
-    r"(?x)^[0-9]+([,\.][0-9]+)*",
-    r"(?x)^[๐-๙]+([,\.][๐-๙]+)*",
-    r"(?x)^[\ \t]+",
-    r"(?x)^\r?\n",
-];
+/// Number of vertices a graph search visits between two deadline checks
+const DEADLINE_CHECK_INTERVAL: usize = 64;
 
-lazy_static! {
-    static ref NON_THAI_PATTERN: Regex = Regex::new(
-        &NON_THAI_READABLE_PATTERN
-            .iter()
-            .map(|p| regex_pattern_to_custom_pattern(p).unwrap())
-            .collect::<Vec<_>>()
-            .join("|")
-    )
-    .unwrap();
-}
+type CharacterIndex = usize;
 
 lazy_static! {
     static ref THAI_TWOCHARS_PATTERN: Regex =
@@ -85,28 +82,292 @@ impl Display for BFSSearchError {
 
 impl Error for BFSSearchError {}
 
+impl From<BFSSearchError> for ThongnaError {
+    fn from(error: BFSSearchError) -> Self {
+        ThongnaError::Segmentation(error.to_string())
+    }
+}
+
+/// How newmm chooses one path through the graph of candidate words
+/// between two unambiguous cut points.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PathSelection {
+    /// The first path found by breadth-first search, i.e. the one with the fewest tokens.
+    #[default]
+    FewestTokens,
+    /// The path with the lowest sum of word costs, where the cost of a word is
+    /// its negative log probability estimated from dictionary frequencies
+    /// (add-one smoothed, so words without a frequency still get a finite cost).
+    LowestCost,
+}
+
+/// Limits of newmm segmentation, and the safe and parallel modes used
+/// when a call does not choose them.
+///
+/// Safe mode splits a text of at least `text_scan_point + text_scan_right`
+/// characters into parts segmented on their own. Each part ends at a cut
+/// point found between `text_scan_point - text_scan_left` and
+/// `text_scan_point + text_scan_right` characters from its start: after the
+/// last space there, else before the longest word found there.
+///
+/// ```
+/// use std::time::Duration;
+/// use thongna::tokenizer::newmm::NewmmConfig;
+///
+/// let config = NewmmConfig::new()
+///     .with_text_scan_point(200)
+///     .with_safe(true)
+///     .with_timeout(Some(Duration::from_millis(50)));
+/// assert_eq!(config.text_scan_end(), 220);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NewmmConfig {
+    max_graph_size: usize,
+    text_scan_point: usize,
+    text_scan_left: usize,
+    text_scan_right: usize,
+    safe: bool,
+    parallel: bool,
+    timeout: Option<Duration>,
+}
+
+impl Default for NewmmConfig {
+    fn default() -> Self {
+        Self {
+            max_graph_size: MAX_GRAPH_SIZE,
+            text_scan_point: TEXT_SCAN_POINT,
+            text_scan_left: TEXT_SCAN_LEFT,
+            text_scan_right: TEXT_SCAN_RIGHT,
+            safe: false,
+            parallel: false,
+            timeout: None,
+        }
+    }
+}
+
+impl NewmmConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of candidate edges after which the graph between
+    /// two cut points stops growing
+    pub fn with_max_graph_size(mut self, max_graph_size: usize) -> Self {
+        self.max_graph_size = max_graph_size;
+        self
+    }
+
+    /// Usual length of a safe-mode part, in characters
+    pub fn with_text_scan_point(mut self, text_scan_point: usize) -> Self {
+        self.text_scan_point = text_scan_point;
+        self
+    }
+
+    /// How far before the scan point a cut point may be, in characters
+    pub fn with_text_scan_left(mut self, text_scan_left: usize) -> Self {
+        self.text_scan_left = text_scan_left;
+        self
+    }
+
+    /// How far after the scan point a cut point may be, in characters
+    pub fn with_text_scan_right(mut self, text_scan_right: usize) -> Self {
+        self.text_scan_right = text_scan_right;
+        self
+    }
+
+    /// Safe mode of [`NewmmTokenizer::segment_with_config_modes`] and of the
+    /// Python functions called without `safe`. Methods taking a `safe`
+    /// argument use that argument instead.
+    pub fn with_safe(mut self, safe: bool) -> Self {
+        self.safe = safe;
+        self
+    }
+
+    /// Parallel mode of [`NewmmTokenizer::segment_with_config_modes`] and of
+    /// the Python functions called without `parallel`. Methods taking a
+    /// `parallel` argument use that argument instead.
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Longest time segmenting one document may take, after which
+    /// segmentation fails with [`ThongnaError::Timeout`]. `None` for no limit.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn max_graph_size(&self) -> usize {
+        self.max_graph_size
+    }
+
+    pub fn text_scan_point(&self) -> usize {
+        self.text_scan_point
+    }
+
+    pub fn text_scan_left(&self) -> usize {
+        self.text_scan_left
+    }
+
+    pub fn text_scan_right(&self) -> usize {
+        self.text_scan_right
+    }
+
+    pub fn safe(&self) -> bool {
+        self.safe
+    }
+
+    pub fn parallel(&self) -> bool {
+        self.parallel
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Start of the window searched for a safe-mode cut point
+    pub fn text_scan_begin(&self) -> usize {
+        self.text_scan_point.saturating_sub(self.text_scan_left)
+    }
+
+    /// End of the window searched for a safe-mode cut point,
+    /// also the length from which safe mode splits a text
+    pub fn text_scan_end(&self) -> usize {
+        self.text_scan_point + self.text_scan_right
+    }
+}
+
+/// Time after which the segmentation of a document gives up
+#[derive(Clone, Copy, Debug)]
+struct Deadline {
+    at: Instant,
+    timeout: Duration,
+}
+
+impl Deadline {
+    fn start(timeout: Option<Duration>) -> Option<Self> {
+        timeout.map(|timeout| Deadline {
+            at: Instant::now() + timeout,
+            timeout,
+        })
+    }
+
+    #[inline(always)]
+    fn passed(deadline: Option<Self>) -> bool {
+        deadline.is_some_and(|deadline| Instant::now() >= deadline.at)
+    }
+
+    #[inline(always)]
+    fn check(deadline: Option<Self>) -> ThongnaResult<()> {
+        match deadline {
+            Some(deadline) if Instant::now() >= deadline.at => {
+                Err(ThongnaError::Timeout(deadline.timeout))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct NewmmTokenizer {
-    dict: Box<Trie>,
+    dict: Box<dyn Dictionary>,
+    path_selection: PathSelection,
+    token_patterns: TokenPatterns,
+    config: NewmmConfig,
 }
 
 impl NewmmTokenizer {
     /// Create a new tokenizer using a dictionary from a text file
-    pub fn new(dict_path: &str) -> Self {
-        NewmmTokenizer {
-            dict: Box::from(
-                create_dict_trie(DictSource::FilePath(PathBuf::from(dict_path))).unwrap(),
-            ),
-        }
+    pub fn new(dict_path: &str) -> ThongnaResult<Self> {
+        Ok(Self::from_dictionary(Box::from(create_dict_trie(
+            DictSource::FilePath(PathBuf::from(dict_path)),
+        )?)))
     }
 
     /// Create a new tokenizer using a dictionary from a vector of Strings
     pub fn from_word_list(word_list: Vec<String>) -> Self {
+        let words: Vec<CustomString> = word_list.iter().map(|word| CustomString::new(word)).collect();
+        Self::from_dictionary(Box::from(TrieChar::new(&words)))
+    }
+
+    /// Create a new tokenizer using a dictionary from (word, frequency) pairs
+    pub fn from_weighted_word_list(word_list: Vec<(String, u64)>) -> Self {
+        let words: Vec<(CustomString, u64)> = word_list
+            .iter()
+            .map(|(word, frequency)| (CustomString::new(word), *frequency))
+            .collect();
+        Self::from_dictionary(Box::from(TrieChar::new_with_frequencies(&words)))
+    }
+
+    /// Create a new tokenizer using a dictionary compiled by
+    /// [`compile_dict`](super::compiled_dict::compile_dict), memory-mapped from a file
+    pub fn from_compiled_dict(compiled_dict_path: &str) -> ThongnaResult<Self> {
+        Ok(Self::from_dictionary(Box::from(CompiledDictionary::open(
+            Path::new(compiled_dict_path),
+        )?)))
+    }
+
+    /// Create a new tokenizer using any dictionary
+    pub fn from_dictionary(dict: Box<dyn Dictionary>) -> Self {
         NewmmTokenizer {
-            dict: Box::from(create_dict_trie(DictSource::WordList(word_list)).unwrap()),
+            dict,
+            path_selection: PathSelection::default(),
+            token_patterns: TokenPatterns::default(),
+            config: NewmmConfig::default(),
         }
     }
 
+    /// Use the given path selection for every following segmentation
+    pub fn with_path_selection(mut self, path_selection: PathSelection) -> Self {
+        self.path_selection = path_selection;
+        self
+    }
+
+    pub fn set_path_selection(&mut self, path_selection: PathSelection) {
+        self.path_selection = path_selection;
+    }
+
+    pub fn path_selection(&self) -> PathSelection {
+        self.path_selection
+    }
+
+    /// Take the tokens matched by `token_patterns` whole, before any dictionary lookup
+    pub fn with_token_patterns(mut self, token_patterns: TokenPatterns) -> Self {
+        self.token_patterns = token_patterns;
+        self
+    }
+
+    pub fn set_token_patterns(&mut self, token_patterns: TokenPatterns) {
+        self.token_patterns = token_patterns;
+    }
+
+    pub fn token_patterns(&self) -> &TokenPatterns {
+        &self.token_patterns
+    }
+
+    /// Use the given limits for every following segmentation
+    pub fn with_config(mut self, config: NewmmConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn set_config(&mut self, config: NewmmConfig) {
+        self.config = config;
+    }
+
+    pub fn config(&self) -> &NewmmConfig {
+        &self.config
+    }
+
+    /// Add words with their frequencies to the tokenizer's dictionary,
+    /// replacing the frequency of words that already exist
+    pub fn add_weighted_word(&mut self, word_list: &[(&str, u64)]) {
+        word_list.iter().for_each(|(word, frequency)| {
+            self.dict.add_with_frequency(&CustomString::new(word), *frequency);
+        });
+    }
+
     /// Add words to the tokenizer's dictionary
     pub fn add_word(&mut self, word_list: &[&str]) {
         word_list.iter().for_each(|word| {
@@ -121,53 +382,176 @@ impl NewmmTokenizer {
         });
     }
 
+    /// Number of words in the tokenizer's dictionary
+    pub fn amount_of_words(&self) -> usize {
+        self.dict.amount_of_words()
+    }
+
+    /// Every word of the tokenizer's dictionary with its frequency, in no particular order
+    pub fn words(&self) -> Vec<(String, u64)> {
+        self.dict.words()
+    }
+
+    pub(crate) fn dictionary(&self) -> &dyn Dictionary {
+        self.dict.as_ref()
+    }
+
+    /// Up to `limit` words of the tokenizer's dictionary starting with `prefix`,
+    /// the most frequent first
+    pub fn complete(&self, prefix: &str, limit: usize) -> Vec<Completion> {
+        rank_completions(completions(self.dict.as_ref(), prefix, 0), limit)
+    }
+
+    /// Up to `limit` words of the tokenizer's dictionary starting with `prefix`
+    /// give or take `max_distance` edits, the closest first, then the most frequent
+    pub fn complete_fuzzy(&self, prefix: &str, max_distance: usize, limit: usize) -> Vec<Completion> {
+        rank_completions(completions(self.dict.as_ref(), prefix, max_distance), limit)
+    }
+
+    /// Path with the fewest edges from `start` to `goal`. Every vertex is
+    /// queued once, keeping the vertex it was first reached from.
     #[inline(always)]
     fn bfs_paths_graph(
         graph: &HashMap<CharacterIndex, Vec<CharacterIndex>>,
         start: CharacterIndex,
         goal: CharacterIndex,
-        current_queue: &mut VecDeque<(usize, Vec<usize>)>,
-    ) -> AnyResult<Vec<CharacterIndex>> {
+        current_queue: &mut VecDeque<CharacterIndex>,
+        deadline: Option<Deadline>,
+    ) -> ThongnaResult<Vec<CharacterIndex>> {
         current_queue.clear();
 
-        let mut init_path = Vec::with_capacity(goal - start);
-        init_path.push(start);
-        current_queue.push_back((start, init_path));
-
-        while let Some((vertex, path)) = current_queue.pop_front() {
-            if let Some(idk) = graph.get(&vertex) {
-                for position in idk {
-                    if *position != goal {
-                        let mut appended_path = path.clone();
-                        appended_path.push(*position);
-                        current_queue.push_back((*position, appended_path));
-                    } else {
-                        let mut appended_path = path;
-                        appended_path.push(*position);
-                        return Ok(appended_path);
-                    };
+        // vertex -> previous vertex on the path from start
+        let mut previous: HashMap<CharacterIndex, CharacterIndex> = HashMap::default();
+        previous.insert(start, start);
+        current_queue.push_back(start);
+
+        let mut popped: usize = 0;
+        while let Some(vertex) = current_queue.pop_front() {
+            popped += 1;
+            if popped % DEADLINE_CHECK_INTERVAL == 0 {
+                Deadline::check(deadline)?;
+            }
+            if let Some(ends) = graph.get(&vertex) {
+                for &position in ends {
+                    if previous.contains_key(&position) {
+                        continue;
+                    }
+                    previous.insert(position, vertex);
+                    if position == goal {
+                        let mut path = vec![goal];
+                        let mut vertex = goal;
+                        while vertex != start {
+                            vertex = previous[&vertex];
+                            path.push(vertex);
+                        }
+                        path.reverse();
+                        return Ok(path);
+                    }
+                    current_queue.push_back(position);
                 }
-            };
+            }
         }
 
         Err(BFSSearchError::new(graph, start, goal).into())
     }
 
+    /// Cost of a graph edge covering `word` (custom string bytes).
+    #[inline(always)]
+    fn word_cost(custom_dict: &dyn Dictionary, word: &CustomStringBytesSlice) -> f64 {
+        let smoothed_total = (custom_dict.total_frequency() + custom_dict.amount_of_words() as u64 + 1) as f64;
+        let smoothed_frequency = custom_dict.frequency(word).unwrap_or_default() + 1;
+        (smoothed_total / smoothed_frequency as f64).ln()
+    }
+
+    /// Edges always point forward, so visiting vertices in increasing
+    /// order relaxes every edge after all edges leading into its start.
     #[inline(always)]
-    fn one_cut<'a>(
-        input: &'a CustomString,
-        custom_dict: &Trie,
-    ) -> AnyResult<Vec<&'a CustomStringBytesSlice>> {
+    fn lowest_cost_path(
+        graph: &HashMap<CharacterIndex, Vec<CharacterIndex>>,
+        start: CharacterIndex,
+        goal: CharacterIndex,
+        edge_cost: impl Fn(CharacterIndex, CharacterIndex) -> f64,
+    ) -> ThongnaResult<Vec<CharacterIndex>> {
+        // vertex -> (cost from start, previous vertex)
+        let mut best: HashMap<CharacterIndex, (f64, CharacterIndex)> = HashMap::default();
+        let mut vertices: BinaryHeap<CharacterIndex, MinComparator> = BinaryHeap::new_min();
+        best.insert(start, (0.0, start));
+        vertices.push(start);
+
+        while let Some(vertex) = vertices.pop() {
+            if vertex == goal {
+                break;
+            }
+            let (vertex_cost, _) = best[&vertex];
+            if let Some(ends) = graph.get(&vertex) {
+                for &end in ends.iter().filter(|&&end| end <= goal) {
+                    let cost = vertex_cost + edge_cost(vertex, end);
+                    match best.get(&end) {
+                        Some((known_cost, _)) if *known_cost <= cost => {}
+                        Some(_) => {
+                            best.insert(end, (cost, vertex));
+                        }
+                        None => {
+                            best.insert(end, (cost, vertex));
+                            vertices.push(end);
+                        }
+                    }
+                }
+            }
+        }
+
+        if !best.contains_key(&goal) {
+            return Err(BFSSearchError::new(graph, start, goal).into());
+        }
+        let mut path = vec![goal];
+        let mut vertex = goal;
+        while vertex != start {
+            vertex = best[&vertex].1;
+            path.push(vertex);
+        }
+        path.reverse();
+        Ok(path)
+    }
+
+    /// Returns character spans of tokens, relative to `input`.
+    #[inline(always)]
+    fn one_cut(
+        &self,
+        input: &CustomString,
+        deadline: Option<Deadline>,
+    ) -> ThongnaResult<Vec<(CharacterIndex, CharacterIndex)>> {
+        self.one_cut_with_graph(input, deadline).map(|(spans, _)| spans)
+    }
+
+    /// Returns character spans of tokens together with the graph of
+    /// every candidate edge considered on the way, relative to `input`.
+    #[inline(always)]
+    #[allow(clippy::type_complexity)]
+    fn one_cut_with_graph(
+        &self,
+        input: &CustomString,
+        deadline: Option<Deadline>,
+    ) -> ThongnaResult<(
+        Vec<(CharacterIndex, CharacterIndex)>,
+        HashMap<CharacterIndex, Vec<CharacterIndex>>,
+    )> {
         let text = input;
+        let custom_dict = self.dict.as_ref();
+        let token_patterns = &self.token_patterns;
         let input_char_len = text.chars_len();
-        let mut reused_queue: VecDeque<(usize, Vec<usize>)> = VecDeque::with_capacity(10);
+        let mut reused_queue: VecDeque<CharacterIndex> = VecDeque::with_capacity(10);
         let mut graph_size: usize = 0;
         let mut graph: HashMap<CharacterIndex, Vec<CharacterIndex>> = HashMap::default();
         graph.reserve(input_char_len / 10);
-        let mut result_str: Vec<&CustomStringBytesSlice> = Vec::with_capacity(input_char_len / 10);
+        let mut result_spans: Vec<(CharacterIndex, CharacterIndex)> =
+            Vec::with_capacity(input_char_len / 10);
 
         // all position should be refered as character index
-        let valid_position = tcc_tokenizer::tcc_pos(text.raw_content());
+        // valid_position[i]: whether a cluster ends at character i
+        let mut valid_position = vec![false; input_char_len + 1];
+        for position in tcc_tokenizer::tcc_end_positions(text.raw_content()) {
+            valid_position[position] = true;
+        }
         let text_length = input_char_len;
         let mut position_list: BinaryHeap<CharacterIndex, MinComparator> = BinaryHeap::new_min();
         let mut existing_candidate: HashSet<CharacterIndex> = HashSet::default();
@@ -180,16 +564,22 @@ impl NewmmTokenizer {
             if begin_position >= text_length {
                 break;
             }
+            Deadline::check(deadline)?;
             
             let sub_text_prefix = text.substring(begin_position, text.chars_len());
-            let prefixes = Trie::prefix_ref(&sub_text_prefix, custom_dict);
+            // a user or preset pattern match is a token of its own,
+            // whatever the dictionary holds
+            let prefixes = match token_patterns.match_len(sub_text_prefix.raw_content()) {
+                Some(_) => vec![],
+                None => custom_dict.prefix_ref(&sub_text_prefix),
+            };
             
             for word in prefixes {
                 let word_length = word.chars_len();
                 let end_position_candidate = begin_position + word_length;
-                if valid_position.contains(&end_position_candidate) {
+                if valid_position[end_position_candidate] {
                     graph.entry(begin_position)
-                        .or_insert_with(Vec::new)
+                        .or_default()
                         .push(end_position_candidate);
 
                     graph_size += 1;
@@ -197,7 +587,7 @@ impl NewmmTokenizer {
                         existing_candidate.insert(end_position_candidate);
                         position_list.push(end_position_candidate);
                     }
-                    if graph_size > MAX_GRAPH_SIZE {
+                    if graph_size > self.config.max_graph_size {
                         break;
                     }
                 }
@@ -206,53 +596,61 @@ impl NewmmTokenizer {
             let position_list_length = position_list.len();
             if position_list_length == 1 {
                 if let Some(first_position_list) = position_list.peek() {
-                    let group_of_end_position_candidate = Self::bfs_paths_graph(
-                        &graph,
-                        end_position,
-                        *first_position_list,
-                        &mut reused_queue,
-                    )?;
+                    let group_of_end_position_candidate = match self.path_selection {
+                        PathSelection::FewestTokens => Self::bfs_paths_graph(
+                            &graph,
+                            end_position,
+                            *first_position_list,
+                            &mut reused_queue,
+                            deadline,
+                        )?,
+                        PathSelection::LowestCost => Self::lowest_cost_path(
+                            &graph,
+                            end_position,
+                            *first_position_list,
+                            |start, end| {
+                                Self::word_cost(
+                                    custom_dict,
+                                    text.raw_content().slice_by_char_indice(start, end),
+                                )
+                            },
+                        )?,
+                    };
                     graph_size = 0; // reset our graph
 
                     for position in group_of_end_position_candidate.iter().skip(1) {
-                        let token_bytes = text.substring_as_bytes(end_position, *position);
-                        result_str.push(token_bytes);
+                        result_spans.push((end_position, *position));
                         end_position = *position;
                     }
                 }
             } else if position_list_length == 0 {
                 // no candidate, deal with non-dict word
-                match NON_THAI_PATTERN.find(sub_text_prefix.raw_content()) {
-                    Some(match_point) => {
-                        let matched_start_char_index = match_point.start() / BYTES_PER_CHAR;
-                        let matched_end_char_index = match_point.end() / BYTES_PER_CHAR;
-                        end_position = begin_position
-                            + sub_text_prefix
-                                .raw_content()
-                                .slice_by_char_indice(
-                                    matched_start_char_index,
-                                    matched_end_char_index,
-                                )
-                                .chars_len();
+                match token_patterns.fallback_match_len(sub_text_prefix.raw_content()) {
+                    Some(matched_length) => {
+                        end_position = begin_position + matched_length;
                     }
                     None => {
                         end_position = (begin_position + 1..text_length)
                             .find(|&position| {
-                                if valid_position.contains(&position) {
+                                // stop scanning, the next round fails
+                                if Deadline::passed(deadline) {
+                                    return true;
+                                }
+                                if valid_position[position] {
                                     let prefix = text.substring(position, text_length);
-                                    let list_of_prefixes = Trie::prefix_ref(&prefix, custom_dict);
+                                    let list_of_prefixes = custom_dict.prefix_ref(&prefix);
                                     let valid_words: Vec<&[u8]> = list_of_prefixes
                                         .into_par_iter()
                                         .filter(|word| {
                                             let new_position = position + word.chars_len();
-                                            valid_position.contains(&new_position) && !THAI_TWOCHARS_PATTERN.is_match(word)
+                                            valid_position[new_position] && !THAI_TWOCHARS_PATTERN.is_match(word)
                                         })
                                         .collect();
 
                                     if !valid_words.is_empty() {
                                         return true;
                                     }
-                                    NON_THAI_PATTERN.is_match(prefix.raw_content())
+                                    token_patterns.fallback_match_len(prefix.raw_content()).is_some()
                                 } else {
                                     false
                                 }
@@ -262,103 +660,580 @@ impl NewmmTokenizer {
                 }
 
                 graph.entry(begin_position)
-                    .or_insert_with(Vec::new)
+                    .or_default()
                     .push(end_position);
                 graph_size += 1;
-                let token_bytes = text.substring_as_bytes(begin_position, end_position);
-                result_str.push(token_bytes);
+                result_spans.push((begin_position, end_position));
                 position_list.push(end_position);
                 existing_candidate.insert(end_position);
             }
         }
-        Ok(result_str)
+        Ok((result_spans, graph))
+    }
+
+    /// End of the first safe-mode part of `txt`, which has at least
+    /// `text_scan_end` characters, or `None` if `txt` is one part.
+    ///
+    /// The cut point only depends on the first `text_scan_end` characters
+    /// of `txt`, and on the pattern matches starting there.
+    fn safe_mode_cut(
+        &self,
+        txt: &CustomString,
+        deadline: Option<Deadline>,
+    ) -> ThongnaResult<Option<CharacterIndex>> {
+        let text_scan_begin = self.config.text_scan_begin();
+        let text_scan_end = self.config.text_scan_end();
+        let sample = txt.substring(text_scan_begin, text_scan_end);
+
+        let cut_pos = match rfind_space_char_index(sample.raw_content()) {
+            Some(space_char_index) => space_char_index + 1,
+            None => {
+                let word_spans = self.one_cut(&sample, deadline)?;
+                let token_max_index = word_spans
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, (start, end))| end - start)
+                    .map(|(index, _)| index)
+                    .unwrap_or_default();
+
+                text_scan_begin
+                    + word_spans[..token_max_index]
+                        .iter()
+                        .map(|(start, end)| end - start)
+                        .sum::<usize>()
+            }
+        };
+        // never cut through a pattern match
+        let pattern_spans = self.token_patterns.spans(txt.raw_content(), text_scan_end);
+        let cut_pos = match pattern_spans
+            .get(pattern_spans.partition_point(|&(_, end)| end <= cut_pos))
+        {
+            Some(&(start, _)) if 0 < start && start < cut_pos => start,
+            Some(&(start, end)) if start < cut_pos => end,
+            _ => cut_pos,
+        }
+        // an empty scan window still moves on
+        .max(1);
+        Ok((cut_pos < txt.chars_len()).then_some(cut_pos))
+    }
+
+    /// Splits a long input into parts at safe-mode cut points,
+    /// returned as character spans relative to `input`.
+    fn safe_mode_parts(
+        &self,
+        input: &CustomString,
+        deadline: Option<Deadline>,
+    ) -> ThongnaResult<Vec<(CharacterIndex, CharacterIndex)>> {
+        let mut parts: Vec<(CharacterIndex, CharacterIndex)> =
+            Vec::with_capacity(input.chars_len() / 10);
+        let mut part_begin: CharacterIndex = 0;
+        let mut txt = input.substring(0, input.chars_len());
+        while txt.chars_len() >= self.config.text_scan_end() {
+            let Some(cut_pos) = self.safe_mode_cut(&txt, deadline)? else {
+                break;
+            };
+            parts.push((part_begin, part_begin + cut_pos));
+            part_begin += cut_pos;
+            txt = txt.substring(cut_pos, txt.chars_len());
+        }
+        if !txt.is_empty() {
+            parts.push((part_begin, input.chars_len()));
+        }
+        Ok(parts)
+    }
+
+    /// End of the first safe-mode part of `input`, or `None` if `input`
+    /// is one part, for segmenting text that arrives in pieces.
+    pub(crate) fn first_safe_mode_cut(
+        &self,
+        input: &CustomString,
+    ) -> ThongnaResult<Option<CharacterIndex>> {
+        if input.chars_len() < self.config.text_scan_end() {
+            return Ok(None);
+        }
+        self.safe_mode_cut(input, Deadline::start(self.config.timeout))
+    }
+
+    /// Character spans of the tokens of one safe-mode part, relative to `part`.
+    pub(crate) fn segment_part(
+        &self,
+        part: &CustomString,
+    ) -> ThongnaResult<Vec<(CharacterIndex, CharacterIndex)>> {
+        self.one_cut(part, Deadline::start(self.config.timeout))
     }
 
+    /// Returns character spans of tokens, relative to `input`.
     fn internal_segment(
+        &self,
         input: &CustomString,
-        custom_dict: &Trie,
         safe: bool,
         parallel: bool,
-    ) -> AnyResult<Vec<String>> {
+    ) -> ThongnaResult<Vec<(CharacterIndex, CharacterIndex)>> {
         if input.is_empty() {
             return Ok(vec![]);
         }
-        if !safe || input.chars_len() < TEXT_SCAN_END {
-            let result = Self::one_cut(input, custom_dict)?;
-            Ok(if parallel {
-                result
-                    .into_par_iter()
-                    .map(CustomString::convert_raw_bytes_to_std_string)
-                    .collect()
-            } else {
-                result
+        let deadline = Deadline::start(self.config.timeout);
+        if !safe || input.chars_len() < self.config.text_scan_end() {
+            return self.one_cut(input, deadline);
+        }
+
+        let parts = self.safe_mode_parts(input, deadline)?;
+        let cut_part = |&(part_begin, part_end): &(CharacterIndex, CharacterIndex)| {
+            self.one_cut(&input.substring(part_begin, part_end), deadline).map(|spans| {
+                spans
                     .into_iter()
-                    .map(CustomString::convert_raw_bytes_to_std_string)
-                    .collect()
+                    .map(|(start, end)| (part_begin + start, part_begin + end))
+                    .collect::<Vec<_>>()
             })
+        };
+        let part_spans: Vec<Vec<(CharacterIndex, CharacterIndex)>> = if parallel {
+            parts.par_iter().map(cut_part).collect::<ThongnaResult<_>>()?
         } else {
-            let mut txt = input.substring(0, input.chars_len());
-            let mut txt_parts: Vec<CustomString> = Vec::with_capacity(txt.chars_len() / 10);
-            while txt.chars_len() >= TEXT_SCAN_END {
-                let sample = txt.substring(TEXT_SCAN_BEGIN, TEXT_SCAN_END);
-
-                let cut_pos = rfind_space_char_index(sample.raw_content())
-                    .map(|space_char_index| space_char_index + 1)
-                    .unwrap_or_else(|| {
-                        let word_tokens = Self::one_cut(&sample, custom_dict).unwrap();
-                        let (token_max_index, _) = word_tokens
-                            .iter()
-                            .enumerate()
-                            .max_by_key(|(_, token)| token.chars_len())
-                            .unwrap();
-                        
-                        TEXT_SCAN_BEGIN + word_tokens[..token_max_index].iter().map(|token| token.chars_len()).sum::<usize>()
-                    });
-
-                txt_parts.push(txt.substring(0, cut_pos));
-                txt = txt.substring(cut_pos, txt.chars_len());
-            }
-            if !txt.is_empty() {
-                txt_parts.push(txt);
-            }
+            parts.iter().map(cut_part).collect::<ThongnaResult<_>>()?
+        };
+        Ok(part_spans.into_iter().flatten().collect())
+    }
 
-            Ok(if parallel {
-                txt_parts
-                    .par_iter()
-                    .flat_map(|part| -> AnyResult<_> {
-                        let bind_part = &part.substring(0, part.chars_len());
-                        let words = Self::one_cut(bind_part, custom_dict)?;
-                        Ok(words
-                            .into_par_iter()
-                            .map(CustomString::convert_raw_bytes_to_std_string)
-                            .collect::<Vec<String>>())
-                    })
-                    .flatten()
-                    .collect()
-            } else {
-                txt_parts
-                    .iter()
-                    .flat_map(|part| -> AnyResult<_> {
-                        Ok(
-                            Self::one_cut(&part.substring(0, part.chars_len()), custom_dict)?
-                                .iter()
-                                .map(|word| CustomString::convert_raw_bytes_to_std_string(word))
-                                .collect::<Vec<String>>(),
-                        )
-                    })
-                    .flatten()
-                    .collect()
-            })
+    /// Build the segmentation lattice of `text`: every candidate word
+    /// newmm considers, as edges between character positions.
+    ///
+    /// Edge costs follow the tokenizer's path selection: 1 per token
+    /// for [`PathSelection::FewestTokens`], the word cost for
+    /// [`PathSelection::LowestCost`].
+    pub fn lattice(&self, text: &str) -> ThongnaResult<Lattice> {
+        let input = CustomString::new(text);
+        if input.is_empty() {
+            return Ok(Lattice::default());
         }
+        let (_, graph) =
+            self.one_cut_with_graph(&input, Deadline::start(self.config.timeout))?;
+
+        let mut spans: Vec<(CharacterIndex, CharacterIndex)> = graph
+            .iter()
+            .flat_map(|(&start, ends)| ends.iter().map(move |&end| (start, end)))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        spans.sort_unstable();
+
+        let edges = tokens_from_spans(text, &spans)
+            .into_iter()
+            .map(|token| {
+                let word = input.raw_content().slice_by_char_indice(token.start, token.end);
+                let in_dictionary = self.dict.frequency(word).is_some();
+                let cost = match self.path_selection {
+                    PathSelection::FewestTokens => 1.0,
+                    PathSelection::LowestCost => Self::word_cost(self.dict.as_ref(), word),
+                };
+                LatticeEdge {
+                    token,
+                    in_dictionary,
+                    cost,
+                }
+            })
+            .collect();
+        Ok(Lattice::new(input.chars_len(), edges))
+    }
+
+    /// List the `k` best segmentations of `text`, best first.
+    ///
+    /// Segmentations are ranked by the sum of their lattice edge costs,
+    /// see [`NewmmTokenizer::lattice`].
+    pub fn segment_nbest(&self, text: &str, k: usize) -> ThongnaResult<Vec<Segmentation>> {
+        Ok(self.lattice(text)?.nbest(k))
+    }
+
+    /// Break text into tokens in the safe and parallel modes of the
+    /// tokenizer's config, see [`NewmmConfig::with_safe`] and
+    /// [`NewmmConfig::with_parallel`].
+    pub fn segment_with_config_modes(&self, text: &str) -> ThongnaResult<Vec<String>> {
+        self.segment(text, self.config.safe(), self.config.parallel())
+    }
+
+    /// Break text into tokens, keeping the position of every token.
+    ///
+    /// Each token carries its start/end character indices and UTF-8 byte
+    /// offsets into `text`.
+    pub fn segment_with_offsets(
+        &self,
+        text: &str,
+        safe: bool,
+        parallel: bool,
+    ) -> ThongnaResult<Vec<Token>> {
+        let spans = self.internal_segment(&CustomString::new(text), safe, parallel)?;
+        Ok(tokens_from_spans(text, &spans))
+    }
+
+    /// Normalize text with `normalizer`, then break it into tokens,
+    /// keeping the position of every token in the original `text`.
+    ///
+    /// Each token is the part of `text` its normalized characters came from,
+    /// see [`NormalizedText::alignment`](crate::normalize::NormalizedText::alignment),
+    /// so token texts are not normalized. Tokens made only of characters added
+    /// by normalization, like the spaces around numbers, are left out, and
+    /// characters removed by normalization between two tokens are in neither.
+    pub fn segment_normalized_with_offsets(
+        &self,
+        text: &str,
+        normalizer: &Normalizer,
+        safe: bool,
+        parallel: bool,
+    ) -> ThongnaResult<Vec<Token>> {
+        let normalized = normalizer.normalize_with_alignment(text);
+        let spans: Vec<(usize, usize)> = self
+            .internal_segment(&CustomString::new(&normalized.text), safe, parallel)?
+            .into_iter()
+            .map(|(start, end)| normalized.original_span(start, end))
+            .filter(|(start, end)| start < end)
+            .collect();
+        Ok(tokens_from_spans(text, &spans))
+    }
+
+    /// Break the text of `reader` into tokens as it is read, without holding
+    /// the whole text in memory.
+    ///
+    /// The tokens and their offsets are those of
+    /// `segment_with_offsets(text, true, _)` on the whole text.
+    /// Text that is not valid UTF-8 is an [`ThongnaError::Io`] error.
+    pub fn segment_stream<R: BufRead>(&self, reader: R) -> TokenStream<'_, R> {
+        TokenStream::new(self, reader)
+    }
+
+    /// Break every text of `texts` into tokens, segmenting the texts in parallel.
+    ///
+    /// Whole documents are spread over the rayon thread pool, unlike the
+    /// `parallel` flag of [`Tokenizer::segment`] which works inside one document.
+    pub fn segment_batch<S: AsRef<str> + Sync>(
+        &self,
+        texts: &[S],
+        safe: bool,
+    ) -> ThongnaResult<Vec<Vec<String>>> {
+        texts
+            .par_iter()
+            .map(|text| self.segment(text.as_ref(), safe, false))
+            .collect()
     }
 }
 
 impl Tokenizer for NewmmTokenizer {
-    fn segment(&self, text: &str, safe: bool, parallel: bool) -> AnyResult<Vec<String>> {
-        Self::internal_segment(&CustomString::new(text), &self.dict, safe, parallel)
+    fn segment(&self, text: &str, safe: bool, parallel: bool) -> ThongnaResult<Vec<String>> {
+        let input = CustomString::new(text);
+        let spans = self.internal_segment(&input, safe, parallel)?;
+        let to_string = |&(start, end): &(CharacterIndex, CharacterIndex)| {
+            CustomString::convert_raw_bytes_to_std_string(
+                input.raw_content().slice_by_char_indice(start, end),
+            )
+        };
+        Ok(if parallel {
+            spans.par_iter().map(to_string).collect()
+        } else {
+            spans.iter().map(to_string).collect()
+        })
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DICT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/dataset/words_th.txt");
+
+    #[test]
+    fn test_segment_with_offsets() {
+        let tokenizer = NewmmTokenizer::new(DICT_PATH).unwrap();
+        let text = "ไข่คน2021 臺灣";
+        let tokens = tokenizer.segment_with_offsets(text, false, false).unwrap();
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(texts, tokenizer.segment(text, false, false).unwrap());
+        assert_eq!((tokens[1].start, tokens[1].end), (3, 5));
+        assert_eq!((tokens[1].byte_start, tokens[1].byte_end), (9, 15));
+        for token in &tokens {
+            assert_eq!(&text[token.byte_start..token.byte_end], token.text);
+        }
+    }
+
+    #[test]
+    fn test_add_and_remove_word() {
+        let mut tokenizer = NewmmTokenizer::from_word_list(vec!["ตา".to_string(), "กลม".to_string()]);
+        assert_eq!(tokenizer.amount_of_words(), 2);
+        assert_eq!(tokenizer.segment("ตากลม", false, false).unwrap(), vec!["ตา", "กลม"]);
+
+        tokenizer.add_word(&["ตากลม", " ตา "]);
+        assert_eq!(tokenizer.amount_of_words(), 3);
+        assert_eq!(tokenizer.segment("ตากลม", false, false).unwrap(), vec!["ตากลม"]);
+
+        tokenizer.remove_word(&["ตากลม", "ไม่มี"]);
+        assert_eq!(tokenizer.amount_of_words(), 2);
+        let mut words = tokenizer.words();
+        words.sort();
+        assert_eq!(words, vec![("กลม".to_string(), 0), ("ตา".to_string(), 0)]);
+        assert_eq!(tokenizer.segment("ตากลม", false, false).unwrap(), vec!["ตา", "กลม"]);
+    }
+
+    #[test]
+    fn test_complete() {
+        let mut tokenizer = NewmmTokenizer::new(DICT_PATH).unwrap();
+        tokenizer.add_weighted_word(&[("โรงเรียนวัด", 10)]);
+        let completions = tokenizer.complete("โรงเรีย", 5);
+        assert_eq!(completions.len(), 5);
+        assert_eq!(completions[0].word, "โรงเรียนวัด");
+        assert!(completions.iter().all(|completion| completion.word.starts_with("โรงเรีย")));
+
+        let completions = tokenizer.complete_fuzzy("โรงเรยน", 1, 3);
+        assert_eq!(completions[0].word, "โรงเรียนวัด");
+        assert!(completions.iter().all(|completion| completion.distance == 1));
+        assert!(tokenizer.complete("โรงเรยน", 3).is_empty());
+    }
+
+    #[test]
+    fn test_segment_batch() {
+        let tokenizer = NewmmTokenizer::new(DICT_PATH).unwrap();
+        let long_text = "ด้านหน้า".repeat(20);
+        let texts = vec!["ไข่คน2021", "", "ไต้หวัน (แป่ะเอ๋ยี้: Tâi-oân)", &long_text];
+        let segmented = tokenizer.segment_batch(&texts, true).unwrap();
+        assert_eq!(segmented.len(), texts.len());
+        for (text, tokens) in texts.iter().zip(segmented) {
+            assert_eq!(tokens, tokenizer.segment(text, true, false).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_safe_mode_keeps_text() {
+        let tokenizer = NewmmTokenizer::new(DICT_PATH).unwrap();
+        let text = "ด้านหน้า".repeat(20) + &"ก".repeat(40) + " ไต้หวัน (แป่ะเอ๋ยี้: Tâi-oân)";
+        for parallel in [false, true] {
+            let tokens = tokenizer.segment_with_offsets(&text, true, parallel).unwrap();
+            let mut expected_start = 0;
+            for token in &tokens {
+                assert_eq!(token.start, expected_start);
+                assert_eq!(&text[token.byte_start..token.byte_end], token.text);
+                expected_start = token.end;
+            }
+            assert_eq!(expected_start, text.chars().count());
+        }
+    }
+
+    #[test]
+    fn test_lowest_cost_path_selection() {
+        let word_list = vec![
+            ("ตา".to_string(), 1),
+            ("กลม".to_string(), 1),
+            ("ตาก".to_string(), 500),
+            ("ลม".to_string(), 800),
+        ];
+        let tokenizer = NewmmTokenizer::from_weighted_word_list(word_list);
+        assert_eq!(tokenizer.segment("ตากลม", false, false).unwrap(), vec!["ตา", "กลม"]);
+
+        let tokenizer = tokenizer.with_path_selection(PathSelection::LowestCost);
+        assert_eq!(tokenizer.segment("ตากลม", false, false).unwrap(), vec!["ตาก", "ลม"]);
+        assert_eq!(
+            tokenizer.segment("ตากลม hi ตากลม", true, false).unwrap(),
+            vec!["ตาก", "ลม", " ", "hi", " ", "ตาก", "ลม"]
+        );
+    }
+
+    #[test]
+    fn test_lowest_cost_without_frequencies() {
+        let tokenizer = NewmmTokenizer::new(DICT_PATH).unwrap();
+        let text = "ค่าจ้างที่ได้รับต้องทำให้แรงงานสามารถเลี้ยงดูตัวเองและครอบครัว";
+        let fewest = tokenizer.segment(text, false, false).unwrap();
+        let tokenizer = tokenizer.with_path_selection(PathSelection::LowestCost);
+        let lowest_cost = tokenizer.segment(text, false, false).unwrap();
+        assert_eq!(fewest.len(), lowest_cost.len());
+        assert_eq!(lowest_cost.concat(), text);
+    }
+
+    #[test]
+    fn test_lattice_and_nbest() {
+        let word_list = vec![
+            ("ตา".to_string(), 1),
+            ("กลม".to_string(), 1),
+            ("ตาก".to_string(), 500),
+            ("ลม".to_string(), 800),
+        ];
+        let tokenizer = NewmmTokenizer::from_weighted_word_list(word_list)
+            .with_path_selection(PathSelection::LowestCost);
+
+        let lattice = tokenizer.lattice("ตากลม!").unwrap();
+        assert_eq!(lattice.nodes, vec![0, 2, 3, 5, 6]);
+        let edge_texts: Vec<&str> = lattice.edges.iter().map(|edge| edge.token.text.as_str()).collect();
+        assert_eq!(edge_texts, vec!["ตา", "ตาก", "กลม", "ลม", "!"]);
+        assert!(!lattice.edges.last().unwrap().in_dictionary);
+
+        let nbest = tokenizer.segment_nbest("ตากลม!", 5).unwrap();
+        assert_eq!(nbest.len(), 2);
+        let best: Vec<String> = nbest[0].tokens.iter().map(|token| token.text.clone()).collect();
+        assert_eq!(best, tokenizer.segment("ตากลม!", false, false).unwrap());
+        assert!(nbest[0].cost < nbest[1].cost);
+        assert_eq!(nbest[1].tokens[1].text, "กลม");
     }
 
-    fn segment_to_string(&self, text: &str, safe: bool, parallel: bool) -> Vec<String> {
-        self.segment(text, safe, parallel).unwrap_or_default()
+    #[test]
+    fn test_token_patterns() {
+        use crate::tokenizer::token_patterns::TokenPatternPreset;
+
+        let text = "ดูที่ https://thongna.dev/docs ราคา 3.5kg";
+        let tokenizer = NewmmTokenizer::new(DICT_PATH).unwrap();
+        assert!(!tokenizer
+            .segment(text, false, false)
+            .unwrap()
+            .contains(&"https://thongna.dev/docs".to_string()));
+
+        let tokenizer = tokenizer.with_token_patterns(TokenPatterns::from_presets(&[
+            TokenPatternPreset::Url,
+            TokenPatternPreset::NumberWithUnit,
+        ]));
+        let tokens = tokenizer.segment(text, false, false).unwrap();
+        assert_eq!(
+            tokens,
+            vec!["ดู", "ที่", " ", "https://thongna.dev/docs", " ", "ราคา", " ", "3.5kg"]
+        );
+
+        // safe mode does not cut through a match
+        let url = format!("https://thongna.dev/{}", "a".repeat(200));
+        let long_text = format!("{}{}", "ด้านหน้า".repeat(15), url);
+        let tokens = tokenizer.segment(&long_text, true, false).unwrap();
+        assert_eq!(tokens.last(), Some(&url));
+
+        // the default patterns only match where the dictionary has no word
+        let tokenizer = NewmmTokenizer::from_word_list(vec!["COVID-19".to_string(), "โควิด".to_string()]);
+        assert_eq!(
+            tokenizer.segment("COVID-19โควิด COVID-2", false, false).unwrap(),
+            vec!["COVID-19", "โควิด", " ", "COVID-", "2"]
+        );
+        let tokenizer = tokenizer.with_token_patterns(TokenPatterns::from_presets(&[
+            TokenPatternPreset::NumberWithUnit,
+        ]));
+        assert_eq!(
+            tokenizer.segment("COVID-19 19kg", false, false).unwrap(),
+            vec!["COVID-19", " ", "19kg"]
+        );
+    }
+
+    #[test]
+    fn test_config() {
+        let text = "ด้านหน้า".repeat(20) + &"ก".repeat(40);
+        let tokenizer = NewmmTokenizer::new(DICT_PATH).unwrap();
+        let default_tokens = tokenizer.segment(&text, true, false).unwrap();
+
+        let tokenizer = tokenizer.with_config(
+            NewmmConfig::new()
+                .with_max_graph_size(10)
+                .with_text_scan_point(30)
+                .with_text_scan_left(5)
+                .with_text_scan_right(5),
+        );
+        assert_eq!(tokenizer.config().text_scan_begin(), 25);
+        let tokens = tokenizer.segment(&text, true, false).unwrap();
+        assert_eq!(tokens.concat(), text);
+        assert_ne!(tokens, default_tokens);
+
+        let config = tokenizer.config().with_safe(true);
+        let tokenizer = tokenizer.with_config(config);
+        assert_eq!(tokenizer.segment_with_config_modes(&text).unwrap(), tokens);
+
+        // a zero window still makes progress
+        let tokenizer = tokenizer.with_config(
+            NewmmConfig::new()
+                .with_text_scan_point(0)
+                .with_text_scan_right(0),
+        );
+        assert_eq!(tokenizer.segment("ตากลม", true, false).unwrap().concat(), "ตากลม");
+    }
+
+    #[test]
+    fn test_timeout() {
+        let tokenizer = NewmmTokenizer::new(DICT_PATH)
+            .unwrap()
+            .with_config(NewmmConfig::new().with_timeout(Some(Duration::ZERO)));
+        for safe in [false, true] {
+            assert!(matches!(
+                tokenizer.segment(&"ชิ".repeat(100), safe, false),
+                Err(ThongnaError::Timeout(_))
+            ));
+        }
+        assert!(tokenizer.segment("", false, false).unwrap().is_empty());
+
+        // a large graph still gives up on time
+        let tokenizer = tokenizer.with_config(
+            NewmmConfig::new()
+                .with_max_graph_size(100_000)
+                .with_timeout(Some(Duration::from_millis(50))),
+        );
+        let started = Instant::now();
+        let result = tokenizer.segment(&"ด้านหน้า".repeat(24), false, false);
+        assert!(matches!(result, Ok(_) | Err(ThongnaError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        // exponentially many paths lead to the last vertex of this graph
+        let graph: HashMap<CharacterIndex, Vec<CharacterIndex>> = (0..10_000)
+            .map(|vertex| (vertex, vec![vertex + 1, vertex + 2]))
+            .collect();
+        let started = Instant::now();
+        assert!(matches!(
+            NewmmTokenizer::bfs_paths_graph(
+                &graph,
+                0,
+                10_000,
+                &mut VecDeque::new(),
+                Deadline::start(Some(Duration::ZERO)),
+            ),
+            Err(ThongnaError::Timeout(_))
+        ));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(
+            NewmmTokenizer::bfs_paths_graph(&graph, 0, 10_000, &mut VecDeque::new(), None)
+                .unwrap()
+                .len(),
+            5_001
+        );
+
+        let tokenizer =
+            tokenizer.with_config(NewmmConfig::new().with_timeout(Some(Duration::from_secs(60))));
+        assert_eq!(tokenizer.segment("ไข่คน2021", false, false).unwrap(), vec!["ไข่", "คน", "2021"]);
+    }
+
+    #[test]
+    fn test_segment_normalized_with_offsets() {
+        let tokenizer = NewmmTokenizer::new(DICT_PATH).unwrap();
+        let normalizer = Normalizer::default().with_step(crate::normalize::NormalizeStep::SpaceNumbers);
+        let text = "เเปลก\u{200b}  ราคา100บาท";
+        let tokens = tokenizer
+            .segment_normalized_with_offsets(text, &normalizer, false, false)
+            .unwrap();
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(texts, vec!["เเปลก", "  ", "ราคา", "100", "บาท"]);
+        assert_eq!((tokens[0].start, tokens[0].end), (0, 5));
+        assert_eq!((tokens[1].start, tokens[1].end), (6, 8));
+        for token in &tokens {
+            assert_eq!(&text[token.byte_start..token.byte_end], token.text);
+        }
+        assert!(tokenizer
+            .segment_normalized_with_offsets("", &normalizer, false, false)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_compiled_dict_matches_text_dict() {
+        let compiled_path = std::env::temp_dir().join(format!(
+            "thongna-newmm-test-{}.bin",
+            std::process::id()
+        ));
+        crate::tokenizer::compiled_dict::compile_dict(
+            DictSource::FilePath(PathBuf::from(DICT_PATH)),
+            &compiled_path,
+        )
+        .unwrap();
+        let compiled = NewmmTokenizer::from_compiled_dict(compiled_path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&compiled_path).unwrap();
+        let text_dict = NewmmTokenizer::new(DICT_PATH).unwrap();
+
+        let text = "ไต้หวัน (แป่ะเอ๋ยี้: Tâi-oân; ไต่อวัน) หรือ ไถวาน ค่าจ้างที่ได้รับต้องทำให้แรงงาน";
+        assert_eq!(
+            compiled.segment(text, false, false).unwrap(),
+            text_dict.segment(text, false, false).unwrap()
+        );
     }
 }
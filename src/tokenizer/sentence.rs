@@ -0,0 +1,309 @@
+//! Sentence segmentation for Thai text.
+//!
+//! Thai marks sentence ends with a space rather than punctuation, but spaces
+//! also separate phrases, numbers and foreign words. Sentences are therefore
+//! cut on the newmm token stream, at line breaks, at terminal punctuation,
+//! and at spaces that follow a sentence-final particle or that separate two
+//! Thai clauses not joined by a connective word.
+
+use super::newmm::NewmmTokenizer;
+use super::token::{tokens_from_spans, Token};
+use super::tokenizer_trait::Tokenizer;
+use crate::error::ThongnaResult;
+
+use lazy_static::lazy_static;
+use rustc_hash::FxHashSet as HashSet;
+
+/// Minimum number of words before a plain space ends a sentence,
+/// including a space after ๆ or ฯ, which also repeat or shorten words
+/// inside a sentence
+const MIN_SENTENCE_WORDS: usize = 3;
+
+/// Words that usually end a sentence when followed by a space
+const SENTENCE_FINAL_PARTICLES: &[&str] = &[
+    "ครับ",
+    "ค่ะ",
+    "คะ",
+    "ขา",
+    "จ้ะ",
+    "จ้า",
+    "จ๊ะ",
+    "จ๋า",
+    "นะ",
+    "น่ะ",
+    "เถอะ",
+    "เถิด",
+    "สิ",
+    "ซิ",
+    "หรอก",
+    "ล่ะ",
+    "ไหม",
+    "มั้ย",
+    "หรือเปล่า",
+    "เหรอ",
+    "หรอ",
+    "เลย",
+    "ด้วย",
+    "แล้ว",
+];
+
+/// Words that join two clauses, so a space next to them does not end a sentence
+const CONNECTIVES: &[&str] = &[
+    "และ",
+    "หรือ",
+    "แต่",
+    "แต่ว่า",
+    "ซึ่ง",
+    "ที่",
+    "โดย",
+    "เพราะ",
+    "เพราะว่า",
+    "เนื่องจาก",
+    "ว่า",
+    "ของ",
+    "กับ",
+    "ใน",
+    "จาก",
+    "ถ้า",
+    "หาก",
+    "แม้",
+    "แม้ว่า",
+    "จึง",
+    "ก็",
+    "คือ",
+    "เช่น",
+    "ได้แก่",
+    "เพื่อ",
+    "ให้",
+    "หรือไม่",
+    "ตลอดจน",
+    "รวมทั้ง",
+    "อีกทั้ง",
+];
+
+/// Characters that end a sentence when they make up a whole token
+const TERMINAL_PUNCTUATION: &[char] = &['.', '!', '?', '…'];
+
+const OPENING_BRACKETS: &[char] = &['(', '[', '{', '“', '"'];
+const CLOSING_BRACKETS: &[char] = &[')', ']', '}', '”'];
+
+lazy_static! {
+    static ref SENTENCE_FINAL_PARTICLE_SET: HashSet<&'static str> =
+        SENTENCE_FINAL_PARTICLES.iter().copied().collect();
+    static ref CONNECTIVE_SET: HashSet<&'static str> = CONNECTIVES.iter().copied().collect();
+}
+
+fn is_thai_char(character: char) -> bool {
+    ('\u{0E00}'..='\u{0E7F}').contains(&character)
+}
+
+fn is_thai_word(word: &str) -> bool {
+    word.chars().next().is_some_and(is_thai_char)
+}
+
+fn is_line_break(word: &str) -> bool {
+    word.ends_with('\n')
+}
+
+fn is_space(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(char::is_whitespace)
+}
+
+/// Whether a space right after `previous` and before `next` ends a sentence
+/// of `words_so_far` words.
+fn is_sentence_break(previous: &str, next: &str, words_so_far: usize) -> bool {
+    let mut previous_chars = previous.chars();
+    if let (Some(character), None) = (previous_chars.next(), previous_chars.next()) {
+        if TERMINAL_PUNCTUATION.contains(&character) {
+            return true;
+        }
+    }
+    if !is_thai_word(previous) || !is_thai_word(next) {
+        return false;
+    }
+    if SENTENCE_FINAL_PARTICLE_SET.contains(previous) {
+        return true;
+    }
+    words_so_far >= MIN_SENTENCE_WORDS
+        && !CONNECTIVE_SET.contains(previous)
+        && !CONNECTIVE_SET.contains(next)
+}
+
+/// Groups word tokens, in text order, into sentence spans in characters.
+/// Spaces and line breaks between sentences are left out of every span.
+pub(crate) fn sentence_spans(words: &[Token]) -> Vec<(usize, usize)> {
+    let mut spans = vec![];
+    // (start, end) of the words of the current sentence
+    let mut current: Option<(usize, usize)> = None;
+    let mut words_so_far = 0;
+    let mut bracket_depth: usize = 0;
+
+    let mut close = |current: &mut Option<(usize, usize)>, words_so_far: &mut usize| {
+        if let Some(span) = current.take() {
+            spans.push(span);
+        }
+        *words_so_far = 0;
+    };
+
+    for (index, word) in words.iter().enumerate() {
+        let text = word.text.as_str();
+        if is_line_break(text) {
+            close(&mut current, &mut words_so_far);
+            bracket_depth = 0;
+            continue;
+        }
+        if is_space(text) {
+            let previous = index
+                .checked_sub(1)
+                .map(|previous| words[previous].text.as_str());
+            let next = words.get(index + 1).map(|next| next.text.as_str());
+            if let (Some(previous), Some(next)) = (previous, next) {
+                if bracket_depth == 0
+                    && current.is_some()
+                    && is_sentence_break(previous, next, words_so_far)
+                {
+                    close(&mut current, &mut words_so_far);
+                }
+            }
+            continue;
+        }
+
+        bracket_depth += text
+            .chars()
+            .filter(|c| OPENING_BRACKETS.contains(c))
+            .count();
+        bracket_depth = bracket_depth.saturating_sub(
+            text.chars()
+                .filter(|c| CLOSING_BRACKETS.contains(c))
+                .count(),
+        );
+        words_so_far += 1;
+        current = Some(match current {
+            Some((start, _)) => (start, word.end),
+            None => (word.start, word.end),
+        });
+    }
+    close(&mut current, &mut words_so_far);
+    spans
+}
+
+/// Splits Thai text into sentences, on top of a newmm word tokenizer.
+///
+/// ```no_run
+/// use thongna::tokenizer::sentence::SentenceTokenizer;
+/// use thongna::{NewmmTokenizer, Tokenizer};
+///
+/// let words = NewmmTokenizer::new("dataset/words_th.txt")?;
+/// let sentences = SentenceTokenizer::new(&words).segment("ฝนตกหนักมากครับ เราจึงไม่ได้ไปทะเล", false, false)?;
+/// assert_eq!(sentences, vec!["ฝนตกหนักมากครับ", "เราจึงไม่ได้ไปทะเล"]);
+/// # Ok::<(), thongna::ThongnaError>(())
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SentenceTokenizer<'w> {
+    word_tokenizer: &'w NewmmTokenizer,
+}
+
+impl<'w> SentenceTokenizer<'w> {
+    pub fn new(word_tokenizer: &'w NewmmTokenizer) -> Self {
+        Self { word_tokenizer }
+    }
+
+    /// Split text into sentences, keeping the position of every sentence.
+    ///
+    /// Sentences do not include the spaces and line breaks around them.
+    /// `safe` and `parallel` are passed to the word tokenizer.
+    pub fn segment_with_offsets(
+        &self,
+        text: &str,
+        safe: bool,
+        parallel: bool,
+    ) -> ThongnaResult<Vec<Token>> {
+        let words = self
+            .word_tokenizer
+            .segment_with_offsets(text, safe, parallel)?;
+        Ok(tokens_from_spans(text, &sentence_spans(&words)))
+    }
+}
+
+impl Tokenizer for SentenceTokenizer<'_> {
+    fn segment(&self, text: &str, safe: bool, parallel: bool) -> ThongnaResult<Vec<String>> {
+        Ok(self
+            .segment_with_offsets(text, safe, parallel)?
+            .into_iter()
+            .map(|sentence| sentence.text)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DICT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/dataset/words_th.txt");
+
+    fn sentences(text: &str) -> Vec<String> {
+        let words = NewmmTokenizer::new(DICT_PATH).unwrap();
+        SentenceTokenizer::new(&words)
+            .segment(text, false, false)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sentence_signals() {
+        assert_eq!(
+            sentences("วันนี้อากาศดีมากครับ ผมจะไปเที่ยวทะเล เด็กๆ เล่นกันสนุก"),
+            vec!["วันนี้อากาศดีมากครับ", "ผมจะไปเที่ยวทะเล", "เด็กๆ เล่นกันสนุก"]
+        );
+        assert_eq!(
+            sentences("เราไปเที่ยวทะเลบ่อยๆ น้องชอบมาก"),
+            vec!["เราไปเที่ยวทะเลบ่อยๆ", "น้องชอบมาก"]
+        );
+        assert_eq!(
+            sentences("สมชายมาถึงแล้ว! คุณจะไปไหม?\nบรรทัดใหม่"),
+            vec!["สมชายมาถึงแล้ว!", "คุณจะไปไหม?", "บรรทัดใหม่"]
+        );
+        // connectives and numbers keep the sentence going
+        assert_eq!(
+            sentences("ผมชอบกินข้าวมาก และชอบกินก๋วยเตี๋ยว พ.ศ. 2564 มีคน 3.5 ล้าน"),
+            vec!["ผมชอบกินข้าวมาก และชอบกินก๋วยเตี๋ยว", "พ.ศ. 2564 มีคน 3.5 ล้าน"]
+        );
+        assert!(sentences("").is_empty());
+        assert!(sentences(" \n ").is_empty());
+    }
+
+    #[test]
+    fn test_long_paragraph() {
+        let words = NewmmTokenizer::new(DICT_PATH).unwrap();
+        let tokenizer = SentenceTokenizer::new(&words);
+        let text = "ไต้หวัน (แป่ะเอ๋ยี้: Tâi-oân; ไต่อวัน) หรือ ไถวาน (อักษรโรมัน: Taiwan; จีนตัวย่อ: 台湾; จีนตัวเต็ม: 臺灣/台灣; พินอิน: Táiwān; ไถวาน) หรือชื่อทางการว่า สาธารณรัฐจีน (จีนตัวย่อ: 中华民国; จีนตัวเต็ม: 中華民國; พินอิน: Zhōnghuá Mínguó) เป็นรัฐในทวีปเอเชียตะวันออก ค่าจ้างที่ได้รับต้องทำให้แรงงานสามารถเลี้ยงดูตัวเองและครอบครัว อย่างสมศักดิ์ศรีความเป็นมนุษย์\n\nรัฐบาลประกาศมาตรการใหม่แล้ว ประชาชนจำนวนมากเดินทางกลับบ้าน";
+        let sentences = tokenizer.segment_with_offsets(text, false, false).unwrap();
+        let texts: Vec<&str> = sentences
+            .iter()
+            .map(|sentence| sentence.text.as_str())
+            .collect();
+        assert_eq!(texts.len(), 5);
+        assert!(texts[0].starts_with("ไต้หวัน (") && texts[0].ends_with("เป็นรัฐในทวีปเอเชียตะวันออก"));
+        assert_eq!(
+            texts[1],
+            "ค่าจ้างที่ได้รับต้องทำให้แรงงานสามารถเลี้ยงดูตัวเองและครอบครัว"
+        );
+        assert_eq!(texts[2], "อย่างสมศักดิ์ศรีความเป็นมนุษย์");
+        assert_eq!(texts[3], "รัฐบาลประกาศมาตรการใหม่แล้ว");
+        assert_eq!(texts[4], "ประชาชนจำนวนมากเดินทางกลับบ้าน");
+
+        let chars: Vec<char> = text.chars().collect();
+        for sentence in &sentences {
+            assert_eq!(&text[sentence.byte_start..sentence.byte_end], sentence.text);
+            assert_eq!(
+                chars[sentence.start..sentence.end]
+                    .iter()
+                    .collect::<String>(),
+                sentence.text
+            );
+        }
+        assert_eq!(
+            tokenizer.segment_with_offsets(text, true, true).unwrap(),
+            sentences
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+
+use super::newmm::NewmmTokenizer;
+use super::tcc::tcc_tokenizer::tcc_end_positions;
+use super::token::{tokens_from_spans, Token};
+use crate::bytes_str::custom_string::CustomString;
+use crate::error::ThongnaResult;
+
+/// Characters read past a safe-mode scan window before cutting there,
+/// so that token patterns matching across the window see their whole match
+const STREAM_LOOKAHEAD: usize = 1024;
+
+/// Most characters held for a pattern match that has not ended yet,
+/// a longer match is cut at a character cluster boundary
+const STREAM_MAX_PENDING: usize = 64 * STREAM_LOOKAHEAD;
+
+/// Segments text that arrives in pieces, holding only the text
+/// not yet segmented.
+///
+/// Text is cut into the parts of safe-mode segmentation as soon as enough
+/// of it has been read to find the cut points, so the tokens are the same as
+/// those of `segment_with_offsets(whole_text, true, _)`, offsets included,
+/// but for pattern matches longer than 65536 characters, which are cut.
+/// The timeout of the tokenizer's [`NewmmConfig`](super::newmm::NewmmConfig)
+/// applies to each part.
+#[derive(Debug, Default)]
+pub struct StreamSegmenter {
+    /// Text read and not segmented yet
+    pending: String,
+    pending_chars: usize,
+    /// Length of `pending` to reach before segmenting again when the end
+    /// of a pattern match was not read yet, 0 otherwise
+    segment_at: usize,
+    /// Bytes read that do not make a whole UTF-8 character yet
+    undecoded: Vec<u8>,
+    /// Position of `pending` in the stream, in characters and in bytes
+    char_offset: usize,
+    byte_offset: usize,
+}
+
+impl StreamSegmenter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the next UTF-8 bytes of the stream, returning the tokens
+    /// that can be completed so far.
+    ///
+    /// A character may be split between two pieces.
+    pub fn push(&mut self, tokenizer: &NewmmTokenizer, bytes: &[u8]) -> ThongnaResult<Vec<Token>> {
+        self.undecoded.extend_from_slice(bytes);
+        let decoded_length = match std::str::from_utf8(&self.undecoded) {
+            Ok(text) => text.len(),
+            // an incomplete character at the end waits for the next piece
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => return Err(io::Error::new(io::ErrorKind::InvalidData, error).into()),
+        };
+        let decoded = std::str::from_utf8(&self.undecoded[..decoded_length]).unwrap();
+        self.pending.push_str(decoded);
+        self.pending_chars += decoded.chars().count();
+        self.undecoded.drain(..decoded_length);
+
+        // segment once twice the kept text has been read, so that
+        // small pieces do not convert the kept text again every time
+        let segment_at = self
+            .segment_at
+            .max(2 * (tokenizer.config().text_scan_end() + STREAM_LOOKAHEAD));
+        if self.pending_chars < segment_at {
+            return Ok(vec![]);
+        }
+        self.segment_pending(tokenizer, false)
+    }
+
+    /// End the stream, returning the remaining tokens.
+    pub fn finish(&mut self, tokenizer: &NewmmTokenizer) -> ThongnaResult<Vec<Token>> {
+        if !self.undecoded.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream ends inside a UTF-8 character",
+            )
+            .into());
+        }
+        self.segment_pending(tokenizer, true)
+    }
+
+    fn segment_pending(
+        &mut self,
+        tokenizer: &NewmmTokenizer,
+        last: bool,
+    ) -> ThongnaResult<Vec<Token>> {
+        let input = CustomString::new(&self.pending);
+        let input_length = input.chars_len();
+        let keep = if last {
+            0
+        } else {
+            tokenizer.config().text_scan_end() + STREAM_LOOKAHEAD
+        };
+
+        let mut spans = vec![];
+        let mut part_begin = 0;
+        self.segment_at = 0;
+        while input_length - part_begin > keep {
+            let txt = input.substring(part_begin, input_length);
+            let part_end = match tokenizer.first_safe_mode_cut(&txt)? {
+                Some(cut_pos) => part_begin + cut_pos,
+                None if last => input_length,
+                // a pattern match too long to wait for its end
+                None if txt.chars_len() >= STREAM_MAX_PENDING => {
+                    let cut_pos = tcc_end_positions(txt.raw_content())
+                        .into_iter()
+                        .take_while(|&position| position <= STREAM_MAX_PENDING)
+                        .last()
+                        .unwrap_or(STREAM_MAX_PENDING);
+                    part_begin + cut_pos
+                }
+                // a pattern match runs to the end of what was read, wait
+                // for twice as much text rather than looking again each time
+                None => {
+                    self.segment_at = (2 * txt.chars_len()).min(STREAM_MAX_PENDING);
+                    break;
+                }
+            };
+            spans.extend(
+                tokenizer
+                    .segment_part(&input.substring(part_begin, part_end))?
+                    .into_iter()
+                    .map(|(start, end)| (part_begin + start, part_begin + end)),
+            );
+            part_begin = part_end;
+        }
+
+        let mut tokens = tokens_from_spans(&self.pending, &spans);
+        let consumed_bytes = tokens.last().map_or(0, |token| token.byte_end);
+        for token in tokens.iter_mut() {
+            token.start += self.char_offset;
+            token.end += self.char_offset;
+            token.byte_start += self.byte_offset;
+            token.byte_end += self.byte_offset;
+        }
+        self.pending.drain(..consumed_bytes);
+        self.pending_chars -= part_begin;
+        self.char_offset += part_begin;
+        self.byte_offset += consumed_bytes;
+        Ok(tokens)
+    }
+}
+
+/// Tokens of a reader, segmented as they are read,
+/// see [`NewmmTokenizer::segment_stream`].
+pub struct TokenStream<'t, R> {
+    tokenizer: &'t NewmmTokenizer,
+    reader: R,
+    segmenter: StreamSegmenter,
+    tokens: VecDeque<Token>,
+    done: bool,
+}
+
+impl<'t, R: BufRead> TokenStream<'t, R> {
+    pub(crate) fn new(tokenizer: &'t NewmmTokenizer, reader: R) -> Self {
+        Self {
+            tokenizer,
+            reader,
+            segmenter: StreamSegmenter::new(),
+            tokens: VecDeque::new(),
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for TokenStream<'_, R> {
+    type Item = ThongnaResult<Token>;
+
+    /// Stops after the first error.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(token) = self.tokens.pop_front() {
+                return Some(Ok(token));
+            }
+            if self.done {
+                return None;
+            }
+            let result = match self.reader.fill_buf() {
+                Ok([]) => {
+                    self.done = true;
+                    self.segmenter.finish(self.tokenizer)
+                }
+                Ok(bytes) => {
+                    let length = bytes.len();
+                    let result = self.segmenter.push(self.tokenizer, bytes);
+                    self.reader.consume(length);
+                    result
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => Err(error.into()),
+            };
+            match result {
+                Ok(tokens) => self.tokens.extend(tokens),
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::token_patterns::{TokenPatternPreset, TokenPatterns};
+    use std::io::BufReader;
+
+    const DICT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/dataset/words_th.txt");
+
+    #[test]
+    fn test_stream_matches_whole_text() {
+        let tokenizer = NewmmTokenizer::new(DICT_PATH)
+            .unwrap()
+            .with_token_patterns(TokenPatterns::from_presets(&[TokenPatternPreset::Url]));
+        let text = [
+            "ไต้หวัน (แป่ะเอ๋ยี้: Tâi-oân; ไต่อวัน) หรือ ไถวาน ดู https://thongna.dev/docs\r\n",
+            &"ด้านหน้า".repeat(20),
+            &"ก".repeat(40),
+            "ค่าจ้างที่ได้รับต้องทำให้แรงงานสามารถเลี้ยงดูตัวเองและครอบครัว",
+        ]
+        .concat()
+        .repeat(30);
+        let expected = tokenizer.segment_with_offsets(&text, true, false).unwrap();
+
+        // tiny buffers split characters and lines anywhere
+        for capacity in [1, 7, 4096] {
+            let reader = BufReader::with_capacity(capacity, text.as_bytes());
+            let tokens: Vec<Token> = tokenizer
+                .segment_stream(reader)
+                .collect::<ThongnaResult<_>>()
+                .unwrap();
+            assert_eq!(tokens, expected);
+        }
+        assert_eq!(tokenizer.segment_stream("".as_bytes()).count(), 0);
+    }
+
+    #[test]
+    fn test_stream_long_pattern_match() {
+        let tokenizer = NewmmTokenizer::from_word_list(vec!["ตา".to_string()]);
+        let text = format!("ตา{}ตา", "a".repeat(3 * STREAM_MAX_PENDING));
+        let mut segmenter = StreamSegmenter::new();
+        let mut tokens = vec![];
+        for piece in text.as_bytes().chunks(100) {
+            tokens.extend(segmenter.push(&tokenizer, piece).unwrap());
+            assert!(segmenter.pending_chars <= STREAM_MAX_PENDING + 100);
+        }
+        tokens.extend(segmenter.finish(&tokenizer).unwrap());
+
+        assert_eq!(tokens.first().unwrap().text, "ตา");
+        assert_eq!(tokens.last().unwrap().text, "ตา");
+        assert!(tokens
+            .iter()
+            .all(|token| token.text.chars().count() <= STREAM_MAX_PENDING));
+        for token in &tokens {
+            assert_eq!(&text[token.byte_start..token.byte_end], token.text);
+        }
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.text.as_str())
+                .collect::<String>(),
+            text
+        );
+    }
+
+    #[test]
+    fn test_stream_invalid_utf8() {
+        let tokenizer = NewmmTokenizer::from_word_list(vec!["ตา".to_string()]);
+        let mut tokens = tokenizer.segment_stream(&b"\xe0\xb8\x95\xe0\xb8"[..]);
+        assert!(matches!(tokens.next(), Some(Err(_))));
+        assert!(tokens.next().is_none());
+
+        let mut segmenter = StreamSegmenter::new();
+        assert!(segmenter.push(&tokenizer, b"\xff").is_err());
+    }
+}
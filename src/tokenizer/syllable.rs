@@ -0,0 +1,5 @@
+pub(crate) mod syllable_parts;
+pub(crate) mod syllable_rules;
+pub mod syllable_tokenizer;
+
+pub use syllable_tokenizer::SyllableTokenizer;
@@ -0,0 +1,385 @@
+//! Parts of a written Thai syllable: initial consonants, vowel sound,
+//! final sound and tone mark, read from its spelling, and the tone
+//! they give.
+
+/// Vowel sounds, short then long
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum VowelSound {
+    A,
+    Aa,
+    I,
+    Ii,
+    Ue,
+    Uee,
+    U,
+    Uu,
+    E,
+    Ee,
+    Ae,
+    Aee,
+    O,
+    Oo,
+    Or,
+    Orr,
+    Oe,
+    Oee,
+    Ia,
+    Iia,
+    Uea,
+    Ueea,
+    Ua,
+    Uua,
+}
+
+/// Sounds a syllable can end with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FinalSound {
+    K,
+    T,
+    P,
+    Ng,
+    N,
+    M,
+    /// The glide written ย, as in "ชาย", "ไ"
+    Y,
+    /// The glide written ว, as in "ขาว", "เ-า"
+    W,
+}
+
+/// Class of an initial consonant, which decides the tone with the tone mark
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConsonantClass {
+    Mid,
+    High,
+    Low,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Tone {
+    Mid,
+    Low,
+    Falling,
+    High,
+    Rising,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SyllableParts {
+    /// Consonants of the initial sound, one or two, without a silent
+    /// leading ห or อ nor a silent ร, and with ทร read as ซ
+    pub initial: Vec<char>,
+    /// The silent ห or อ written before `initial`, if any
+    pub leading: Option<char>,
+    pub vowel: VowelSound,
+    pub final_sound: Option<FinalSound>,
+    pub tone_mark: Option<char>,
+}
+
+const TONE_MARKS: [char; 4] = ['่', '้', '๊', '๋'];
+const LEADING_VOWELS: [char; 5] = ['เ', 'แ', 'โ', 'ใ', 'ไ'];
+
+pub(crate) fn is_consonant(character: char) -> bool {
+    ('ก'..='ฮ').contains(&character) && !matches!(character, 'ฤ' | 'ฦ')
+}
+
+pub(crate) fn consonant_class(consonant: char) -> ConsonantClass {
+    match consonant {
+        'ก' | 'จ' | 'ฎ' | 'ฏ' | 'ด' | 'ต' | 'บ' | 'ป' | 'อ' => ConsonantClass::Mid,
+        'ข' | 'ฃ' | 'ฉ' | 'ฐ' | 'ถ' | 'ผ' | 'ฝ' | 'ศ' | 'ษ' | 'ส' | 'ห' => ConsonantClass::High,
+        _ => ConsonantClass::Low,
+    }
+}
+
+/// Pairs of consonants read as one initial sound: the clusters, then the
+/// pairs whose ร is silent, as in "จริง" and "สร้าง", or reads ซ with ท
+pub(crate) const CLUSTERS: [&str; 20] = [
+    "กร", "ขร", "คร", "ตร", "ปร", "พร", "กล", "ขล", "คล", "ปล", "ผล", "พล", "กว", "ขว", "คว", "จร",
+    "ซร", "ศร", "สร", "ทร",
+];
+
+/// Whether `first` and `second` can be read as one initial sound
+fn is_cluster(first: char, second: char) -> bool {
+    CLUSTERS
+        .iter()
+        .any(|cluster| cluster.chars().eq([first, second]))
+}
+
+/// Sound of a consonant at the end of a syllable, `None` if it is silent
+pub(crate) fn final_sound_of(consonant: char) -> Option<FinalSound> {
+    match consonant {
+        'ก' | 'ข' | 'ฃ' | 'ค' | 'ฅ' | 'ฆ' => Some(FinalSound::K),
+        'จ' | 'ฉ' | 'ช' | 'ซ' | 'ฌ' | 'ฎ' | 'ฏ' | 'ฐ' | 'ฑ' | 'ฒ' | 'ด' | 'ต' | 'ถ' | 'ท' | 'ธ'
+        | 'ศ' | 'ษ' | 'ส' => Some(FinalSound::T),
+        'บ' | 'ป' | 'ผ' | 'ฝ' | 'พ' | 'ฟ' | 'ภ' => Some(FinalSound::P),
+        'ง' => Some(FinalSound::Ng),
+        'ญ' | 'ณ' | 'น' | 'ร' | 'ล' | 'ฬ' => Some(FinalSound::N),
+        'ม' => Some(FinalSound::M),
+        'ย' => Some(FinalSound::Y),
+        'ว' => Some(FinalSound::W),
+        _ => None,
+    }
+}
+
+/// Drop the letters silenced by a thanthakhat (`์`), with the vowel
+/// written on them: "ร์" of "จันทร์", "ธุ์" of "พันธุ์"
+fn drop_silent_letters(chars: &mut Vec<char>) {
+    while let Some(position) = chars.iter().position(|&character| character == '์') {
+        let mut start = position;
+        if start > 0 && matches!(chars[start - 1], 'ิ' | 'ุ') {
+            start -= 1;
+        }
+        if start > 0 && is_consonant(chars[start - 1]) {
+            start -= 1;
+        }
+        chars.drain(start..=position);
+    }
+}
+
+/// Read the parts of a written syllable, as cut by
+/// [`SyllableTokenizer`](super::SyllableTokenizer).
+///
+/// A syllable of consonants only is read with a short "a" if it has
+/// one consonant ("ส" of "สบาย"), and a short "o" otherwise ("คน").
+/// Returns `None` if `syllable` is not spelled like a Thai syllable.
+pub(crate) fn parse_syllable(syllable: &str) -> Option<SyllableParts> {
+    let tone_mark = syllable
+        .chars()
+        .find(|character| TONE_MARKS.contains(character));
+    let mut chars: Vec<char> = syllable
+        .chars()
+        .filter(|character| !TONE_MARKS.contains(character))
+        .collect();
+    drop_silent_letters(&mut chars);
+
+    match chars[..] {
+        ['ฤ' | 'ฦ', ..] => {
+            let initial = if chars[0] == 'ฤ' { 'ร' } else { 'ล' };
+            let vowel = if chars.get(1) == Some(&'ๅ') {
+                VowelSound::Uee
+            } else {
+                VowelSound::Ue
+            };
+            return Some(SyllableParts {
+                initial: vec![initial],
+                leading: None,
+                vowel,
+                final_sound: None,
+                tone_mark,
+            });
+        }
+        [] => return None,
+        _ => {}
+    }
+
+    let (leading_vowel, mut position) = if LEADING_VOWELS.contains(&chars[0]) {
+        (Some(chars[0]), 1)
+    } else {
+        (None, 0)
+    };
+
+    let first = *chars
+        .get(position)
+        .filter(|&&character| is_consonant(character))?;
+    position += 1;
+    let mut initial = vec![first];
+    let mut leading = None;
+    if let Some(&second) = chars.get(position) {
+        let followed = leading_vowel.is_some() || position + 1 < chars.len();
+        let next = chars.get(position + 1).copied();
+        // "กรรม" and "ขวด" read their second consonant as a vowel
+        let second_is_vowel = (second == 'ร' && next == Some('ร'))
+            || (second == 'ว' && leading_vowel.is_none() && next.is_some_and(is_consonant));
+        if followed && !second_is_vowel {
+            if (first == 'ห' && "งญนมยรลว".contains(second)) || (first == 'อ' && second == 'ย')
+            {
+                leading = Some(first);
+                initial = vec![second];
+                position += 1;
+            } else if is_cluster(first, second) {
+                // the ร of "จริง" and "สร้าง" is silent, "ทราย" reads "ซาย"
+                match (first, second) {
+                    ('ท', 'ร') => initial = vec!['ซ'],
+                    ('จ' | 'ซ' | 'ศ' | 'ส', 'ร') => {}
+                    _ => initial.push(second),
+                }
+                position += 1;
+            }
+        }
+    }
+
+    let rest = &chars[position..];
+    let (vowel, vowel_length, implicit_final) = match (leading_vowel, rest) {
+        (None, ['ร', 'ร', ..]) => (
+            VowelSound::A,
+            2,
+            rest.get(2).is_none().then_some(FinalSound::N),
+        ),
+        (None, ['ะ', ..]) => (VowelSound::A, 1, None),
+        (None, ['ั', 'ว', 'ะ', ..]) => (VowelSound::Ua, 3, None),
+        (None, ['ั', 'ว', ..]) => (VowelSound::Uua, 2, None),
+        (None, ['ั', ..]) => (VowelSound::A, 1, None),
+        (None, ['า', ..]) => (VowelSound::Aa, 1, None),
+        (None, ['ำ', ..]) => (VowelSound::A, 1, Some(FinalSound::M)),
+        (None, ['ิ', ..]) => (VowelSound::I, 1, None),
+        (None, ['ี', ..]) => (VowelSound::Ii, 1, None),
+        (None, ['ึ', ..]) => (VowelSound::Ue, 1, None),
+        (None, ['ื', 'อ', ..]) => (VowelSound::Uee, 2, None),
+        (None, ['ื', ..]) => (VowelSound::Uee, 1, None),
+        (None, ['ุ', ..]) => (VowelSound::U, 1, None),
+        (None, ['ู', ..]) => (VowelSound::Uu, 1, None),
+        (None, ['็', ..]) => (VowelSound::Or, 1, None),
+        (None, ['อ', ..]) => (VowelSound::Orr, 1, None),
+        (None, ['ว', ..]) if rest.len() > 1 => (VowelSound::Uua, 1, None),
+        (None, []) => (VowelSound::A, 0, None),
+        (None, _) => (VowelSound::O, 0, None),
+        (Some('เ'), ['็', ..]) => (VowelSound::E, 1, None),
+        (Some('เ'), ['ิ', ..]) => (VowelSound::Oee, 1, None),
+        (Some('เ'), ['ี', 'ย', 'ะ', ..]) => (VowelSound::Ia, 3, None),
+        (Some('เ'), ['ี', 'ย', ..]) => (VowelSound::Iia, 2, None),
+        (Some('เ'), ['ื', 'อ', 'ะ', ..]) => (VowelSound::Uea, 3, None),
+        (Some('เ'), ['ื', 'อ', ..]) => (VowelSound::Ueea, 2, None),
+        (Some('เ'), ['า', 'ะ', ..]) => (VowelSound::Or, 2, None),
+        (Some('เ'), ['า', ..]) => (VowelSound::A, 1, Some(FinalSound::W)),
+        (Some('เ'), ['อ', 'ะ', ..]) => (VowelSound::Oe, 2, None),
+        (Some('เ'), ['อ', ..]) => (VowelSound::Oee, 1, None),
+        (Some('เ'), ['ะ', ..]) => (VowelSound::E, 1, None),
+        (Some('เ'), ['ย']) => (VowelSound::Oee, 1, Some(FinalSound::Y)),
+        (Some('เ'), _) => (VowelSound::Ee, 0, None),
+        (Some('แ'), ['ะ' | '็', ..]) => (VowelSound::Ae, 1, None),
+        (Some('แ'), _) => (VowelSound::Aee, 0, None),
+        (Some('โ'), ['ะ', ..]) => (VowelSound::O, 1, None),
+        (Some('โ'), _) => (VowelSound::Oo, 0, None),
+        // ใ and ไ, the ย of "ไทย" is silent
+        (Some(_), _) => (VowelSound::A, rest.len(), Some(FinalSound::Y)),
+    };
+
+    // only the first of several final consonants is read, as in "สมัคร"
+    let final_sound = implicit_final.or_else(|| {
+        rest.get(vowel_length)
+            .filter(|&&character| is_consonant(character))
+            .and_then(|&consonant| final_sound_of(consonant))
+    });
+    Some(SyllableParts {
+        initial,
+        leading,
+        vowel,
+        final_sound,
+        tone_mark,
+    })
+}
+
+impl VowelSound {
+    pub fn is_long(self) -> bool {
+        matches!(
+            self,
+            VowelSound::Aa
+                | VowelSound::Ii
+                | VowelSound::Uee
+                | VowelSound::Uu
+                | VowelSound::Ee
+                | VowelSound::Aee
+                | VowelSound::Oo
+                | VowelSound::Orr
+                | VowelSound::Oee
+                | VowelSound::Iia
+                | VowelSound::Ueea
+                | VowelSound::Uua
+        )
+    }
+}
+
+impl SyllableParts {
+    /// Whether the syllable is live: it ends with a long vowel or a
+    /// sonorant, rather than a short vowel or a stop
+    pub fn is_live(&self) -> bool {
+        match self.final_sound {
+            Some(FinalSound::K | FinalSound::T | FinalSound::P) => false,
+            Some(_) => true,
+            None => self.vowel.is_long(),
+        }
+    }
+
+    /// Tone of the syllable, from the class of its first consonant
+    /// (the silent ห or อ if any), whether it is live and its tone mark
+    pub fn tone(&self) -> Tone {
+        let class = consonant_class(self.leading.unwrap_or(self.initial[0]));
+        match (self.tone_mark, class) {
+            (Some('่'), ConsonantClass::Low) => Tone::Falling,
+            (Some('่'), _) => Tone::Low,
+            (Some('้'), ConsonantClass::Low) => Tone::High,
+            (Some('้'), _) => Tone::Falling,
+            (Some('๊'), _) => Tone::High,
+            (Some('๋'), _) => Tone::Rising,
+            (_, ConsonantClass::Mid) if self.is_live() => Tone::Mid,
+            (_, ConsonantClass::High) if self.is_live() => Tone::Rising,
+            (_, ConsonantClass::Low) if self.is_live() => Tone::Mid,
+            (_, ConsonantClass::Low) if self.vowel.is_long() => Tone::Falling,
+            (_, ConsonantClass::Low) => Tone::High,
+            (_, _) => Tone::Low,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_syllable() {
+        let parts = parse_syllable("เปล่า").unwrap();
+        assert_eq!(parts.initial, vec!['ป', 'ล']);
+        assert_eq!(
+            (parts.vowel, parts.final_sound),
+            (VowelSound::A, Some(FinalSound::W))
+        );
+        assert_eq!(parts.tone_mark, Some('่'));
+
+        let parts = parse_syllable("หมด").unwrap();
+        assert_eq!((parts.leading, parts.initial), (Some('ห'), vec!['ม']));
+        assert_eq!(
+            (parts.vowel, parts.final_sound),
+            (VowelSound::O, Some(FinalSound::T))
+        );
+
+        let parts = parse_syllable("จันทร์").unwrap();
+        assert_eq!(
+            (parts.vowel, parts.final_sound),
+            (VowelSound::A, Some(FinalSound::N))
+        );
+        assert_eq!(parse_syllable("ทราย").unwrap().initial, vec!['ซ']);
+        assert_eq!(parse_syllable("จริง").unwrap().initial, vec!['จ']);
+        assert_eq!(parse_syllable("สวน").unwrap().initial, vec!['ส']);
+        let parts = parse_syllable("ขวด").unwrap();
+        assert_eq!((parts.initial, parts.vowel), (vec!['ข'], VowelSound::Uua));
+        let parts = parse_syllable("ส").unwrap();
+        assert_eq!((parts.vowel, parts.final_sound), (VowelSound::A, None));
+
+        assert!(parse_syllable("").is_none());
+        assert!(parse_syllable("ะ").is_none());
+    }
+
+    #[test]
+    fn test_tone() {
+        for (syllable, tone) in [
+            ("กา", Tone::Mid),
+            ("กัด", Tone::Low),
+            ("ขา", Tone::Rising),
+            ("ขาด", Tone::Low),
+            ("คา", Tone::Mid),
+            ("คะ", Tone::High),
+            ("คาด", Tone::Falling),
+            ("ไก่", Tone::Low),
+            ("ข้าว", Tone::Falling),
+            ("พ่อ", Tone::Falling),
+            ("น้ำ", Tone::High),
+            ("โต๊ะ", Tone::High),
+            ("จ๋า", Tone::Rising),
+            ("หมา", Tone::Rising),
+            ("อยู่", Tone::Low),
+            ("ไทย", Tone::Mid),
+        ] {
+            assert_eq!(parse_syllable(syllable).unwrap().tone(), tone, "{}", syllable);
+        }
+        assert!(parse_syllable("ะ").is_none());
+    }
+}
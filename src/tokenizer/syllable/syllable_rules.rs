@@ -0,0 +1,131 @@
+use super::syllable_parts::CLUSTERS;
+use crate::bytes_str::custom_regex::regex_pattern_to_custom_pattern;
+use crate::tokenizer::tcc::tcc_rules::replace_tcc_symbol;
+use lazy_static::lazy_static;
+use regex::bytes::Regex;
+
+/// Expands the syllable symbols, then the TCC symbols, of a pattern.
+///
+/// `o` is an initial consonant, one of the `CLUSTERS` or a consonant after
+/// a silent `ห` or `อ`, `f` an optional final consonant
+/// followed by silent letters under a thanthakhat (`ร์`, `ทร์`, `ดิ์`).
+#[inline(always)]
+pub fn replace_syllable_symbol(syllable_pattern: &str) -> String {
+    replace_tcc_symbol(
+        &syllable_pattern
+            .replace('o', &format!("(c|{}|ห[งญนมยรลว]|อย)", CLUSTERS.join("|")))
+            .replace('f', "(c?(c{1,2}[ิุ]?์)?)"),
+    )
+}
+
+lazy_static! {
+    /// Matches a whole written Thai syllable.
+    pub static ref SYLLABLE: Regex = Regex::new(&format!(
+        "^({})$",
+        [
+            r"otf",
+            r"otะ",
+            r"otาf",
+            r"otอf",
+            r"otวc",
+            r"otำ",
+            r"oัtวะ",
+            r"oัtวf",
+            r"oัtf",
+            r"o[ิีึืุู]tf",
+            r"o็",
+            r"oรรf",
+            r"เotาะ",
+            r"เotา",
+            r"เotะ",
+            r"เotf",
+            r"เo็tf",
+            r"เoิtf",
+            r"เoีtยะ",
+            r"เoีtยf",
+            r"เoืtอะ",
+            r"เoืtอf",
+            r"เotอะ",
+            r"เotอ",
+            r"แotะ",
+            r"แotf",
+            r"แo็tf",
+            r"โotะ",
+            r"โotf",
+            r"[ใไ]otf",
+            r"[ฤฦ]ๅ?",
+        ]
+        .iter()
+        .map(|&pattern| regex_pattern_to_custom_pattern(&replace_syllable_symbol(pattern)).unwrap())
+        .collect::<Vec<_>>()
+        .join("|")
+    ))
+    .unwrap();
+
+    /// Matches a syllable of consonants only, whose vowel is not written.
+    pub static ref BARE_SYLLABLE: Regex = Regex::new(&format!(
+        "^{}$",
+        regex_pattern_to_custom_pattern(&replace_syllable_symbol("otf")).unwrap()
+    ))
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytes_str::custom_string::CustomString;
+
+    #[test]
+    fn syllable_regex_test_cases() {
+        for syllable in [
+            "คน",
+            "ส",
+            "ค่ะ",
+            "บาย",
+            "น้ำ",
+            "ตัว",
+            "ควร",
+            "ที่",
+            "ก็",
+            "กรรม",
+            "เกาะ",
+            "เงา",
+            "เทศ",
+            "เด็ก",
+            "เดิน",
+            "เรียน",
+            "เรื่อง",
+            "เธอ",
+            "แล้ว",
+            "โต๊ะ",
+            "ไทย",
+            "ไม้",
+            "กว่า",
+            "หมู",
+            "อยู่",
+            "จันทร์",
+            "ศักดิ์",
+            "เล่ห์",
+            "ฤ",
+            "คอม",
+            "ร่อย",
+            "ด้วย",
+        ] {
+            assert!(
+                SYLLABLE.is_match(CustomString::new(syllable).raw_content()),
+                "Failed for syllable: {}",
+                syllable
+            );
+        }
+        for not_syllable in ["", "สบาย", "ประเทศ", "ไทยคน", "าก", "เ", "สวัส", "บริ"]
+        {
+            assert!(
+                !SYLLABLE.is_match(CustomString::new(not_syllable).raw_content()),
+                "Matched non-syllable: {}",
+                not_syllable
+            );
+        }
+        assert!(BARE_SYLLABLE.is_match(CustomString::new("คน").raw_content()));
+        assert!(!BARE_SYLLABLE.is_match(CustomString::new("คอม").raw_content()));
+    }
+}
@@ -0,0 +1,325 @@
+use super::syllable_parts::parse_syllable;
+use super::syllable_rules::{BARE_SYLLABLE, SYLLABLE};
+
+use crate::bytes_str::custom_string::{CustomString, FixedCharsLengthByteSlice};
+use crate::error::ThongnaResult;
+use crate::tokenizer::dict_reader::{create_dict_trie, DictSource};
+use crate::tokenizer::tcc::tcc_tokenizer::tcc_end_positions;
+use crate::tokenizer::token::{tokens_from_spans, Token};
+use crate::tokenizer::tokenizer_trait::Tokenizer;
+use crate::tokenizer::trie_char::TrieChar as Trie;
+
+/// Longest syllable to consider, in character clusters
+const MAX_SYLLABLE_CLUSTERS: usize = 5;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Thai,
+    Space,
+    Alphanumeric,
+    Other,
+}
+
+fn char_class(character: char) -> CharClass {
+    if ('\u{0E01}'..='\u{0E4E}').contains(&character) {
+        CharClass::Thai
+    } else if character.is_whitespace() {
+        CharClass::Space
+    } else if character.is_alphanumeric() {
+        CharClass::Alphanumeric
+    } else {
+        CharClass::Other
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SyllableKind {
+    /// Consonants only, the vowel is not written
+    Bare,
+    Written,
+}
+
+/// Kind of the syllable following another one, which can make the
+/// reading of the other one unlikely
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NextSyllable {
+    Other,
+    /// A bare syllable ending with a consonant, e.g. "นม" of "ข|นม"
+    ClosedBare,
+    /// A bare syllable ending with a consonant that ends the Thai text
+    LastClosedBare,
+}
+
+/// Cost of a segmentation, compared field by field
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct Cost {
+    /// Clusters that are not part of any known syllable
+    unknown: usize,
+    /// Bare syllables ending with a consonant right after a syllable that
+    /// ends with one, or, but for the last one, after a long vowel, as in
+    /// "หาน|คร" and "เท|พม|หา" where "หา|น|คร" and "เทพ|ม|หา" are read
+    misread_bare: usize,
+    syllables: usize,
+    /// Syllables without a written vowel
+    bare: usize,
+}
+
+/// Thai syllable tokenizer.
+///
+/// Thai text is cut at character cluster boundaries into written syllables,
+/// recognised by the patterns of `syllable_rules` or found in an optional
+/// syllable dictionary. Among the possible segmentations, the one with the
+/// fewest clusters left unrecognised, then the fewest syllables, then the
+/// fewest syllables without a written vowel, is kept. A bare syllable ending
+/// with a consonant rather follows a syllable read without a final, as in
+/// "ข|นม" and "หา|น|คร".
+/// Runs of spaces, of Latin letters or of digits are kept as one token,
+/// other characters are tokens of their own.
+///
+/// Syllables are written ones: "สบาย" is "ส", "บาย", and the
+/// implicit vowels of a word like "สวัสดี" are not restored.
+/// A syllable can straddle two words of running text, e.g. the "มอ" of
+/// "ขนมอร่อย", so segment words first when their boundaries matter.
+#[derive(Debug, Default)]
+pub struct SyllableTokenizer {
+    dictionary: Option<Trie>,
+}
+
+impl SyllableTokenizer {
+    /// Creates a tokenizer using the syllable patterns only.
+    pub fn new() -> Self {
+        Self { dictionary: None }
+    }
+
+    /// Creates a tokenizer that also accepts the syllables of a dictionary,
+    /// e.g. loanword syllables the patterns do not cover.
+    pub fn from_dictionary(source: DictSource) -> ThongnaResult<Self> {
+        Ok(Self {
+            dictionary: Some(create_dict_trie(source)?),
+        })
+    }
+
+    /// Split text into syllables, keeping the position of every syllable.
+    pub fn segment_with_offsets(&self, text: &str) -> Vec<Token> {
+        tokens_from_spans(text, &self.spans(text))
+    }
+
+    fn spans(&self, text: &str) -> Vec<(usize, usize)> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut spans = vec![];
+        let mut start = 0;
+        while start < chars.len() {
+            let class = char_class(chars[start]);
+            let mut end = start + 1;
+            if class != CharClass::Other {
+                while end < chars.len() && char_class(chars[end]) == class {
+                    end += 1;
+                }
+            }
+            if class == CharClass::Thai {
+                spans.extend(
+                    self.thai_spans(&chars[start..end])
+                        .into_iter()
+                        .map(|(begin, end)| (start + begin, start + end)),
+                );
+            } else {
+                spans.push((start, end));
+            }
+            start = end;
+        }
+        spans
+    }
+
+    /// Whether the clusters between `begin` and `end` make a syllable,
+    /// and if so whether its vowel is written
+    fn syllable_kind(
+        &self,
+        chars: &[char],
+        custom_text: &CustomString,
+        begin: usize,
+        end: usize,
+    ) -> Option<SyllableKind> {
+        let syllable = custom_text.raw_content().slice_by_char_indice(begin, end);
+        let in_dictionary = self.dictionary.as_ref().is_some_and(|dictionary| {
+            dictionary.contain(&CustomString::new(
+                &chars[begin..end].iter().collect::<String>(),
+            ))
+        });
+        if in_dictionary {
+            Some(SyllableKind::Written)
+        } else if BARE_SYLLABLE.is_match(syllable) {
+            Some(SyllableKind::Bare)
+        } else if SYLLABLE.is_match(syllable) {
+            Some(SyllableKind::Written)
+        } else {
+            None
+        }
+    }
+
+    /// Syllable spans of a run of Thai characters
+    fn thai_spans(&self, chars: &[char]) -> Vec<(usize, usize)> {
+        let custom_text = CustomString::new(&chars.iter().collect::<String>());
+        let boundaries: Vec<usize> = std::iter::once(0)
+            .chain(tcc_end_positions(custom_text.raw_content()))
+            .collect();
+        let last = boundaries.len() - 1;
+
+        // best[boundary][kind]: (cost, next boundary, next kind) from each
+        // boundary to the end, among the segmentations whose first syllable
+        // is of each `NextSyllable` kind
+        let mut best: Vec<[Option<(Cost, usize, usize)>; 3]> = vec![[None; 3]; boundaries.len()];
+        best[last][NextSyllable::Other as usize] = Some((Cost::default(), last, 0));
+        for from in (0..last).rev() {
+            for to in from + 1..=last.min(from + MAX_SYLLABLE_CLUSTERS) {
+                let kind =
+                    self.syllable_kind(chars, &custom_text, boundaries[from], boundaries[to]);
+                // the next cluster is always a candidate
+                if kind.is_none() && to != from + 1 {
+                    continue;
+                }
+                let parts = kind.and_then(|_| {
+                    parse_syllable(
+                        &chars[boundaries[from]..boundaries[to]]
+                            .iter()
+                            .collect::<String>(),
+                    )
+                });
+                let closed = parts
+                    .as_ref()
+                    .is_some_and(|parts| parts.final_sound.is_some());
+                let long_open = parts
+                    .as_ref()
+                    .is_some_and(|parts| parts.final_sound.is_none() && parts.vowel.is_long());
+                let syllable = match kind {
+                    Some(SyllableKind::Bare) if closed && to == last => {
+                        NextSyllable::LastClosedBare
+                    }
+                    Some(SyllableKind::Bare) if closed => NextSyllable::ClosedBare,
+                    _ => NextSyllable::Other,
+                };
+                for (next_syllable, next) in best[to].into_iter().enumerate() {
+                    let Some((next_cost, _, _)) = next else {
+                        continue;
+                    };
+                    let misread = (closed && next_syllable != NextSyllable::Other as usize)
+                        || (long_open && next_syllable == NextSyllable::ClosedBare as usize);
+                    let cost = Cost {
+                        unknown: next_cost.unknown + usize::from(kind.is_none()),
+                        misread_bare: next_cost.misread_bare + usize::from(misread),
+                        syllables: next_cost.syllables + 1,
+                        bare: next_cost.bare + usize::from(kind == Some(SyllableKind::Bare)),
+                    };
+                    let best_here = &mut best[from][syllable as usize];
+                    if best_here.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                        *best_here = Some((cost, to, next_syllable));
+                    }
+                }
+            }
+        }
+
+        let (mut syllable, _) = best[0]
+            .iter()
+            .enumerate()
+            .filter_map(|(syllable, best_here)| best_here.map(|(cost, _, _)| (syllable, cost)))
+            .min_by_key(|&(_, cost)| cost)
+            .unwrap();
+        let mut spans = vec![];
+        let mut from = 0;
+        while from < last {
+            let (_, to, next_syllable) = best[from][syllable].unwrap();
+            spans.push((boundaries[from], boundaries[to]));
+            from = to;
+            syllable = next_syllable;
+        }
+        spans
+    }
+}
+
+impl Tokenizer for SyllableTokenizer {
+    /// Syllables are found in a single linear pass,
+    /// so `safe` and `parallel` have no effect here.
+    fn segment(&self, text: &str, _safe: bool, _parallel: bool) -> ThongnaResult<Vec<String>> {
+        Ok(self
+            .segment_with_offsets(text)
+            .into_iter()
+            .map(|syllable| syllable.text)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syllables(tokenizer: &SyllableTokenizer, text: &str) -> Vec<String> {
+        tokenizer.segment(text, false, false).unwrap()
+    }
+
+    #[test]
+    fn test_syllable_tokenizer() {
+        let tokenizer = SyllableTokenizer::new();
+        assert_eq!(
+            syllables(&tokenizer, "ประเทศไทย"),
+            vec!["ประ", "เทศ", "ไทย"]
+        );
+        assert_eq!(syllables(&tokenizer, "สบายดี"), vec!["ส", "บาย", "ดี"]);
+        assert_eq!(
+            syllables(&tokenizer, "มหาวิทยาลัย"),
+            vec!["ม", "หา", "วิท", "ยา", "ลัย"]
+        );
+        assert_eq!(
+            syllables(&tokenizer, "ขนม อร่อยมากๆ ok 123"),
+            vec![
+                "ข",
+                "นม",
+                " ",
+                "อ",
+                "ร่อย",
+                "มาก",
+                "ๆ",
+                " ",
+                "ok",
+                " ",
+                "123"
+            ]
+        );
+        assert_eq!(
+            syllables(&tokenizer, "เรือน้อยลอยอยู่"),
+            vec!["เรือ", "น้อย", "ลอย", "อยู่"]
+        );
+        assert!(syllables(&tokenizer, "").is_empty());
+
+        let text = "กรุงเทพฯ, 2567";
+        let tokens = tokenizer.segment_with_offsets(text);
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["กรุง", "เทพ", "ฯ", ",", " ", "2567"]
+        );
+        for token in tokens {
+            assert_eq!(&text[token.byte_start..token.byte_end], token.text);
+        }
+
+        // "สว" is not a cluster, the "น" of "นคร" is a syllable of its own
+        assert_eq!(syllables(&tokenizer, "สวัสดี"), vec!["ส", "วัส", "ดี"]);
+        assert_eq!(
+            syllables(&tokenizer, "กรุงเทพมหานคร"),
+            vec!["กรุง", "เทพ", "ม", "หา", "น", "คร"]
+        );
+        assert_eq!(syllables(&tokenizer, "คนดี"), vec!["คน", "ดี"]);
+    }
+
+    #[test]
+    fn test_syllable_dictionary() {
+        let text = "พฤษภาคม";
+        let rules = SyllableTokenizer::new();
+        assert_eq!(syllables(&rules, text), vec!["พ", "ฤษ", "ภา", "คม"]);
+
+        let tokenizer =
+            SyllableTokenizer::from_dictionary(DictSource::WordList(vec!["พฤษ".to_string()]))
+                .unwrap();
+        assert_eq!(syllables(&tokenizer, text), vec!["พฤษ", "ภา", "คม"]);
+    }
+}
@@ -1,2 +1,4 @@
-pub(crate) mod tcc_tokenizer;
-pub(crate) mod tcc_rules;
\ No newline at end of file
+pub mod tcc_tokenizer;
+pub(crate) mod tcc_rules;
+
+pub use tcc_tokenizer::TccTokenizer;
@@ -1,14 +1,18 @@
 use super::tcc_rules::{LOOKAHEAD_TCC, NON_LOOKAHEAD_TCC};
 
 use crate::bytes_str::custom_string::{
-    CustomStringBytesSlice, FixedCharsLengthByteSlice, BYTES_PER_CHAR,
+    CustomString, CustomStringBytesSlice, FixedCharsLengthByteSlice, BYTES_PER_CHAR,
 };
+use crate::tokenizer::token::{tokens_from_spans, Token};
+use crate::error::ThongnaResult;
+use crate::tokenizer::tokenizer_trait::Tokenizer;
+use rayon::prelude::*;
 use rustc_hash::FxHashSet as HashSet;
 
-/// Returns a set of "character" indices at the end of each token
+/// Returns "character" indices at the end of each cluster, in increasing order
 #[inline]
-pub fn tcc_pos(custom_text_type: &CustomStringBytesSlice) -> HashSet<usize> {
-    let mut set = HashSet::with_capacity_and_hasher(custom_text_type.chars_len() / 10, Default::default());
+pub fn tcc_end_positions(custom_text_type: &CustomStringBytesSlice) -> Vec<usize> {
+    let mut positions = Vec::with_capacity(custom_text_type.chars_len() / 2);
     let mut txt = custom_text_type;
     let mut position: usize = 0;
 
@@ -20,22 +24,84 @@ pub fn tcc_pos(custom_text_type: &CustomStringBytesSlice) -> HashSet<usize> {
             if LOOKAHEAD_TCC.is_match(matched) {
                 let end_char_index = (match_length - BYTES_PER_CHAR) / BYTES_PER_CHAR;
                 position += end_char_index;
-                set.insert(position);
+                positions.push(position);
                 txt = txt.slice_by_char_indice(end_char_index, txt.chars_len());
             } else {
                 let segment_size = match_length / BYTES_PER_CHAR;
                 position += segment_size;
-                set.insert(position);
+                positions.push(position);
                 txt = txt.slice_by_char_indice(segment_size, txt.chars_len());
             }
         } else {
             // not thai
             position += 1;
-            set.insert(position);
+            positions.push(position);
             txt = txt.slice_by_char_indice(1, txt.chars_len());
         }
     }
-    set
+    positions
+}
+
+/// Returns a set of "character" indices at the end of each token
+#[inline]
+pub fn tcc_pos(custom_text_type: &CustomStringBytesSlice) -> HashSet<usize> {
+    tcc_end_positions(custom_text_type).into_iter().collect()
+}
+
+/// Thai Character Cluster (TCC) tokenizer.
+///
+/// A TCC is the smallest unit of Thai text that cannot be split without
+/// breaking a consonant from its vowels and tone marks, so cluster
+/// boundaries are safe places to cut text for subword models, cursor
+/// movement and truncation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TccTokenizer;
+
+impl TccTokenizer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Returns character indices at the end of each cluster, in increasing order
+    pub fn end_positions(&self, text: &str) -> Vec<usize> {
+        tcc_end_positions(CustomString::new(text).raw_content())
+    }
+
+    /// Split text into clusters, keeping the position of every cluster.
+    pub fn segment_with_offsets(&self, text: &str) -> Vec<Token> {
+        tokens_from_spans(text, &cluster_spans(&self.end_positions(text)))
+    }
+}
+
+fn cluster_spans(end_positions: &[usize]) -> Vec<(usize, usize)> {
+    let mut begin = 0;
+    end_positions
+        .iter()
+        .map(|&end| {
+            let span = (begin, end);
+            begin = end;
+            span
+        })
+        .collect()
+}
+
+impl Tokenizer for TccTokenizer {
+    /// Clusters are found in a single linear scan,
+    /// so `safe` has no effect here.
+    fn segment(&self, text: &str, _safe: bool, parallel: bool) -> ThongnaResult<Vec<String>> {
+        let input = CustomString::new(text);
+        let spans = cluster_spans(&tcc_end_positions(input.raw_content()));
+        let to_string = |&(start, end): &(usize, usize)| {
+            CustomString::convert_raw_bytes_to_std_string(
+                input.raw_content().slice_by_char_indice(start, end),
+            )
+        };
+        Ok(if parallel {
+            spans.par_iter().map(to_string).collect()
+        } else {
+            spans.iter().map(to_string).collect()
+        })
+    }
 }
 
 #[cfg(test)]
@@ -65,4 +131,23 @@ mod tests {
         assert!(gen_result.contains(&12));
         assert!(gen_result.contains(&15));
     }
+
+    #[test]
+    fn test_tcc_tokenizer() {
+        let tokenizer = TccTokenizer::new();
+        assert_eq!(
+            tokenizer.segment("ประเทศไทย", false, false).unwrap(),
+            vec!["ป", "ระ", "เท", "ศ", "ไท", "ย"]
+        );
+        assert_eq!(tokenizer.end_positions("พิสูจน์ได้ค่ะ"), vec![2, 7, 10, 13]);
+        assert!(tokenizer.segment("", false, false).unwrap().is_empty());
+
+        let text = "เรือน้อย ok";
+        let tokens = tokenizer.segment_with_offsets(text);
+        assert_eq!(
+            tokens.iter().map(|token| token.text.as_str()).collect::<Vec<_>>(),
+            tokenizer.segment(text, false, true).unwrap()
+        );
+        assert_eq!(tokens.last().unwrap().byte_end, text.len());
+    }
 }
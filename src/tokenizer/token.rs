@@ -0,0 +1,55 @@
+/// A token together with its position in the original input.
+///
+/// `start`/`end` are character (Unicode scalar value) indices,
+/// `byte_start`/`byte_end` are UTF-8 byte offsets, so
+/// `&text[token.byte_start..token.byte_end] == token.text`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Token {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+    pub byte_start: usize,
+    pub byte_end: usize,
+}
+
+/// Builds tokens from character spans over `text`.
+/// Spans must lie within `text`.
+pub(crate) fn tokens_from_spans(text: &str, spans: &[(usize, usize)]) -> Vec<Token> {
+    let byte_offsets: Vec<usize> = text
+        .char_indices()
+        .map(|(byte_index, _)| byte_index)
+        .chain(std::iter::once(text.len()))
+        .collect();
+
+    spans
+        .iter()
+        .map(|&(start, end)| {
+            let (byte_start, byte_end) = (byte_offsets[start], byte_offsets[end]);
+            Token {
+                text: text[byte_start..byte_end].to_string(),
+                start,
+                end,
+                byte_start,
+                byte_end,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_from_spans() {
+        let text = "ไข่ a臺灣";
+        let tokens = tokens_from_spans(text, &[(0, 3), (3, 4), (4, 5), (5, 7)]);
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(texts, vec!["ไข่", " ", "a", "臺灣"]);
+        assert_eq!((tokens[1].byte_start, tokens[1].byte_end), (9, 10));
+        assert_eq!((tokens[3].byte_start, tokens[3].byte_end), (11, 17));
+        for token in tokens {
+            assert_eq!(&text[token.byte_start..token.byte_end], token.text);
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::bytes_str::custom_regex::regex_pattern_to_custom_pattern;
+use crate::bytes_str::custom_string::{CustomStringBytesSlice, BYTES_PER_CHAR};
+use crate::error::{ThongnaError, ThongnaResult};
+
+use regex::bytes::Regex;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Patterns of the tokens newmm finds without a dictionary:
+/// Latin words, numbers, Thai numbers, spaces and line breaks.
+pub const DEFAULT_TOKEN_PATTERNS: &[&str; 5] = &[
+    r"(?x)^[-a-zA-Z]+",
+    r"(?x)^[0-9]+([,\.][0-9]+)*",
+    r"(?x)^[๐-๙]+([,\.][๐-๙]+)*",
+    r"(?x)^[\ \t]+",
+    r"(?x)^\r?\n",
+];
+
+/// Built-in patterns for tokens that no dictionary holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TokenPatternPreset {
+    /// `https://example.com/path`, `www.example.com`
+    Url,
+    /// `name@example.com`
+    Email,
+    /// `@user`
+    Mention,
+    /// `#hashtag`, `#แฮชแท็ก`
+    Hashtag,
+    /// Emoji, with their skin tone modifiers, variation selectors and
+    /// zero-width joiner sequences, and flags
+    Emoji,
+    /// `3.5kg`, `10%`, `25°C`
+    NumberWithUnit,
+}
+
+impl TokenPatternPreset {
+    pub const ALL: [TokenPatternPreset; 6] = [
+        TokenPatternPreset::Url,
+        TokenPatternPreset::Email,
+        TokenPatternPreset::Mention,
+        TokenPatternPreset::Hashtag,
+        TokenPatternPreset::Emoji,
+        TokenPatternPreset::NumberWithUnit,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenPatternPreset::Url => "url",
+            TokenPatternPreset::Email => "email",
+            TokenPatternPreset::Mention => "mention",
+            TokenPatternPreset::Hashtag => "hashtag",
+            TokenPatternPreset::Emoji => "emoji",
+            TokenPatternPreset::NumberWithUnit => "number_with_unit",
+        }
+    }
+
+    /// The regex of the preset, in the syntax accepted by [`TokenPatterns::new`]
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            TokenPatternPreset::Url => r"^(https?://|www\.)[-a-zA-Z0-9._~:/?#\[\]@!$&'()*+,;=%]+",
+            TokenPatternPreset::Email => {
+                r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9-]+(\.[a-zA-Z0-9-]+)*\.[a-zA-Z]{2,}"
+            }
+            TokenPatternPreset::Mention => r"^@\w+",
+            TokenPatternPreset::Hashtag => r"^#\w+",
+            TokenPatternPreset::Emoji => {
+                r"^(\p{Extended_Pictographic}\x{FE0F}?\p{Emoji_Modifier}?(\x{200D}\p{Extended_Pictographic}\x{FE0F}?\p{Emoji_Modifier}?)*|[\x{1F1E6}-\x{1F1FF}]{2})"
+            }
+            TokenPatternPreset::NumberWithUnit => r"^[0-9]+([,.][0-9]+)*(%|°[CF]?|[a-zA-Z]+)",
+        }
+    }
+}
+
+impl Display for TokenPatternPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for TokenPatternPreset {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        TokenPatternPreset::ALL
+            .into_iter()
+            .find(|preset| preset.name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown token pattern preset {:?}, expected one of {}",
+                    name,
+                    TokenPatternPreset::ALL
+                        .map(|preset| preset.name())
+                        .join(", ")
+                )
+            })
+    }
+}
+
+/// Regexes of tokens taken whole, before any dictionary lookup.
+///
+/// Where several patterns match, the first one in the list wins,
+/// so put specific patterns (URLs) before general ones (Latin words).
+/// Patterns are matched from the start of the remaining text,
+/// whether or not they begin with `^`.
+///
+/// The patterns of [`DEFAULT_TOKEN_PATTERNS`] are the exception: they only
+/// match where the dictionary has no word, so that dictionary words such as
+/// `COVID-19` are not cut into a Latin word and a number.
+#[derive(Clone, Debug)]
+pub struct TokenPatterns {
+    patterns: Vec<String>,
+    /// Patterns matched before the dictionary, `None` when there is none
+    regex: Option<Regex>,
+    /// Default patterns, matched after a dictionary miss
+    fallback_regex: Option<Regex>,
+}
+
+/// One regex matching any of `patterns` at the start of a custom string,
+/// `None` when there is no pattern
+fn joined_regex(patterns: &[&str]) -> ThongnaResult<Option<Regex>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let custom_patterns = patterns
+        .iter()
+        .map(|pattern| regex_pattern_to_custom_pattern(pattern))
+        .collect::<ThongnaResult<Vec<_>>>()?;
+    let joined = format!("^({})", custom_patterns.join("|"));
+    let regex = Regex::new(&joined).map_err(|error| ThongnaError::UnsupportedRegex {
+        pattern: patterns.join("|"),
+        reason: error.to_string(),
+    })?;
+    Ok(Some(regex))
+}
+
+impl TokenPatterns {
+    /// Use exactly `patterns`, in order of priority.
+    ///
+    /// Patterns go through [`regex_pattern_to_custom_pattern`], so a pattern
+    /// it cannot convert is an [`ThongnaError::UnsupportedRegex`] error.
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> ThongnaResult<Self> {
+        let (fallback_patterns, patterns): (Vec<&str>, Vec<&str>) = patterns
+            .iter()
+            .map(|pattern| pattern.as_ref())
+            .partition(|pattern| DEFAULT_TOKEN_PATTERNS.contains(pattern));
+        Ok(Self {
+            regex: joined_regex(&patterns)?,
+            fallback_regex: joined_regex(&fallback_patterns)?,
+            patterns: patterns
+                .iter()
+                .chain(&fallback_patterns)
+                .map(|pattern| pattern.to_string())
+                .collect(),
+        })
+    }
+
+    /// Use the patterns of `presets`, then the default patterns.
+    pub fn from_presets(presets: &[TokenPatternPreset]) -> Self {
+        let patterns: Vec<&str> = presets
+            .iter()
+            .map(TokenPatternPreset::pattern)
+            .chain(DEFAULT_TOKEN_PATTERNS.iter().copied())
+            .collect();
+        Self::new(&patterns).expect("built-in token patterns are supported")
+    }
+
+    /// The patterns, in order of priority
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Length in characters of the token at the start of `text` taken
+    /// before any dictionary lookup, if any. Empty matches do not count.
+    #[inline]
+    pub(crate) fn match_len(&self, text: &CustomStringBytesSlice) -> Option<usize> {
+        Self::regex_match_len(self.regex.as_ref(), text)
+    }
+
+    /// Length in characters of the token at the start of `text` where the
+    /// dictionary has no word, if any.
+    #[inline]
+    pub(crate) fn fallback_match_len(&self, text: &CustomStringBytesSlice) -> Option<usize> {
+        self.match_len(text)
+            .or_else(|| Self::regex_match_len(self.fallback_regex.as_ref(), text))
+    }
+
+    #[inline]
+    fn regex_match_len(regex: Option<&Regex>, text: &CustomStringBytesSlice) -> Option<usize> {
+        regex?
+            .find(text)
+            .map(|found| found.end() / BYTES_PER_CHAR)
+            .filter(|&length| length > 0)
+    }
+
+    /// Character spans of the tokens matched by the patterns
+    /// that start before character `until`, in order.
+    pub(crate) fn spans(&self, text: &CustomStringBytesSlice, until: usize) -> Vec<(usize, usize)> {
+        let mut spans = vec![];
+        if self.regex.is_none() && self.fallback_regex.is_none() {
+            return spans;
+        }
+        let chars_len = text.len() / BYTES_PER_CHAR;
+        let mut position = 0;
+        while position < chars_len.min(until) {
+            match self.fallback_match_len(&text[position * BYTES_PER_CHAR..]) {
+                Some(length) => {
+                    spans.push((position, position + length));
+                    position += length;
+                }
+                None => position += 1,
+            }
+        }
+        spans
+    }
+}
+
+impl Default for TokenPatterns {
+    fn default() -> Self {
+        Self::from_presets(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytes_str::custom_string::CustomString;
+
+    fn matched(patterns: &TokenPatterns, text: &str) -> Vec<String> {
+        let chars: Vec<char> = text.chars().collect();
+        patterns
+            .spans(CustomString::new(text).raw_content(), usize::MAX)
+            .into_iter()
+            .map(|(start, end)| chars[start..end].iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_presets() {
+        let patterns = TokenPatterns::from_presets(&TokenPatternPreset::ALL);
+        assert_eq!(
+            matched(
+                &patterns,
+                "ดูที่ https://thongna.dev/a?b=1 หรือ me@mail.co.th @นุ่น #ข้าวมันไก่ 👍🏽👨‍👩‍👧🇹🇭 3.5kg 10% 25°C"
+            ),
+            vec![
+                " ",
+                "https://thongna.dev/a?b=1",
+                " ",
+                " ",
+                "me@mail.co.th",
+                " ",
+                "@นุ่น",
+                " ",
+                "#ข้าวมันไก่",
+                " ",
+                "👍🏽",
+                "👨‍👩‍👧",
+                "🇹🇭",
+                " ",
+                "3.5kg",
+                " ",
+                "10%",
+                " ",
+                "25°C"
+            ]
+        );
+        for preset in TokenPatternPreset::ALL {
+            assert_eq!(preset.name().parse::<TokenPatternPreset>(), Ok(preset));
+        }
+        assert!("phone".parse::<TokenPatternPreset>().is_err());
+    }
+
+    #[test]
+    fn test_custom_patterns() {
+        let patterns = TokenPatterns::new(&[r"[A-Z]{2}-\d+", r"x*"]).unwrap();
+        assert_eq!(matched(&patterns, "รหัส TH-1234 ok"), vec!["TH-1234"]);
+        assert!(TokenPatterns::new(&[r"\bword"]).is_err());
+        assert!(TokenPatterns::new::<&str>(&[])
+            .unwrap()
+            .spans(CustomString::new("abc").raw_content(), usize::MAX)
+            .is_empty());
+    }
+}
@@ -1,6 +1,5 @@
-use anyhow::Result as AnyResult;
+use crate::error::ThongnaResult;
 
 pub trait Tokenizer {
-    fn segment(&self, text: &str, safe: bool, parallel: bool) -> AnyResult<Vec<String>>;
-    fn segment_to_string(&self, text: &str, safe: bool, parallel: bool) -> Vec<String>;
+    fn segment(&self, text: &str, safe: bool, parallel: bool) -> ThongnaResult<Vec<String>>;
 }
@@ -1,136 +1,366 @@
 use crate::bytes_str::custom_string::{
-    CustomString, CustomStringBytesSlice, CustomStringBytesVec, FixedCharsLengthByteSlice,
+    decode_custom_char, CustomString, CustomStringBytesSlice, CustomStringBytesVec,
+    FixedCharsLengthByteSlice, BYTES_PER_CHAR,
 };
 
-use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
-use std::borrow::BorrowMut;
+use super::completion::{EditMatch, EditMatcher, EditQuery};
+use super::dictionary::Dictionary;
 
-#[derive(Debug)]
+/// Index of a node in the arena of a [`TrieChar`]
+type NodeIndex = u32;
+
+const ROOT: NodeIndex = 0;
+
+#[derive(Clone, Copy, Debug, Default)]
 struct TrieNode {
-    ///
-    children: HashMap<char, Self>,
+    /// Start of the children of this node in `TrieChar::edges`
+    edges_start: u32,
+    /// Number of children
+    edges_len: u32,
+    /// Frequency of the word ending at this node, if `end`
+    frequency: u64,
     end: bool,
 }
 
-impl Default for TrieNode {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl TrieNode {
-    pub fn new() -> Self {
-        Self {
-            children: HashMap::default(),
-            end: false,
-        }
-    }
-
-    fn find_child(&self, word: &char) -> Option<&Self> {
-        self.children.get(word)
-    }
-
-    #[allow(dead_code)]
-    fn remove_child(&mut self, word: &char) {
-        self.children.remove(word);
-    }
-
-    #[allow(dead_code)]
-    fn find_mut_child(&mut self, word: &char) -> Option<&mut Self> {
-        self.children.get_mut(word)
+    fn edges_range(&self) -> std::ops::Range<usize> {
+        self.edges_start as usize..(self.edges_start + self.edges_len) as usize
     }
 
-    #[allow(dead_code)]
-    fn set_not_end(&mut self) {
-        self.end = false;
+    fn word_frequency(&self) -> Option<u64> {
+        self.end.then_some(self.frequency)
     }
 
-    fn add_word(&mut self, input_word: &CustomString) {
-        // thanks to https://stackoverflow.com/questions/36957286/how-do-you-implement-this-simple-trie-node-in-rust
-        if input_word.is_empty() {
-            self.end = true;
-            return;
-        }
-        self.children
-            .entry(*input_word.get_chars_content().first().unwrap())
-            .or_insert_with(TrieNode::new)
-            .add_word(&input_word.substring(1, input_word.chars_len()));
-    }
-
-    fn remove_word(&mut self, input_word: &CustomString) {
-        let mut word = input_word;
-        let char_count = word.chars_len();
-        // if has at least 1 char
-        if char_count >= 1 {
-            let character = word.get_chars_content().first().unwrap();
-            if let Some(child) = self.find_mut_child(character) {
-                // move 1 character
-                let substring_of_word = word.substring(1, word.chars_len());
-                if char_count == 1 {
-                    child.set_not_end();
-                }
-                word = &substring_of_word;
-                child.remove_word(word);
-                if !child.end && child.children.is_empty() {
-                    self.remove_child(character);
-                }
-            };
-        }
+    fn is_unused(&self) -> bool {
+        !self.end && self.edges_len == 0
     }
 }
 
 #[derive(Debug)]
-/// This version of Trie still stores custom bytes vector as words,
-/// but prefix operation and its node uses char instead.
+/// A character trie stored in two flat arenas.
+///
+/// `nodes` holds every node and `edges` holds the children of every node as
+/// (character, child) pairs, the children of one node being contiguous and
+/// sorted by character. A lookup is then a binary search over a short slice
+/// instead of a hash map probe, and words are stored only once, as paths in
+/// the trie. Nodes freed by `remove` are reused by later additions; edges
+/// left behind when a node outgrows its slice are reclaimed by compaction.
+///
+/// Every word carries a frequency, 0 when the dictionary has none.
 pub struct TrieChar {
-    words: HashSet<CustomStringBytesVec>,
-    root: TrieNode,
+    nodes: Vec<TrieNode>,
+    edges: Vec<(char, NodeIndex)>,
+    free_nodes: Vec<NodeIndex>,
+    /// Number of entries of `edges` that no node refers to anymore
+    unused_edges: usize,
+    amount_of_words: usize,
+    total_frequency: u64,
 }
 
 impl TrieChar {
     pub fn new(words: &[CustomString]) -> Self {
         let mut instance = Self {
-            words: HashSet::default(),
-            root: TrieNode::new(),
+            nodes: vec![TrieNode::default()],
+            edges: vec![],
+            free_nodes: vec![],
+            unused_edges: 0,
+            amount_of_words: 0,
+            total_frequency: 0,
         };
         for word in words.iter() {
             instance.add(word);
         }
+        instance.compact();
+        instance
+    }
+
+    pub fn new_with_frequencies(words: &[(CustomString, u64)]) -> Self {
+        let mut instance = Self::new(&[]);
+        for (word, frequency) in words.iter() {
+            instance.add_with_frequency(word, *frequency);
+        }
+        instance.compact();
         instance
     }
 
-    #[allow(dead_code)]
-    fn remove_word_from_set(&mut self, word: &CustomString) {
-        self.words.remove(word.raw_content());
+    fn node(&self, index: NodeIndex) -> &TrieNode {
+        &self.nodes[index as usize]
+    }
+
+    fn node_mut(&mut self, index: NodeIndex) -> &mut TrieNode {
+        &mut self.nodes[index as usize]
+    }
+
+    fn children(&self, index: NodeIndex) -> &[(char, NodeIndex)] {
+        &self.edges[self.node(index).edges_range()]
+    }
+
+    fn find_child(&self, index: NodeIndex, character: char) -> Option<NodeIndex> {
+        let children = self.children(index);
+        children
+            .binary_search_by_key(&character, |&(child_char, _)| child_char)
+            .ok()
+            .map(|position| children[position].1)
     }
 
+    fn new_node(&mut self) -> NodeIndex {
+        match self.free_nodes.pop() {
+            Some(index) => index,
+            None => {
+                self.nodes.push(TrieNode::default());
+                (self.nodes.len() - 1) as NodeIndex
+            }
+        }
+    }
+
+    /// Inserts a child at `position` among the children of `index`.
+    /// Children not at the end of `edges` are moved there first, so they can grow.
+    fn insert_child(&mut self, index: NodeIndex, position: usize, character: char) -> NodeIndex {
+        let child = self.new_node();
+        let node = *self.node(index);
+        if node.edges_range().end != self.edges.len() {
+            let new_start = self.edges.len();
+            self.edges.extend_from_within(node.edges_range());
+            self.unused_edges += node.edges_len as usize;
+            self.node_mut(index).edges_start = new_start as u32;
+        }
+        let edges_start = self.node(index).edges_start as usize;
+        self.edges
+            .insert(edges_start + position, (character, child));
+        self.node_mut(index).edges_len += 1;
+        child
+    }
+
+    /// Removes the child at `position` among the children of `index`.
+    fn remove_child(&mut self, index: NodeIndex, position: usize) {
+        let range = self.node(index).edges_range();
+        self.edges[range.start + position..range.end].rotate_left(1);
+        self.node_mut(index).edges_len -= 1;
+        self.unused_edges += 1;
+    }
+
+    /// Rebuilds both arenas in breadth-first order, dropping unused edges and
+    /// freed nodes, so that the children of a node are stored next to each other.
+    fn compact(&mut self) {
+        let mut nodes = Vec::with_capacity(self.nodes.len() - self.free_nodes.len());
+        let mut edges = Vec::with_capacity(self.edges.len() - self.unused_edges);
+        nodes.push(*self.node(ROOT));
+        let mut current = 0;
+        while current < nodes.len() {
+            let edges_start = edges.len() as u32;
+            for &(character, child) in &self.edges[nodes[current].edges_range()] {
+                edges.push((character, nodes.len() as NodeIndex));
+                nodes.push(*self.node(child));
+            }
+            nodes[current].edges_start = edges_start;
+            current += 1;
+        }
+        self.nodes = nodes;
+        self.edges = edges;
+        self.free_nodes.clear();
+        self.unused_edges = 0;
+    }
+
+    /// Compacts the arenas once most of their entries are unused.
+    fn compact_if_sparse(&mut self) {
+        if self.unused_edges > self.edges.len() / 2 || self.free_nodes.len() > self.nodes.len() / 2
+        {
+            self.compact();
+        }
+    }
+
+    /// Returns the node reached by following `characters` from the root.
+    fn find_node(&self, characters: impl Iterator<Item = char>) -> Option<NodeIndex> {
+        let mut current = ROOT;
+        for character in characters {
+            current = self.find_child(current, character)?;
+        }
+        Some(current)
+    }
+
+    /// Returns the node of `word`, creating the missing nodes along its path.
+    fn find_or_insert_node(&mut self, word: &CustomString) -> NodeIndex {
+        let mut current = ROOT;
+        for &character in word.get_chars_content() {
+            current = match self
+                .children(current)
+                .binary_search_by_key(&character, |&(child_char, _)| child_char)
+            {
+                Ok(position) => self.children(current)[position].1,
+                Err(position) => self.insert_child(current, position, character),
+            };
+        }
+        current
+    }
+
+    /// Adds a word, keeping its frequency if it is already in the dictionary.
     pub fn add(&mut self, word: &CustomString) {
+        let stripped_word = word.trim();
+        if !stripped_word.is_empty() && !self.contain(&stripped_word) {
+            self.add_with_frequency(&stripped_word, 0);
+        }
+    }
+
+    /// Adds a word, replacing its frequency if it is already in the dictionary.
+    pub fn add_with_frequency(&mut self, word: &CustomString, frequency: u64) {
         let stripped_word = word.trim();
         if !stripped_word.is_empty() {
-            self.words.insert(stripped_word.raw_content().into());
-            let current_cursor = self.root.borrow_mut();
-            current_cursor.add_word(&stripped_word);
+            let index = self.find_or_insert_node(&stripped_word);
+            let node = self.node_mut(index);
+            let previous = node.word_frequency();
+            (node.frequency, node.end) = (frequency, true);
+            self.total_frequency = self.total_frequency - previous.unwrap_or_default() + frequency;
+            if previous.is_none() {
+                self.amount_of_words += 1;
+            }
+            self.compact_if_sparse();
         }
     }
 
     pub fn remove(&mut self, word: &CustomString) {
         let stripped_word = word.trim();
-        if !stripped_word.is_empty() && self.words.contains(stripped_word.raw_content()) {
-            self.remove_word_from_set(&stripped_word);
-            self.root.remove_word(&stripped_word); // remove from node
+        if stripped_word.is_empty() {
+            return;
+        }
+        // (parent, character) of every edge on the path of the word
+        let mut path: Vec<(NodeIndex, char)> = Vec::with_capacity(stripped_word.chars_len());
+        let mut current = ROOT;
+        for &character in stripped_word.get_chars_content() {
+            let Some(child) = self.find_child(current, character) else {
+                return;
+            };
+            path.push((current, character));
+            current = child;
+        }
+        let Some(frequency) = self.node(current).word_frequency() else {
+            return;
+        };
+        let node = self.node_mut(current);
+        (node.frequency, node.end) = (0, false);
+        self.amount_of_words -= 1;
+        self.total_frequency -= frequency;
+
+        // prune the nodes that no longer lead to any word
+        for (parent, character) in path.into_iter().rev() {
+            if !self.node(current).is_unused() {
+                break;
+            }
+            if let Ok(position) = self
+                .children(parent)
+                .binary_search_by_key(&character, |&(child_char, _)| child_char)
+            {
+                self.remove_child(parent, position);
+            }
+            *self.node_mut(current) = TrieNode::default();
+            self.free_nodes.push(current);
+            current = parent;
         }
+        self.compact_if_sparse();
     }
-    #[allow(dead_code)]
+
     pub fn contain(&self, word: &CustomString) -> bool {
-        self.words.contains(word.raw_content())
+        self.find_node(word.get_chars_content().iter().copied())
+            .is_some_and(|index| self.node(index).end)
+    }
+
+    /// Returns every word as custom string bytes, in no particular order.
+    pub fn iterate(&self) -> impl Iterator<Item = CustomStringBytesVec> + '_ {
+        self.words_with_frequency()
+            .into_iter()
+            .map(|(word, _)| CustomString::new(&word).raw_content().to_vec())
+    }
+
+    /// Returns every word with its frequency, in the order of the trie.
+    fn words_with_frequency(&self) -> Vec<(String, u64)> {
+        let mut result = Vec::with_capacity(self.amount_of_words);
+        let mut word = String::new();
+        // (node, character leading to the node, whether the node has been visited)
+        let mut stack: Vec<(NodeIndex, Option<char>, bool)> = vec![(ROOT, None, false)];
+        while let Some((index, character, visited)) = stack.pop() {
+            if visited {
+                if character.is_some() {
+                    word.pop();
+                }
+                continue;
+            }
+            if let Some(character) = character {
+                word.push(character);
+            }
+            stack.push((index, character, true));
+            if let Some(frequency) = self.node(index).word_frequency() {
+                result.push((word.clone(), frequency));
+            }
+            for &(child_char, child) in self.children(index).iter().rev() {
+                stack.push((child, Some(child_char), false));
+            }
+        }
+        result
+    }
+
+    /// Visits the nodes below the root depth first, in the order of the trie,
+    /// with the characters leading to each node and the frequency of the word
+    /// ending there, if any. The nodes below a node are visited only if
+    /// `visit` returns true for it, so a search can prune whole subtrees.
+    pub fn walk(&self, mut visit: impl FnMut(&[char], Option<u64>) -> bool) {
+        let mut path: Vec<char> = vec![];
+        // (node, depth of the node, character leading to the node)
+        let mut stack: Vec<(NodeIndex, usize, char)> = vec![];
+        let push_children = |stack: &mut Vec<_>, index: NodeIndex, depth: usize| {
+            for &(character, child) in self.children(index).iter().rev() {
+                stack.push((child, depth + 1, character));
+            }
+        };
+        push_children(&mut stack, ROOT, 0);
+        while let Some((index, depth, character)) = stack.pop() {
+            path.truncate(depth - 1);
+            path.push(character);
+            if visit(&path, self.node(index).word_frequency()) {
+                push_children(&mut stack, index, depth);
+            }
+        }
+    }
+
+    /// Returns the words matching `query`, in the order of the trie.
+    /// Branches that can no longer match are not visited.
+    pub fn fuzzy_search(&self, query: &EditQuery) -> Vec<EditMatch> {
+        let mut matcher = EditMatcher::new(query);
+        let mut matches = vec![];
+        self.walk(|path, frequency| {
+            let reachable = matcher.step(path.len(), path[path.len() - 1]);
+            if let (Some(frequency), Some(cost)) = (frequency, matcher.cost()) {
+                matches.push(EditMatch {
+                    word: path.iter().collect(),
+                    cost,
+                    frequency,
+                });
+            }
+            reachable
+        });
+        matches
     }
-    #[allow(dead_code)]
-    pub fn iterate(&self) -> std::collections::hash_set::Iter<'_, Vec<u8>> {
-        self.words.iter()
+
+    /// Returns the frequency of a word given as custom string bytes,
+    /// or None if the word is not in the dictionary.
+    pub fn frequency(&self, word: &CustomStringBytesSlice) -> Option<u64> {
+        let characters = word
+            .chunks_exact(BYTES_PER_CHAR)
+            .map(decode_custom_char)
+            .collect::<Option<Vec<char>>>()?;
+        self.node(self.find_node(characters.into_iter())?)
+            .word_frequency()
+    }
+    /// Sum of the frequencies of all words.
+    pub fn total_frequency(&self) -> u64 {
+        self.total_frequency
     }
-    #[allow(dead_code)]
     pub fn amount_of_words(&self) -> usize {
-        self.words.len()
+        self.amount_of_words
+    }
+    /// Approximate heap memory used by the trie, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.nodes.capacity() * std::mem::size_of::<TrieNode>()
+            + self.edges.capacity() * std::mem::size_of::<(char, NodeIndex)>()
+            + self.free_nodes.capacity() * std::mem::size_of::<NodeIndex>()
     }
     /// Returns a vec of substring (as reference) as produced by words stored in dict_trie.
     pub fn prefix_ref<'p>(
@@ -138,30 +368,66 @@ impl TrieChar {
         dict_trie: &Self,
     ) -> Vec<&'p CustomStringBytesSlice> {
         let mut result: Vec<&[u8]> = vec![];
-        let prefix_cpy = prefix;
-        let mut current_index = 0;
-        let mut current_node_wrap = Some(&dict_trie.root);
-        while current_index < prefix_cpy.chars_len() {
-            let character = prefix_cpy.get_char_at(current_index);
-            if let Some(current_node) = current_node_wrap {
-                if let Some(child) = current_node.find_child(&character) {
-                    if child.end {
-                        let substring_of_prefix = prefix_cpy
-                            .raw_content()
-                            .slice_by_char_indice(0, current_index + 1);
-                        result.push(substring_of_prefix);
-                    }
-                    current_node_wrap = Some(child);
-                } else {
-                    break;
-                }
+        let mut current = ROOT;
+        for (current_index, &character) in prefix.get_chars_content().iter().enumerate() {
+            let Some(child) = dict_trie.find_child(current, character) else {
+                break;
+            };
+            if dict_trie.node(child).end {
+                result.push(
+                    prefix
+                        .raw_content()
+                        .slice_by_char_indice(0, current_index + 1),
+                );
             }
-            current_index = current_index + 1;
+            current = child;
         }
         result
     }
 }
 
+impl Dictionary for TrieChar {
+    fn prefix_ref<'p>(&self, prefix: &'p CustomString) -> Vec<&'p CustomStringBytesSlice> {
+        TrieChar::prefix_ref(prefix, self)
+    }
+
+    fn contain(&self, word: &CustomString) -> bool {
+        TrieChar::contain(self, word)
+    }
+
+    fn frequency(&self, word: &CustomStringBytesSlice) -> Option<u64> {
+        TrieChar::frequency(self, word)
+    }
+
+    fn total_frequency(&self) -> u64 {
+        self.total_frequency
+    }
+
+    fn amount_of_words(&self) -> usize {
+        TrieChar::amount_of_words(self)
+    }
+
+    fn add(&mut self, word: &CustomString) {
+        TrieChar::add(self, word)
+    }
+
+    fn add_with_frequency(&mut self, word: &CustomString, frequency: u64) {
+        TrieChar::add_with_frequency(self, word, frequency)
+    }
+
+    fn remove(&mut self, word: &CustomString) {
+        TrieChar::remove(self, word)
+    }
+
+    fn words(&self) -> Vec<(String, u64)> {
+        self.words_with_frequency()
+    }
+
+    fn fuzzy_search(&self, query: &EditQuery) -> Vec<EditMatch> {
+        TrieChar::fuzzy_search(self, query)
+    }
+}
+
 #[test]
 fn test_add_and_remove_word() {
     let mut trie = TrieChar::new(&[CustomString::new("ศาล")]);
@@ -181,3 +447,84 @@ fn test_add_and_remove_word() {
     trie.remove(&CustomString::new(""));
     assert_eq!(trie.amount_of_words(), 0);
 }
+
+#[test]
+fn test_word_frequency() {
+    let mut trie = TrieChar::new_with_frequencies(&[
+        (CustomString::new("ศาล"), 10),
+        (CustomString::new("ศาลา"), 5),
+    ]);
+    assert_eq!(trie.total_frequency(), 15);
+    assert_eq!(
+        trie.frequency(CustomString::new("ศาลา").raw_content()),
+        Some(5)
+    );
+    trie.add(&CustomString::new("ศาลา"));
+    assert_eq!(
+        trie.frequency(CustomString::new("ศาลา").raw_content()),
+        Some(5)
+    );
+    trie.add_with_frequency(&CustomString::new("ศาลา"), 2);
+    assert_eq!(trie.total_frequency(), 12);
+    trie.remove(&CustomString::new("ศาล"));
+    assert_eq!(trie.total_frequency(), 2);
+    assert_eq!(trie.frequency(CustomString::new("ศาล").raw_content()), None);
+    assert_eq!(
+        trie.frequency(CustomString::new("ศาลา").raw_content()),
+        Some(2)
+    );
+}
+
+#[test]
+fn test_prefix_ref_and_node_reuse() {
+    let mut trie = TrieChar::new(&[
+        CustomString::new("ศาล"),
+        CustomString::new("ศาลา"),
+        CustomString::new("ศาลาว่าการ"),
+        CustomString::new("กา"),
+    ]);
+    let text = CustomString::new("ศาลาว่าการจังหวัด");
+    let prefixes: Vec<String> = TrieChar::prefix_ref(&text, &trie)
+        .into_iter()
+        .map(CustomString::convert_raw_bytes_to_std_string)
+        .collect();
+    assert_eq!(prefixes, vec!["ศาล", "ศาลา", "ศาลาว่าการ"]);
+    assert!(trie.contain(&CustomString::new("ศาลา")));
+    assert!(!trie.contain(&CustomString::new("ศา")));
+
+    let nodes = trie.nodes.len();
+    trie.remove(&CustomString::new("ศาลาว่าการ"));
+    assert_eq!(trie.free_nodes.len(), 6);
+    assert!(trie.contain(&CustomString::new("ศาลา")));
+    trie.add(&CustomString::new("ศาลาวัด"));
+    assert_eq!(trie.nodes.len(), nodes);
+
+    let mut words = trie.words();
+    words.sort();
+    let words: Vec<&str> = words.iter().map(|(word, _)| word.as_str()).collect();
+    assert_eq!(words, vec!["กา", "ศาล", "ศาลา", "ศาลาวัด"]);
+    assert_eq!(trie.iterate().count(), 4);
+}
+
+#[test]
+fn test_walk() {
+    let trie = TrieChar::new_with_frequencies(&[
+        (CustomString::new("กา"), 3),
+        (CustomString::new("กาว"), 1),
+        (CustomString::new("ขา"), 2),
+    ]);
+    let mut visited = vec![];
+    trie.walk(|path, frequency| {
+        visited.push((path.iter().collect::<String>(), frequency));
+        path.len() < 2
+    });
+    assert_eq!(
+        visited,
+        vec![
+            ("ก".to_string(), None),
+            ("กา".to_string(), Some(3)),
+            ("ข".to_string(), None),
+            ("ขา".to_string(), Some(2)),
+        ]
+    );
+}
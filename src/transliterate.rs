@@ -0,0 +1,316 @@
+//! Grapheme-to-phoneme transliteration of Thai words into the
+//! International Phonetic Alphabet (IPA), with tones.
+
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use rayon::prelude::*;
+
+use crate::error::{ThongnaError, ThongnaResult};
+use crate::tokenizer::dict_reader::{read_dict_lines, DictSource};
+use crate::tokenizer::syllable::syllable_parts::{
+    parse_syllable, FinalSound, SyllableParts, Tone, VowelSound,
+};
+use crate::tokenizer::syllable::SyllableTokenizer;
+
+lazy_static! {
+    static ref DEFAULT_TRANSLITERATOR: Transliterator = Transliterator::new();
+}
+
+/// IPA of a consonant starting a syllable
+fn initial_ipa(consonant: char) -> &'static str {
+    match consonant {
+        'ก' => "k",
+        'ข' | 'ฃ' | 'ค' | 'ฅ' | 'ฆ' => "kʰ",
+        'ง' => "ŋ",
+        'จ' => "tɕ",
+        'ฉ' | 'ช' | 'ฌ' => "tɕʰ",
+        'ซ' | 'ศ' | 'ษ' | 'ส' => "s",
+        'ญ' | 'ย' => "j",
+        'ฎ' | 'ด' => "d",
+        'ฏ' | 'ต' => "t",
+        'ฐ' | 'ฑ' | 'ฒ' | 'ถ' | 'ท' | 'ธ' => "tʰ",
+        'ณ' | 'น' => "n",
+        'บ' => "b",
+        'ป' => "p",
+        'ผ' | 'พ' | 'ภ' => "pʰ",
+        'ฝ' | 'ฟ' => "f",
+        'ม' => "m",
+        'ร' => "r",
+        'ล' | 'ฬ' => "l",
+        'ว' => "w",
+        'ห' | 'ฮ' => "h",
+        'อ' => "ʔ",
+        _ => "",
+    }
+}
+
+fn vowel_ipa(vowel: VowelSound) -> &'static str {
+    match vowel {
+        VowelSound::A => "a",
+        VowelSound::Aa => "aː",
+        VowelSound::I => "i",
+        VowelSound::Ii => "iː",
+        VowelSound::Ue => "ɯ",
+        VowelSound::Uee => "ɯː",
+        VowelSound::U => "u",
+        VowelSound::Uu => "uː",
+        VowelSound::E => "e",
+        VowelSound::Ee => "eː",
+        VowelSound::Ae => "ɛ",
+        VowelSound::Aee => "ɛː",
+        VowelSound::O => "o",
+        VowelSound::Oo => "oː",
+        VowelSound::Or => "ɔ",
+        VowelSound::Orr => "ɔː",
+        VowelSound::Oe => "ɤ",
+        VowelSound::Oee => "ɤː",
+        VowelSound::Ia => "ia",
+        VowelSound::Iia => "iːa",
+        VowelSound::Uea => "ɯa",
+        VowelSound::Ueea => "ɯːa",
+        VowelSound::Ua => "ua",
+        VowelSound::Uua => "uːa",
+    }
+}
+
+fn final_ipa(final_sound: FinalSound) -> &'static str {
+    match final_sound {
+        FinalSound::K => "k",
+        FinalSound::T => "t",
+        FinalSound::P => "p",
+        FinalSound::Ng => "ŋ",
+        FinalSound::N => "n",
+        FinalSound::M => "m",
+        FinalSound::Y => "j",
+        FinalSound::W => "w",
+    }
+}
+
+/// Chao tone letters
+fn tone_ipa(tone: Tone) -> &'static str {
+    match tone {
+        Tone::Mid => "˧",
+        Tone::Low => "˨˩",
+        Tone::Falling => "˥˩",
+        Tone::High => "˦˥",
+        Tone::Rising => "˩˩˦",
+    }
+}
+
+fn syllable_ipa(parts: &SyllableParts) -> String {
+    let initial: String = parts
+        .initial
+        .iter()
+        .map(|&consonant| initial_ipa(consonant))
+        .collect();
+    let final_sound = match parts.final_sound {
+        Some(final_sound) => final_ipa(final_sound),
+        // a short vowel ends with a glottal stop
+        None if !parts.vowel.is_long() => "ʔ",
+        None => "",
+    };
+    format!(
+        "{}{}{}{}",
+        initial,
+        vowel_ipa(parts.vowel),
+        final_sound,
+        tone_ipa(parts.tone())
+    )
+}
+
+/// IPA of a syllable or other token cut by [`SyllableTokenizer`].
+/// Thai digits become Arabic digits, other non-Thai text is kept.
+fn token_ipa(token: &str) -> String {
+    if let Some(parts) = parse_syllable(token) {
+        return syllable_ipa(&parts);
+    }
+    token
+        .chars()
+        .filter_map(|character| match character {
+            '๐'..='๙' => char::from_digit(character as u32 - '๐' as u32, 10),
+            '\u{0E01}'..='\u{0E4F}' => None,
+            _ => Some(character),
+        })
+        .collect()
+}
+
+/// Transcribes Thai words into IPA, with a Chao tone letter after every
+/// syllable and syllables separated by `.`.
+///
+/// Words are cut into written syllables by a [`SyllableTokenizer`]. The
+/// sounds of each syllable are read from its spelling, as for
+/// [`Romanizer`](crate::Romanizer), and its tone from the class of its
+/// initial consonant, whether it is live or dead, and its tone mark.
+/// Words whose pronunciation does not follow their spelling, like the
+/// falling tone of "ก็" or the unwritten vowels of "ราชการ", can be given
+/// in an exceptions dictionary.
+///
+/// ```
+/// use thongna::Transliterator;
+///
+/// let transliterator = Transliterator::new();
+/// assert_eq!(transliterator.transliterate("ข้าว"), "kʰaːw˥˩");
+/// assert_eq!(
+///     transliterator.transliterate_words(&["ภาษา", "ไทย"]),
+///     vec!["pʰaː˧.saː˩˩˦", "tʰaj˧"]
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct Transliterator {
+    syllable_tokenizer: SyllableTokenizer,
+    exceptions: HashMap<String, String>,
+}
+
+impl Transliterator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a transliterator that reads the words of an exceptions
+    /// dictionary as given there.
+    ///
+    /// Every line of the dictionary is a word and its IPA, separated by a
+    /// tab (`word<TAB>ipa`); blank lines are skipped.
+    pub fn from_exceptions(source: DictSource) -> ThongnaResult<Self> {
+        let mut exceptions = HashMap::new();
+        for (index, line) in read_dict_lines(source)?.into_iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (word, ipa) =
+                line.split_once('\t')
+                    .ok_or_else(|| ThongnaError::InvalidDictionaryLine {
+                        line_number: index + 1,
+                        reason: "expected a word and its IPA separated by a tab".to_string(),
+                    })?;
+            exceptions.insert(word.trim().to_string(), ipa.trim().to_string());
+        }
+        Ok(Self::default().with_exceptions(exceptions))
+    }
+
+    /// Read the given words as their IPA, in addition to the exceptions
+    /// already known
+    pub fn with_exceptions(
+        mut self,
+        exceptions: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        self.exceptions.extend(exceptions);
+        self
+    }
+
+    /// Transliterate with the syllables cut by `syllable_tokenizer`,
+    /// e.g. one that knows the syllables of loanwords
+    pub fn with_syllable_tokenizer(mut self, syllable_tokenizer: SyllableTokenizer) -> Self {
+        self.syllable_tokenizer = syllable_tokenizer;
+        self
+    }
+
+    /// Number of words in the exceptions dictionary
+    pub fn exceptions_len(&self) -> usize {
+        self.exceptions.len()
+    }
+
+    /// Transliterate a word, syllable by syllable.
+    pub fn transliterate(&self, word: &str) -> String {
+        if let Some(ipa) = self.exceptions.get(word) {
+            return ipa.clone();
+        }
+        self.syllable_tokenizer
+            .segment_with_offsets(word)
+            .iter()
+            .map(|syllable| token_ipa(&syllable.text))
+            .filter(|ipa| !ipa.is_empty())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Transliterate every word of `words`, in parallel,
+    /// e.g. the words of [`NewmmTokenizer`](crate::NewmmTokenizer).
+    pub fn transliterate_words<S: AsRef<str> + Sync>(&self, words: &[S]) -> Vec<String> {
+        words
+            .par_iter()
+            .map(|word| self.transliterate(word.as_ref()))
+            .collect()
+    }
+}
+
+/// Transliterate a word with the default [`Transliterator`], which has no
+/// exceptions dictionary.
+pub fn transliterate(word: &str) -> String {
+    DEFAULT_TRANSLITERATOR.transliterate(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NewmmTokenizer, Tokenizer};
+
+    const DICT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/dataset/words_th.txt");
+
+    #[test]
+    fn test_transliterate() {
+        for (word, expected) in [
+            ("กา", "kaː˧"),
+            ("ขาด", "kʰaːt˨˩"),
+            ("คะ", "kʰaʔ˦˥"),
+            ("ไม้", "maj˦˥"),
+            ("น้ำ", "nam˦˥"),
+            ("หมา", "maː˩˩˦"),
+            ("อยู่", "juː˨˩"),
+            ("เปล่า", "plaw˨˩"),
+            ("จันทร์", "tɕan˧"),
+            ("ทราย", "saːj˧"),
+            ("โต๊ะ", "toʔ˦˥"),
+            ("เชียงใหม่", "tɕʰiːaŋ˧.maj˨˩"),
+            ("ประเทศ", "praʔ˨˩.tʰeːt˥˩"),
+            // written syllables, the ว of "วัส" keeps its own class
+            ("สวัสดี", "saʔ˨˩.wat˦˥.diː˧"),
+            ("๒๕๖๗", "2567"),
+            ("", ""),
+        ] {
+            assert_eq!(transliterate(word), expected, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_transliterate_newmm_words() {
+        let tokenizer = NewmmTokenizer::new(DICT_PATH).unwrap();
+        let words = tokenizer.segment("ภาษาไทยง่ายนิดเดียว", false, false).unwrap();
+        assert_eq!(words, vec!["ภาษาไทย", "ง่าย", "นิดเดียว"]);
+        assert_eq!(
+            Transliterator::new().transliterate_words(&words),
+            vec!["pʰaː˧.saː˩˩˦.tʰaj˧", "ŋaːj˥˩", "nit˦˥.diːaw˧"]
+        );
+    }
+
+    #[test]
+    fn test_exceptions() {
+        let transliterator = Transliterator::from_exceptions(DictSource::WordList(vec![
+            "ก็\tkɔʔ˥˩".to_string(),
+            "".to_string(),
+            "ราชการ\traːt˥˩.tɕʰaʔ˦˥.kaːn˧".to_string(),
+        ]))
+        .unwrap();
+        assert_eq!(transliterator.exceptions_len(), 2);
+        assert_eq!(transliterator.transliterate("ก็"), "kɔʔ˥˩");
+        assert_eq!(transliterator.transliterate("กา"), "kaː˧");
+
+        // an IPA column is not a frequency, whatever it looks like
+        let transliterator =
+            Transliterator::from_exceptions(DictSource::WordList(vec!["หนึ่ง\t1".to_string()]))
+                .unwrap()
+                .with_exceptions([("ก็".to_string(), "kɔʔ˥˩".to_string())]);
+        assert_eq!(transliterator.transliterate("หนึ่ง"), "1");
+        assert_eq!(transliterator.transliterate("ก็"), "kɔʔ˥˩");
+
+        let result = Transliterator::from_exceptions(DictSource::WordList(vec![
+            "ก็\tkɔʔ˥˩".to_string(),
+            "ราชการ".to_string(),
+        ]));
+        assert!(matches!(
+            result,
+            Err(ThongnaError::InvalidDictionaryLine { line_number: 2, .. })
+        ));
+    }
+}
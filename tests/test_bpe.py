@@ -0,0 +1,54 @@
+import os
+import tempfile
+import unittest
+from thongna_py import BpeTokenizer, DictionaryError, load_dict, newmm
+
+class TestBpePackage(unittest.TestCase):
+    def setUp(self):
+        self.CORPUS = [
+            "ภาษาไทยเป็นภาษาที่สวยงาม",
+            "ภาษาไทยง่ายนิดเดียว",
+            "คนไทยพูดภาษาไทย",
+            None,
+        ]
+        self.DICT_FILENAME = "dataset/words_th.txt"
+        self.DICT_NAME = "words_th"
+        load_dict(self.DICT_FILENAME, self.DICT_NAME)
+
+    def test_train_with_newmm_pre_tokens(self):
+        bpe = BpeTokenizer.train(self.CORPUS, 200, dict_name=self.DICT_NAME)
+        self.assertEqual(bpe.id_to_token(0), "[UNK]")
+        self.assertIsNotNone(bpe.token_to_id("ภาษา"))
+        # merges never join two words
+        self.assertIsNone(bpe.token_to_id("ไทยพูด"))
+        text = "คนไทยพูดภาษาไทยสวยงาม"
+        subwords = bpe.encode(text, self.DICT_NAME)
+        self.assertEqual("".join(subwords), text)
+        self.assertEqual(
+            subwords,
+            [subword for word in newmm(text, self.DICT_NAME) for subword in bpe.encode(word, self.DICT_NAME)],
+        )
+        self.assertEqual(bpe.decode(bpe.encode_ids(text, self.DICT_NAME)), text)
+        self.assertEqual(bpe.encode("", self.DICT_NAME), [])
+        print("test_train_with_newmm_pre_tokens passed")
+
+    def test_save_and_load(self):
+        bpe = BpeTokenizer.train(self.CORPUS, 100)
+        with tempfile.TemporaryDirectory() as directory:
+            vocab_path = os.path.join(directory, "bpe.txt")
+            bpe.save(vocab_path)
+            loaded = BpeTokenizer.load(vocab_path)
+            self.assertEqual(loaded.vocab_size, bpe.vocab_size)
+            text = "ภาษาไทย ของคนไทย"
+            self.assertEqual(loaded.encode_ids(text), bpe.encode_ids(text))
+            # unseen clusters get the unknown id
+            self.assertEqual(loaded.encode_ids("ฮ"), [0])
+
+            with open(vocab_path, "w", encoding="utf-8") as vocab_file:
+                vocab_file.write("#thongna-bpe 1\nก\nก\tข\n")
+            with self.assertRaisesRegex(DictionaryError, "line 3"):
+                BpeTokenizer.load(vocab_path)
+        print("test_save_and_load passed")
+
+if __name__ == '__main__':
+    unittest.main()
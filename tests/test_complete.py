@@ -0,0 +1,90 @@
+import os
+import tempfile
+import threading
+import unittest
+from thongna_py import (
+    Completion,
+    add_words,
+    Tokenizer,
+    compile_dict,
+    complete,
+    complete_fuzzy,
+    load_compiled_dict,
+    load_dict,
+    unload_dict,
+)
+
+
+class TestCompletePackage(unittest.TestCase):
+    def setUp(self):
+        self.DICT_NAME = "test_dict_complete"
+        with tempfile.NamedTemporaryFile("w", suffix=".txt", delete=False, encoding="utf-8") as file:
+            file.write("สวัสดี\t10\nสวัสดิการ\t30\nสวน\t50\nกา\t0\n")
+        self.dict_path = file.name
+
+    def tearDown(self):
+        unload_dict(self.DICT_NAME)
+        os.remove(self.dict_path)
+
+    def test_complete(self):
+        load_dict(self.dict_path, self.DICT_NAME, replace=True)
+        self.assertEqual(
+            complete("สวัส", self.DICT_NAME),
+            [Completion("สวัสดิการ", 0, 30), Completion("สวัสดี", 0, 10)],
+        )
+        self.assertEqual([completion.word for completion in complete("ส", self.DICT_NAME, limit=2)],
+                         ["สวน", "สวัสดิการ"])
+        self.assertEqual(complete("ขา", self.DICT_NAME), [])
+        self.assertEqual(complete(None, self.DICT_NAME), [])
+        with self.assertRaises(RuntimeError):
+            complete("สวัส", "test_dict_not_loaded")
+        print("test_complete passed")
+
+    def test_complete_fuzzy(self):
+        load_dict(self.dict_path, self.DICT_NAME, replace=True)
+        self.assertEqual(complete("สวสด", self.DICT_NAME), [])
+        self.assertEqual(
+            complete_fuzzy("สวสด", self.DICT_NAME),
+            [Completion("สวัสดิการ", 1, 30), Completion("สวัสดี", 1, 10)],
+        )
+        self.assertEqual(complete_fuzzy("สัวส", self.DICT_NAME, limit=1)[0].word, "สวัสดิการ")
+        self.assertEqual(complete_fuzzy("สวสด", self.DICT_NAME, max_distance=0), [])
+        print("test_complete_fuzzy passed")
+
+    def test_complete_fuzzy_while_adding_words(self):
+        # the search runs without the GIL, adding words must not wait for it forever
+        load_dict("dataset/words_th.txt", self.DICT_NAME, replace=True)
+        tokenizer = Tokenizer("dataset/words_th.txt")
+        results = []
+
+        def search():
+            for _ in range(5):
+                results.append(complete_fuzzy("สวสดี", self.DICT_NAME, max_distance=2))
+                results.append(tokenizer.complete_fuzzy("สวสดี", max_distance=2))
+
+        thread = threading.Thread(target=search)
+        thread.start()
+        for index in range(50):
+            add_words(["สวัสดีปีใหม่%d" % index], self.DICT_NAME)
+            tokenizer.add_words(["สวัสดีปีใหม่%d" % index])
+        thread.join()
+        self.assertEqual(len(results), 10)
+        self.assertIn("สวัสดี", [completion.word for completion in results[0]])
+        print("test_complete_fuzzy_while_adding_words passed")
+
+    def test_tokenizer_and_compiled_dict(self):
+        tokenizer = Tokenizer(self.dict_path, words=["สวัสดีครับ"])
+        self.assertEqual([completion.word for completion in tokenizer.complete("สวัสดี")],
+                         ["สวัสดี", "สวัสดีครับ"])
+        self.assertEqual(tokenizer.complete_fuzzy("สวสดี", limit=1), [Completion("สวัสดี", 1, 10)])
+
+        compiled_path = self.dict_path + ".bin"
+        try:
+            compile_dict(self.dict_path, compiled_path)
+            load_compiled_dict(compiled_path, self.DICT_NAME, replace=True)
+            self.assertEqual(complete_fuzzy("สวสด", self.DICT_NAME),
+                             [Completion("สวัสดิการ", 1, 30), Completion("สวัสดี", 1, 10)])
+        finally:
+            unload_dict(self.DICT_NAME)
+            os.remove(compiled_path)
+        print("test_tokenizer_and_compiled_dict passed")
@@ -0,0 +1,68 @@
+import pickle
+import unittest
+from thongna_py import (
+    NewmmConfig,
+    SegmentationError,
+    SegmentationTimeoutError,
+    Tokenizer,
+    load_dict,
+    newmm,
+    set_config,
+)
+
+
+class TestConfigPackage(unittest.TestCase):
+    def setUp(self):
+        self.DANGER_TEXT_1 = "ชิ" * 100
+        self.DANGER_TEXT_3 = "ด้านหน้า" * 20 + "ก" * 40
+        self.DICT_FILENAME = "dataset/words_th.txt"
+
+    def test_config_values(self):
+        config = NewmmConfig()
+        self.assertEqual(config.max_graph_size, 50)
+        self.assertEqual(config.text_scan_point, 120)
+        self.assertEqual((config.text_scan_left, config.text_scan_right), (20, 20))
+        self.assertFalse(config.safe)
+        self.assertIsNone(config.timeout)
+
+        config = NewmmConfig(text_scan_point=60, safe=True, timeout=0.5)
+        self.assertEqual(config.text_scan_point, 60)
+        self.assertEqual(config.timeout, 0.5)
+        self.assertEqual(pickle.loads(pickle.dumps(config)), config)
+        self.assertIn("text_scan_point=60", repr(config))
+        with self.assertRaises(ValueError):
+            NewmmConfig(timeout=-1.0)
+        print("test_config_values passed")
+
+    def test_tokenizer_config(self):
+        tokenizer = Tokenizer(self.DICT_FILENAME)
+        unsafe_tokens = tokenizer.segment(self.DANGER_TEXT_3)
+        safe_tokens = tokenizer.segment(self.DANGER_TEXT_3, safe=True)
+
+        tokenizer.set_config(NewmmConfig(safe=True))
+        self.assertTrue(tokenizer.config.safe)
+        self.assertEqual(tokenizer.segment(self.DANGER_TEXT_3), safe_tokens)
+        self.assertEqual(tokenizer.segment(self.DANGER_TEXT_3, safe=False), unsafe_tokens)
+
+        tokenizer.set_config(NewmmConfig(safe=True, text_scan_point=40, text_scan_left=10, text_scan_right=10))
+        tokens = tokenizer.segment(self.DANGER_TEXT_3)
+        self.assertEqual("".join(tokens), self.DANGER_TEXT_3)
+
+        restored = pickle.loads(pickle.dumps(tokenizer))
+        self.assertEqual(restored.config, tokenizer.config)
+        self.assertEqual(restored.segment(self.DANGER_TEXT_3), tokens)
+        print("test_tokenizer_config passed")
+
+    def test_timeout(self):
+        tokenizer = Tokenizer(self.DICT_FILENAME, config=NewmmConfig(timeout=0.0))
+        with self.assertRaises(SegmentationTimeoutError):
+            tokenizer.segment(self.DANGER_TEXT_1)
+        with self.assertRaises(SegmentationError):
+            tokenizer.segment_batch([self.DANGER_TEXT_1], safe=True)
+
+        load_dict(self.DICT_FILENAME, "words_th_config", replace=True)
+        set_config("words_th_config", NewmmConfig(timeout=30.0))
+        self.assertEqual(newmm("ไข่คน2021", "words_th_config"), ["ไข่", "คน", "2021"])
+        with self.assertRaises(RuntimeError):
+            set_config("no_such_dict", NewmmConfig())
+        print("test_timeout passed")
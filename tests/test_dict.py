@@ -0,0 +1,87 @@
+import os
+import tempfile
+import unittest
+from thongna_py import (
+    DictionaryError,
+    ThongnaError,
+    add_words,
+    load_compiled_dict,
+    dict_word_count,
+    list_dicts,
+    load_dict,
+    load_dict_from_list,
+    newmm,
+    remove_words,
+    unload_dict,
+)
+
+class TestDictPackage(unittest.TestCase):
+    def setUp(self):
+        self.DICT_NAME = "test_dict_lifecycle"
+        unload_dict(self.DICT_NAME)
+
+    def tearDown(self):
+        unload_dict(self.DICT_NAME)
+
+    def test_load_dict_from_list(self):
+        _, success = load_dict_from_list(["ตา", "กลม", " ตา "], self.DICT_NAME)
+        self.assertTrue(success)
+        self.assertIn(self.DICT_NAME, list_dicts())
+        self.assertEqual(dict_word_count(self.DICT_NAME), 2)
+        self.assertEqual(newmm("ตากลม", self.DICT_NAME), ["ตา", "กลม"])
+
+        _, success = load_dict_from_list(["ตากลม"], self.DICT_NAME)
+        self.assertFalse(success)
+        self.assertEqual(dict_word_count(self.DICT_NAME), 2)
+        print("test_load_dict_from_list passed")
+
+    def test_add_and_remove_words(self):
+        load_dict_from_list(["ตา", "กลม"], self.DICT_NAME)
+        add_words(["ตากลม"], self.DICT_NAME)
+        self.assertEqual(dict_word_count(self.DICT_NAME), 3)
+        self.assertEqual(newmm("ตากลม", self.DICT_NAME), ["ตากลม"])
+
+        remove_words(["ตากลม", "ไม่มี"], self.DICT_NAME)
+        self.assertEqual(dict_word_count(self.DICT_NAME), 2)
+        self.assertEqual(newmm("ตากลม", self.DICT_NAME), ["ตา", "กลม"])
+        print("test_add_and_remove_words passed")
+
+    def test_replace_and_unload(self):
+        load_dict_from_list(["ตา", "กลม"], self.DICT_NAME)
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            dict_path = os.path.join(tmp_dir, "dict.txt")
+            with open(dict_path, "w", encoding="utf-8") as dict_file:
+                dict_file.write("ตาก\nลม\n")
+            _, success = load_dict(dict_path, self.DICT_NAME, replace=True)
+        self.assertTrue(success)
+        self.assertEqual(newmm("ตากลม", self.DICT_NAME), ["ตาก", "ลม"])
+
+        self.assertTrue(unload_dict(self.DICT_NAME))
+        self.assertFalse(unload_dict(self.DICT_NAME))
+        self.assertNotIn(self.DICT_NAME, list_dicts())
+        with self.assertRaises(RuntimeError):
+            newmm("ตากลม", self.DICT_NAME)
+        with self.assertRaises(RuntimeError):
+            add_words(["ตา"], self.DICT_NAME)
+        with self.assertRaises(RuntimeError):
+            dict_word_count(self.DICT_NAME)
+        print("test_replace_and_unload passed")
+
+    def test_load_errors(self):
+        with self.assertRaises(FileNotFoundError):
+            load_dict("dataset/no_such_dict.txt", self.DICT_NAME)
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            dict_path = os.path.join(tmp_dir, "dict.txt")
+            with open(dict_path, "wb") as dict_file:
+                dict_file.write("ตา\n".encode("utf-8") + b"\xff\xfe\n")
+            with self.assertRaises(DictionaryError) as context:
+                load_dict(dict_path, self.DICT_NAME)
+            self.assertIn("line 2", str(context.exception))
+            with self.assertRaises(ThongnaError):
+                load_compiled_dict(dict_path, self.DICT_NAME)
+        self.assertTrue(issubclass(DictionaryError, RuntimeError))
+        self.assertNotIn(self.DICT_NAME, list_dicts())
+        print("test_load_errors passed")
+
+if __name__ == '__main__':
+    unittest.main()
@@ -1,6 +1,18 @@
+import os
+import tempfile
 import unittest
 from typing import List
-from thongna_py import newmm, load_dict
+from thongna_py import (
+    DictionaryError,
+    compile_dict,
+    load_compiled_dict,
+    load_dict,
+    newmm,
+    newmm_lattice,
+    newmm_batch,
+    newmm_nbest,
+    newmm_with_offsets,
+)
 
 class TestTokenizePackage(unittest.TestCase):
     def setUp(self):
@@ -21,6 +33,23 @@ class TestTokenizePackage(unittest.TestCase):
         self.assertEqual(newmm(" ", self.DICT_NAME), [" "])
         print("test_segment_empty_input passed")
 
+    def test_segment_batch(self):
+        texts = [self.TEXT_1, self.TEXT_2, "", None, self.LONG_TEXT, self.DANGER_TEXT_3]
+        expected = [newmm(text, self.DICT_NAME, safe=True) for text in texts]
+        self.assertEqual(newmm_batch(texts, self.DICT_NAME, safe=True), expected)
+        self.assertEqual(newmm_batch([], self.DICT_NAME), [])
+        with self.assertRaises(RuntimeError):
+            newmm_batch(texts, "no_such_dict")
+        print("test_segment_batch passed")
+
+    def test_segment_missing_dict(self):
+        for segment in (newmm, newmm_with_offsets, newmm_lattice):
+            with self.assertRaises(DictionaryError):
+                segment(self.TEXT_1, "no_such_dict")
+        with self.assertRaises(DictionaryError):
+            newmm_nbest(self.TEXT_1, "no_such_dict", 2)
+        print("test_segment_missing_dict passed")
+
     def test_segment_simple_input(self):
         self.assertEqual(
             newmm("ไข่คน2021", self.DICT_NAME),
@@ -91,5 +120,58 @@ class TestTokenizePackage(unittest.TestCase):
         self.assertEqual(result_parallel, result_sequential)
         print("test_segment_with_parallel_mode passed")
 
+    def test_segment_with_offsets(self):
+        for text in [self.TEXT_1, self.LONG_TEXT, self.DANGER_TEXT_3]:
+            with self.subTest(text=text):
+                tokens = newmm_with_offsets(text, self.DICT_NAME, safe=True)
+                encoded = text.encode("utf-8")
+                self.assertEqual("".join(token.text for token in tokens), text)
+                for token in tokens:
+                    self.assertEqual(text[token.start:token.end], token.text)
+                    self.assertEqual(
+                        encoded[token.byte_start:token.byte_end].decode("utf-8"),
+                        token.text,
+                    )
+        self.assertEqual(newmm_with_offsets("", self.DICT_NAME), [])
+        print("test_segment_with_offsets passed")
+
+    def test_segment_weighted_dict(self):
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            dict_path = os.path.join(tmp_dir, "weighted.txt")
+            with open(dict_path, "w", encoding="utf-8") as dict_file:
+                dict_file.write("ตา\t1\nกลม\t1\nตาก\t500\nลม\t800\n")
+            load_dict(dict_path, "weighted_fewest")
+            load_dict(dict_path, "weighted_cost", weighted=True)
+        self.assertEqual(newmm("ตากลม", "weighted_fewest"), ["ตา", "กลม"])
+        self.assertEqual(newmm("ตากลม", "weighted_cost"), ["ตาก", "ลม"])
+        print("test_segment_weighted_dict passed")
+
+    def test_lattice_and_nbest(self):
+        lattice = newmm_lattice(self.TEXT_2, self.DICT_NAME)
+        self.assertEqual(lattice.nodes[0], 0)
+        self.assertEqual(lattice.nodes[-1], len(self.TEXT_2))
+        for edge in lattice.edges:
+            self.assertEqual(self.TEXT_2[edge.start:edge.end], edge.text)
+
+        nbest = newmm_nbest(self.TEXT_2, self.DICT_NAME, k=3)
+        self.assertGreater(len(nbest), 1)
+        self.assertLessEqual(len(nbest), 3)
+        self.assertEqual(nbest[0][0], newmm(self.TEXT_2, self.DICT_NAME))
+        for tokens, _ in nbest:
+            self.assertEqual("".join(tokens), self.TEXT_2)
+        print("test_lattice_and_nbest passed")
+
+    def test_compiled_dict(self):
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            compiled_path = os.path.join(tmp_dir, "words_th.bin")
+            compile_dict(self.DICT_FILENAME, compiled_path)
+            _, loaded = load_compiled_dict(compiled_path, "words_th_compiled")
+            self.assertTrue(loaded)
+        self.assertEqual(
+            newmm(self.LONG_TEXT, "words_th_compiled"),
+            newmm(self.LONG_TEXT, self.DICT_NAME),
+        )
+        print("test_compiled_dict passed")
+
 if __name__ == '__main__':
     unittest.main()
@@ -0,0 +1,58 @@
+import pickle
+import unittest
+from thongna_py import Normalizer, Tokenizer, load_dict, newmm_normalized_with_offsets, normalize
+
+
+class TestNormalizePackage(unittest.TestCase):
+    def test_normalize(self):
+        self.assertEqual(normalize("เเปลก  ราคา100บาท"), "แปลก ราคา 100 บาท")
+        self.assertEqual(normalize("นำ้", whitespace_number=False), "น้ำ")
+        print("test_normalize passed")
+
+    def test_normalizer(self):
+        normalizer = Normalizer()
+        self.assertEqual(
+            normalizer.steps,
+            ["remove_zero_width", "collapse_whitespace", "reorder_marks", "remove_duplicate_marks"],
+        )
+        self.assertEqual(normalizer.normalize("เเปลก  ราคา100"), normalize("เเปลก  ราคา100", False))
+        self.assertEqual(normalizer.normalize(None), "")
+        self.assertEqual(normalizer.normalize_batch(["ก่ี", "นานาาา"]), ["กี่", "นานา"])
+
+        spacing = Normalizer(["space_numbers", "nfc"])
+        self.assertEqual(spacing.steps, ["nfc", "space_numbers"])
+        self.assertEqual(spacing.normalize("เเปลก100"), "เเปลก 100 ")
+        invisible = "ก\u200cข\ufeff"
+        self.assertEqual(normalizer.normalize(invisible), invisible)
+        self.assertEqual(Normalizer(["remove_invisible_chars"]).normalize(invisible), "กข")
+        self.assertEqual(Normalizer(Normalizer.STEPS).steps, list(Normalizer.STEPS))
+        with self.assertRaises(ValueError):
+            Normalizer(["lowercase"])
+
+        restored = pickle.loads(pickle.dumps(spacing))
+        self.assertEqual(restored.steps, spacing.steps)
+        print("test_normalizer passed")
+
+    def test_normalize_with_alignment(self):
+        text = "เเปลก  ราคา100"
+        normalized, alignment = Normalizer(Normalizer.STEPS).normalize_with_alignment(text)
+        self.assertEqual(normalized, "แปลก ราคา 100 ")
+        self.assertEqual(len(alignment), len(normalized))
+        self.assertEqual(alignment[:2], [(0, 2), (2, 3)])
+        self.assertEqual(alignment[9], (11, 11))
+        self.assertEqual(Normalizer().normalize_with_alignment(""), ("", []))
+        print("test_normalize_with_alignment passed")
+
+    def test_newmm_normalized_with_offsets(self):
+        text = "เเปลก\u200b  ราคา100บาท"
+        load_dict("dataset/words_th.txt", "words_th_normalized", replace=True)
+        normalizer = Normalizer(["remove_zero_width", "collapse_whitespace", "reorder_marks", "space_numbers"])
+        tokens = newmm_normalized_with_offsets(text, "words_th_normalized", normalizer)
+        self.assertEqual([token.text for token in tokens], ["เเปลก", "  ", "ราคา", "100", "บาท"])
+        for token in tokens:
+            self.assertEqual(text[token.start:token.end], token.text)
+
+        tokenizer = Tokenizer("dataset/words_th.txt")
+        self.assertEqual(tokenizer.segment_normalized_with_offsets(text, normalizer), tokens)
+        self.assertEqual(tokenizer.segment_normalized_with_offsets(None), [])
+        print("test_newmm_normalized_with_offsets passed")
@@ -0,0 +1,26 @@
+import unittest
+from thongna_py import load_dict, newmm, romanize, romanize_words
+
+
+class TestRomanizePackage(unittest.TestCase):
+    def test_romanize(self):
+        self.assertEqual(romanize("เชียงใหม่"), "chiangmai")
+        self.assertEqual(romanize("ขวด"), "khuat")
+        self.assertEqual(romanize("ปี ๒๕๖๗"), "pi 2567")
+        self.assertEqual(romanize(""), "")
+        self.assertEqual(romanize(None), "")
+        print("test_romanize passed")
+
+    def test_romanize_with_dict(self):
+        load_dict("dataset/words_th.txt", "words_th_romanize", replace=True)
+        self.assertEqual(romanize("ขอนแก่น ภูเก็ต", "words_th_romanize"), "khonkaen phuket")
+        with self.assertRaises(RuntimeError):
+            romanize("ขอนแก่น", "not_loaded")
+        print("test_romanize_with_dict passed")
+
+    def test_romanize_words(self):
+        load_dict("dataset/words_th.txt", "words_th_romanize", replace=True)
+        words = newmm("ลำปางสุโขทัย", "words_th_romanize")
+        self.assertEqual(romanize_words(words), ["lampang", "sukhothai"])
+        self.assertEqual(romanize_words(["แพร่", None]), ["phrae", ""])
+        print("test_romanize_words passed")
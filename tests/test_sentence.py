@@ -0,0 +1,58 @@
+import unittest
+from thongna_py import Tokenizer, load_dict, sentences, sentences_with_offsets
+
+
+class TestSentencePackage(unittest.TestCase):
+    def setUp(self):
+        self.LONG_TEXT = "ไต้หวัน (แป่ะเอ๋ยี้: Tâi-oân; ไต่อวัน) หรือ ไถวาน (อักษรโรมัน: Taiwan; จีนตัวย่อ: 台湾; จีนตัวเต็ม: 臺灣/台灣; พินอิน: Táiwān; ไถวาน) หรือชื่อทางการว่า สาธารณรัฐจีน (จีนตัวย่อ: 中华民国; จีนตัวเต็ม: 中華民國; พินอิน: Zhōnghuá Mínguó) เป็นรัฐในทวีปเอเชียตะวันออก"
+        self.PARAGRAPH = (
+            "วันนี้อากาศดีมากครับ ผมจะไปเที่ยวทะเล เด็กๆ เล่นกันสนุก\n"
+            "ผมชอบกินข้าวมาก และชอบกินก๋วยเตี๋ยว พ.ศ. 2564 มีคน 3.5 ล้าน"
+        )
+
+        self.DICT_FILENAME = "dataset/words_th.txt"
+        self.DICT_NAME = "words_th"
+        load_dict(self.DICT_FILENAME, self.DICT_NAME)
+
+    def test_sentences_empty_input(self):
+        self.assertEqual(sentences(None, self.DICT_NAME), [])
+        self.assertEqual(sentences("", self.DICT_NAME), [])
+        self.assertEqual(sentences(" \n ", self.DICT_NAME), [])
+        print("test_sentences_empty_input passed")
+
+    def test_sentences(self):
+        self.assertEqual(
+            sentences(self.PARAGRAPH, self.DICT_NAME),
+            [
+                "วันนี้อากาศดีมากครับ",
+                "ผมจะไปเที่ยวทะเล",
+                "เด็กๆ เล่นกันสนุก",
+                "ผมชอบกินข้าวมาก และชอบกินก๋วยเตี๋ยว",
+                "พ.ศ. 2564 มีคน 3.5 ล้าน",
+            ],
+        )
+        self.assertEqual(sentences(self.LONG_TEXT, self.DICT_NAME), [self.LONG_TEXT])
+        with self.assertRaises(RuntimeError):
+            sentences(self.PARAGRAPH, "no_such_dict")
+        print("test_sentences passed")
+
+    def test_sentences_with_offsets(self):
+        text = self.PARAGRAPH + "\n\n" + self.LONG_TEXT
+        result = sentences_with_offsets(text, self.DICT_NAME, safe=True)
+        self.assertEqual(len(result), 6)
+        encoded = text.encode("utf-8")
+        for sentence in result:
+            self.assertEqual(text[sentence.start : sentence.end], sentence.text)
+            self.assertEqual(encoded[sentence.byte_start : sentence.byte_end].decode("utf-8"), sentence.text)
+        for previous, sentence in zip(result, result[1:]):
+            self.assertLess(previous.end, sentence.start)
+        print("test_sentences_with_offsets passed")
+
+    def test_tokenizer_sentences(self):
+        tokenizer = Tokenizer(self.DICT_FILENAME)
+        self.assertEqual(tokenizer.sentences(self.PARAGRAPH), sentences(self.PARAGRAPH, self.DICT_NAME))
+        self.assertEqual(
+            tokenizer.sentences_with_offsets(self.LONG_TEXT),
+            sentences_with_offsets(self.LONG_TEXT, self.DICT_NAME),
+        )
+        print("test_tokenizer_sentences passed")
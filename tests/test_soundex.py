@@ -0,0 +1,28 @@
+import unittest
+from thongna_py import SOUNDEX_ENGINES, lk82, metasound, soundex, soundex_batch, udom83
+
+
+class TestSoundexPackage(unittest.TestCase):
+    def test_soundex(self):
+        self.assertEqual(lk82("รัก"), "ร1000")
+        self.assertEqual(lk82("ลัก"), lk82("รักษ์"))
+        self.assertEqual(udom83("สมชาย"), "ส072000")
+        self.assertEqual(udom83("ซมชาย"), udom83("สมชาย"))
+        self.assertEqual(metasound("บูรณะ"), "บ550")
+        self.assertEqual(metasound("บูรณะ", 6), "บ55000")
+        self.assertEqual(soundex("สมชาย  ใจดี"), "ส072000 จ240000")
+        self.assertEqual(soundex(""), "")
+        self.assertEqual(soundex(None, "lk82"), "")
+        with self.assertRaises(ValueError):
+            soundex("สมชาย", "nysiis")
+        print("test_soundex passed")
+
+    def test_soundex_batch(self):
+        names = ["จันทร์", "จันท์", "จัน", None]
+        for engine in SOUNDEX_ENGINES:
+            keys = soundex_batch(names, engine)
+            self.assertEqual(keys[:3], [soundex(names[0], engine)] * 3)
+            self.assertEqual(keys[3], "")
+        with self.assertRaises(ValueError):
+            soundex_batch(names, "nysiis")
+        print("test_soundex_batch passed")
@@ -0,0 +1,32 @@
+import os
+import tempfile
+import unittest
+from thongna_py import SpellChecker, Suggestion
+
+
+class TestSpellCheckerPackage(unittest.TestCase):
+    def test_suggest(self):
+        with tempfile.NamedTemporaryFile("w", suffix=".txt", delete=False, encoding="utf-8") as file:
+            file.write("ไก่\t50\nไข่\t10\nไข้\t20\n")
+        try:
+            spell_checker = SpellChecker(file.name, ["เก่ง"])
+            self.assertIn("ไข่", spell_checker)
+            self.assertNotIn("ไข", spell_checker)
+            suggestions = spell_checker.suggest("ไข")
+            self.assertEqual(suggestions[0], Suggestion("ไข้", 0.5, 20))
+            self.assertEqual([suggestion.word for suggestion in suggestions], ["ไข้", "ไข่", "ไก่"])
+            self.assertEqual(spell_checker.correct("เกง"), "เก่ง")
+            self.assertEqual(spell_checker.correct("คน"), "คน")
+            self.assertEqual(SpellChecker(file.name, max_distance=0).suggest("ไข"), [])
+            self.assertEqual(spell_checker.suggest(None), [])
+            self.assertEqual(spell_checker.correct(""), "")
+        finally:
+            os.remove(file.name)
+        print("test_suggest passed")
+
+    def test_correct_text(self):
+        spell_checker = SpellChecker("dataset/words_th.txt")
+        self.assertEqual(spell_checker.correct_text("ฉันไปโรงพยาบานเมื่อวาน"), "ฉันไปโรงพยาบาลเมื่อวาน")
+        self.assertEqual(spell_checker.correct_text("ประเทษไทย 2567"), "ประเทศไทย 2567")
+        self.assertEqual(spell_checker.correct_text(None), "")
+        print("test_correct_text passed")
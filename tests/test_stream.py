@@ -0,0 +1,52 @@
+import io
+import os
+import tempfile
+import unittest
+from thongna_py import Tokenizer, load_dict, newmm_stream, newmm_with_offsets
+
+
+class TestStreamPackage(unittest.TestCase):
+    def setUp(self):
+        self.TEXT = (
+            "ไต้หวัน (แป่ะเอ๋ยี้: Tâi-oân; ไต่อวัน) หรือ ไถวาน\n"
+            + "ด้านหน้า" * 20
+            + "ก" * 40
+            + "ค่าจ้างที่ได้รับต้องทำให้แรงงานสามารถเลี้ยงดูตัวเองและครอบครัว\n"
+        ) * 40
+        self.DICT_FILENAME = "dataset/words_th.txt"
+        self.DICT_NAME = "words_th"
+        load_dict(self.DICT_FILENAME, self.DICT_NAME)
+
+    def test_stream_chunks(self):
+        expected = newmm_with_offsets(self.TEXT, self.DICT_NAME, safe=True)
+        for size in [1, 100, 5000]:
+            with self.subTest(size=size):
+                chunks = (self.TEXT[i:i + size] for i in range(0, len(self.TEXT), size))
+                self.assertEqual(list(newmm_stream(chunks, self.DICT_NAME)), expected)
+        encoded = self.TEXT.encode("utf-8")
+        byte_chunks = [encoded[i:i + 333] for i in range(0, len(encoded), 333)]
+        self.assertEqual(list(newmm_stream(byte_chunks, self.DICT_NAME)), expected)
+        self.assertEqual(list(newmm_stream([], self.DICT_NAME)), [])
+        with self.assertRaises(RuntimeError):
+            newmm_stream([self.TEXT], "no_such_dict")
+        with self.assertRaises(TypeError):
+            list(newmm_stream([1, 2], self.DICT_NAME))
+        with self.assertRaises(OSError):
+            list(newmm_stream([b"\xe0\xb8"], self.DICT_NAME))
+        print("test_stream_chunks passed")
+
+    def test_tokenizer_stream_file(self):
+        tokenizer = Tokenizer(self.DICT_FILENAME)
+        expected = tokenizer.segment_with_offsets(self.TEXT, safe=True)
+        self.assertEqual(list(tokenizer.segment_stream(io.StringIO(self.TEXT))), expected)
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            file_path = os.path.join(tmp_dir, "text.txt")
+            with open(file_path, "w", encoding="utf-8") as text_file:
+                text_file.write(self.TEXT)
+            with open(file_path, "rb") as text_file:
+                self.assertEqual(list(tokenizer.segment_stream(text_file)), expected)
+            with open(file_path, encoding="utf-8") as text_file:
+                tokens = tokenizer.segment_stream(iter(text_file))
+                self.assertEqual(next(tokens), expected[0])
+                self.assertEqual(list(tokens), expected[1:])
+        print("test_tokenizer_stream_file passed")
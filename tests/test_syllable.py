@@ -0,0 +1,38 @@
+import os
+import tempfile
+import unittest
+from thongna_py import SyllableTokenizer, syllables, syllables_with_offsets
+
+class TestSyllablePackage(unittest.TestCase):
+    def test_syllables_empty_input(self):
+        self.assertEqual(syllables(None), [])
+        self.assertEqual(syllables(""), [])
+        self.assertEqual(syllables_with_offsets(""), [])
+        print("test_syllables_empty_input passed")
+
+    def test_syllables(self):
+        self.assertEqual(syllables("ประเทศไทย"), ["ประ", "เทศ", "ไทย"])
+        self.assertEqual(syllables("มหาวิทยาลัย"), ["ม", "หา", "วิท", "ยา", "ลัย"])
+        self.assertEqual(syllables("คอมพิวเตอร์ 2 เครื่อง"), ["คอม", "พิว", "เตอร์", " ", "2", " ", "เครื่อง"])
+        text = "ไต้หวัน Taiwan 台湾"
+        self.assertEqual("".join(syllables(text)), text)
+        for syllable in syllables_with_offsets(text):
+            self.assertEqual(text[syllable.start : syllable.end], syllable.text)
+        print("test_syllables passed")
+
+    def test_syllable_tokenizer(self):
+        self.assertEqual(SyllableTokenizer().segment("พฤษภาคม"), ["พ", "ฤษ", "ภา", "คม"])
+        self.assertEqual(SyllableTokenizer(words=["พฤษ"]).segment("พฤษภาคม"), ["พฤษ", "ภา", "คม"])
+        with tempfile.TemporaryDirectory() as directory:
+            dict_path = os.path.join(directory, "syllables.txt")
+            with open(dict_path, "w", encoding="utf-8") as dict_file:
+                dict_file.write("พฤษ\n")
+            tokenizer = SyllableTokenizer(dict_path)
+            self.assertEqual(
+                [syllable.text for syllable in tokenizer.segment_with_offsets("พฤษภาคม")],
+                ["พฤษ", "ภา", "คม"],
+            )
+        print("test_syllable_tokenizer passed")
+
+if __name__ == '__main__':
+    unittest.main()
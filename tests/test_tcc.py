@@ -0,0 +1,21 @@
+import unittest
+from thongna_py import tcc, tcc_pos
+
+class TestTccPackage(unittest.TestCase):
+    def test_tcc_empty_input(self):
+        self.assertEqual(tcc(None), [])
+        self.assertEqual(tcc(""), [])
+        self.assertEqual(tcc_pos(""), set())
+        print("test_tcc_empty_input passed")
+
+    def test_tcc(self):
+        self.assertEqual(tcc("ประเทศไทย"), ["ป", "ระ", "เท", "ศ", "ไท", "ย"])
+        self.assertEqual("".join(tcc("ไต้หวัน Taiwan 台湾")), "ไต้หวัน Taiwan 台湾")
+        print("test_tcc passed")
+
+    def test_tcc_pos(self):
+        self.assertEqual(tcc_pos("พิสูจน์ได้ค่ะ"), {2, 7, 10, 13})
+        print("test_tcc_pos passed")
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,54 @@
+import pickle
+import unittest
+from thongna_py import RegexError, Tokenizer, load_dict, newmm, set_token_patterns
+
+
+class TestTokenPatternsPackage(unittest.TestCase):
+    def setUp(self):
+        self.TEXT = "ติดต่อ me@mail.co.th หรือ https://thongna.dev/docs ราคา 3.5kg #ข้าวมันไก่ 👍🏽"
+        self.DICT_FILENAME = "dataset/words_th.txt"
+
+    def test_default_patterns(self):
+        tokenizer = Tokenizer(self.DICT_FILENAME)
+        tokens = tokenizer.segment(self.TEXT)
+        self.assertNotIn("me@mail.co.th", tokens)
+        self.assertIn("3.5", tokens)
+        self.assertEqual(len(tokenizer.token_patterns), 5)
+        print("test_default_patterns passed")
+
+    def test_presets(self):
+        tokenizer = Tokenizer(
+            self.DICT_FILENAME,
+            presets=["url", "email", "hashtag", "emoji", "number_with_unit"],
+        )
+        tokens = tokenizer.segment(self.TEXT)
+        for token in ["me@mail.co.th", "https://thongna.dev/docs", "3.5kg", "#ข้าวมันไก่", "👍🏽"]:
+            self.assertIn(token, tokens)
+        self.assertEqual("".join(tokens), self.TEXT)
+        safe_tokens = tokenizer.segment((self.TEXT + " ") * 20, safe=True)
+        for token in ["me@mail.co.th", "https://thongna.dev/docs", "#ข้าวมันไก่"]:
+            self.assertEqual(safe_tokens.count(token), 20)
+
+        restored = pickle.loads(pickle.dumps(tokenizer))
+        self.assertEqual(restored.token_patterns, tokenizer.token_patterns)
+        self.assertEqual(restored.segment(self.TEXT), tokens)
+        print("test_presets passed")
+
+    def test_custom_patterns(self):
+        tokenizer = Tokenizer(words=["รหัส", "สินค้า"], patterns=[r"[A-Z]{2}-\d+"])
+        self.assertEqual(tokenizer.segment("รหัสสินค้าTH-1234"), ["รหัส", "สินค้า", "TH-1234"])
+        tokenizer.set_token_patterns()
+        self.assertEqual(tokenizer.segment("รหัสสินค้าTH-1234"), ["รหัส", "สินค้า", "TH-", "1234"])
+        with self.assertRaises(ValueError):
+            tokenizer.set_token_patterns(presets=["phone"])
+        with self.assertRaises(RegexError):
+            tokenizer.set_token_patterns(patterns=[r"\bword"])
+        print("test_custom_patterns passed")
+
+    def test_set_token_patterns(self):
+        load_dict(self.DICT_FILENAME, "words_th_patterns", replace=True)
+        set_token_patterns("words_th_patterns", presets=["mention"])
+        self.assertIn("@นุ่น", newmm("สวัสดี @นุ่น", "words_th_patterns"))
+        with self.assertRaises(RuntimeError):
+            set_token_patterns("no_such_dict", presets=["url"])
+        print("test_set_token_patterns passed")
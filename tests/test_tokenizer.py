@@ -0,0 +1,47 @@
+import pickle
+import unittest
+from thongna_py import Tokenizer, newmm, load_dict
+
+class TestTokenizerClass(unittest.TestCase):
+    def setUp(self):
+        self.DICT_FILENAME = "dataset/words_th.txt"
+        self.TEXT = "ไต้หวัน (แป่ะเอ๋ยี้: Tâi-oân; ไต่อวัน) หรือ ไถวาน"
+
+    def test_segment_matches_newmm(self):
+        load_dict(self.DICT_FILENAME, "words_th")
+        tokenizer = Tokenizer(self.DICT_FILENAME)
+        self.assertEqual(tokenizer.segment(self.TEXT), newmm(self.TEXT, "words_th"))
+        self.assertEqual(tokenizer.segment(None), [])
+        self.assertEqual(tokenizer.segment(""), [])
+        self.assertEqual(tokenizer.segment_batch([self.TEXT, None]), [newmm(self.TEXT, "words_th"), []])
+        tokens = tokenizer.segment_with_offsets("ไข่คน2021")
+        self.assertEqual([token.text for token in tokens], ["ไข่", "คน", "2021"])
+        self.assertEqual((tokens[1].start, tokens[1].end), (3, 5))
+        print("test_segment_matches_newmm passed")
+
+    def test_instances_are_independent(self):
+        first = Tokenizer(words=["ตา", "กลม"])
+        second = Tokenizer(words=["ตาก", "ลม"])
+        self.assertEqual(first.segment("ตากลม"), ["ตา", "กลม"])
+        self.assertEqual(second.segment("ตากลม"), ["ตาก", "ลม"])
+
+        first.add_words(["ตากลม"])
+        self.assertEqual(len(first), 3)
+        self.assertEqual(first.segment("ตากลม"), ["ตากลม"])
+        self.assertEqual(second.segment("ตากลม"), ["ตาก", "ลม"])
+
+        first.remove_words(["ตากลม"])
+        self.assertEqual(first.segment("ตากลม"), ["ตา", "กลม"])
+        print("test_instances_are_independent passed")
+
+    def test_pickle(self):
+        tokenizer = Tokenizer(words=["ตา", "กลม", "ตาก", "ลม"], weighted=True)
+        tokenizer.add_words(["ตากลม"])
+        restored = pickle.loads(pickle.dumps(tokenizer))
+        self.assertTrue(restored.weighted)
+        self.assertEqual(len(restored), 5)
+        self.assertEqual(restored.segment("ตากลม ตาลม"), tokenizer.segment("ตากลม ตาลม"))
+        print("test_pickle passed")
+
+if __name__ == '__main__':
+    unittest.main()
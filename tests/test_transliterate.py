@@ -0,0 +1,39 @@
+import os
+import tempfile
+import unittest
+from thongna_py import DictionaryError, Transliterator, load_dict, newmm, transliterate
+
+
+class TestTransliteratePackage(unittest.TestCase):
+    def test_transliterate(self):
+        self.assertEqual(transliterate("ข้าว"), "kʰaːw˥˩")
+        self.assertEqual(transliterate("เชียงใหม่"), "tɕʰiːaŋ˧.maj˨˩")
+        self.assertEqual(transliterate(""), "")
+        self.assertEqual(transliterate(None), "")
+        print("test_transliterate passed")
+
+    def test_transliterate_newmm_words(self):
+        load_dict("dataset/words_th.txt", "words_th_transliterate", replace=True)
+        words = newmm("ภาษาไทยง่ายนิดเดียว", "words_th_transliterate")
+        self.assertEqual(
+            Transliterator().transliterate_words(words),
+            ["pʰaː˧.saː˩˩˦.tʰaj˧", "ŋaːj˥˩", "nit˦˥.diːaw˧"],
+        )
+        print("test_transliterate_newmm_words passed")
+
+    def test_exceptions(self):
+        with tempfile.NamedTemporaryFile("w", suffix=".txt", delete=False, encoding="utf-8") as file:
+            file.write("ก็\tkɔʔ˥˩\n\n")
+        try:
+            transliterator = Transliterator(file.name, {"ราชการ": "raːt˥˩.tɕʰaʔ˦˥.kaːn˧"})
+            self.assertEqual(len(transliterator), 2)
+            self.assertEqual(transliterator.transliterate_words(["ก็", "ราชการ", "กา", None]),
+                             ["kɔʔ˥˩", "raːt˥˩.tɕʰaʔ˦˥.kaːn˧", "kaː˧", ""])
+
+            with open(file.name, "w", encoding="utf-8") as bad_file:
+                bad_file.write("ก็\n")
+            with self.assertRaises(DictionaryError):
+                Transliterator(file.name)
+        finally:
+            os.remove(file.name)
+        print("test_exceptions passed")
@@ -1,34 +1,319 @@
 from pathlib import Path
-from typing import List, Tuple
+from typing import IO, Dict, Iterable, Iterator, List, NamedTuple, Optional, Set, Tuple, Union
 
 from thongna import load_dict as rust_load_dict  # type: ignore
+from thongna import compile_dict as rust_compile_dict  # type: ignore
+from thongna import load_compiled_dict as rust_load_compiled_dict  # type: ignore
+from thongna import load_dict_from_list as rust_load_dict_from_list  # type: ignore
+from thongna import unload_dict as rust_unload_dict  # type: ignore
+from thongna import list_dicts as rust_list_dicts  # type: ignore
+from thongna import dict_word_count as rust_dict_word_count  # type: ignore
+from thongna import complete as rust_complete  # type: ignore
+from thongna import complete_fuzzy as rust_complete_fuzzy  # type: ignore
+from thongna import add_words as rust_add_words  # type: ignore
+from thongna import remove_words as rust_remove_words  # type: ignore
+from thongna import set_token_patterns as rust_set_token_patterns  # type: ignore
+from thongna import set_config as rust_set_config  # type: ignore
+from thongna import Tokenizer as RustTokenizer  # type: ignore
+from thongna import (  # type: ignore # noqa: F401
+    DictionaryError,
+    NewmmConfig,
+    RegexError,
+    SegmentationError,
+    SegmentationTimeoutError,
+    ThongnaError,
+)
 from thongna import newmm as rust_newmm  # type: ignore
+from thongna import newmm_with_offsets as rust_newmm_with_offsets  # type: ignore
+from thongna import newmm_normalized_with_offsets as rust_newmm_normalized_with_offsets  # type: ignore
+from thongna import newmm_batch as rust_newmm_batch  # type: ignore
+from thongna import newmm_stream as rust_newmm_stream  # type: ignore
+from thongna import sentences_with_offsets as rust_sentences_with_offsets  # type: ignore
+from thongna import newmm_lattice as rust_newmm_lattice  # type: ignore
+from thongna import newmm_nbest as rust_newmm_nbest  # type: ignore
 from thongna import normalize as rust_normalize # type: ignore
+from thongna import Normalizer as RustNormalizer  # type: ignore
+from thongna import tcc as rust_tcc  # type: ignore
+from thongna import tcc_pos as rust_tcc_pos  # type: ignore
+from thongna import syllables as rust_syllables  # type: ignore
+from thongna import syllables_with_offsets as rust_syllables_with_offsets  # type: ignore
+from thongna import SyllableTokenizer as RustSyllableTokenizer  # type: ignore
+from thongna import romanize as rust_romanize  # type: ignore
+from thongna import romanize_words as rust_romanize_words  # type: ignore
+from thongna import transliterate as rust_transliterate  # type: ignore
+from thongna import Transliterator as RustTransliterator  # type: ignore
+from thongna import soundex as rust_soundex  # type: ignore
+from thongna import soundex_batch as rust_soundex_batch  # type: ignore
+from thongna import metasound as rust_metasound  # type: ignore
+from thongna import SpellChecker as RustSpellChecker  # type: ignore
+from thongna import BpeTokenizer as RustBpeTokenizer  # type: ignore
 
-def load_dict(file_path: str, dict_name: str) -> Tuple[str, bool]:
+def load_dict(
+    file_path: str,
+    dict_name: str,
+    weighted: bool = False,
+    replace: bool = False,
+) -> Tuple[str, bool]:
     """
     Load dictionary from a file.
 
     Load a dictionary file into an in-memory dictionary collection,
     and assign dict_name to it.
-    This function does not override an existing dict name.
+    This function does not override an existing dict name, unless replace is True.
+
+    Args:
+        file_path (str): Path to a dictionary file, one word per line,
+            optionally followed by a tab and the word frequency
+            (e.g. "word\t120")
+        dict_name (str): A unique dictionary name, used for reference
+        weighted (bool, optional): Choose the most probable segmentation
+            using word frequencies instead of the one with the fewest tokens.
+            Defaults to False.
+        replace (bool, optional): Replace the dictionary already loaded as
+            dict_name, if any. Segmentation keeps using the previous
+            dictionary until the new one is loaded. Defaults to False.
+
+    Returns:
+        Tuple[str, bool]: A tuple containing a human-readable result string and a boolean
+    """
+    path = Path(file_path).resolve()
+    return rust_load_dict(str(path), dict_name, weighted, replace)
+
+
+def load_dict_from_list(words: List[str], dict_name: str, replace: bool = False) -> Tuple[str, bool]:
+    """
+    Load dictionary from a list of words.
+
+    This function does not override an existing dict name, unless replace is True.
+
+    Args:
+        words (List[str]): Words of the dictionary
+        dict_name (str): A unique dictionary name, used for reference
+        replace (bool, optional): Replace the dictionary already loaded as
+            dict_name, if any. Defaults to False.
+
+    Returns:
+        Tuple[str, bool]: A tuple containing a human-readable result string and a boolean
+    """
+    return rust_load_dict_from_list(list(words), dict_name, replace)
+
+
+def unload_dict(dict_name: str) -> bool:
+    """
+    Remove a dictionary from the dictionary collection.
+
+    Args:
+        dict_name (str): Dictionary name, as assigned in load_dict()
+
+    Returns:
+        bool: True if the dictionary was loaded, False otherwise
+    """
+    return rust_unload_dict(dict_name)
+
+
+def list_dicts() -> List[str]:
+    """
+    List the names of the loaded dictionaries.
+
+    Returns:
+        List[str]: Dictionary names, sorted
+    """
+    return rust_list_dicts()
+
+
+def dict_word_count(dict_name: str) -> int:
+    """
+    Count the words of a loaded dictionary.
+
+    Args:
+        dict_name (str): Dictionary name, as assigned in load_dict()
+
+    Returns:
+        int: Number of words
+    """
+    return rust_dict_word_count(dict_name)
+
+
+class Completion(NamedTuple):
+    """
+    A dictionary word completing a prefix.
+
+    distance is the number of edits between the prefix and the closest
+    beginning of the word, 0 if the word starts with the prefix.
+    frequency is 0 for a word without a frequency in the dictionary.
+    """
+    word: str
+    distance: int
+    frequency: int
+
+
+def complete(prefix: str, dict_name: str, limit: int = 10) -> List[Completion]:
+    """
+    Words of a loaded dictionary starting with a prefix, the most frequent
+    first, e.g. for the suggestions of a search box.
+
+    Args:
+        prefix (str): Beginning of the words
+        dict_name (str): Dictionary name, as assigned in load_dict()
+        limit (int, optional): Largest number of words. Defaults to 10.
+
+    Returns:
+        List[Completion]: Words starting with the prefix
+
+    Raises:
+        RuntimeError: If the dictionary is not loaded
+    """
+    if not isinstance(prefix, str):
+        return []
+
+    return [Completion(*completion) for completion in rust_complete(prefix, dict_name, limit)]
+
+
+def complete_fuzzy(prefix: str, dict_name: str, max_distance: int = 1, limit: int = 10) -> List[Completion]:
+    """
+    Words of a loaded dictionary starting with a prefix give or take some
+    edits (insertions, deletions, substitutions and swaps of adjacent
+    characters), so that a typo in the prefix still finds the word.
+    The closest words come first, then the most frequent.
 
     Args:
-        file_path (str): Path to a dictionary file
+        prefix (str): Beginning of the words, possibly with typos
+        dict_name (str): Dictionary name, as assigned in load_dict()
+        max_distance (int, optional): Largest number of edits. Defaults to 1.
+        limit (int, optional): Largest number of words. Defaults to 10.
+
+    Returns:
+        List[Completion]: Words starting with the prefix or a close one
+
+    Raises:
+        RuntimeError: If the dictionary is not loaded
+    """
+    if not isinstance(prefix, str):
+        return []
+
+    return [
+        Completion(*completion)
+        for completion in rust_complete_fuzzy(prefix, dict_name, max_distance, limit)
+    ]
+
+
+def add_words(words: List[str], dict_name: str) -> None:
+    """
+    Add words to a loaded dictionary.
+
+    Words already in the dictionary keep their frequency. The change
+    applies to every following segmentation, no reload is needed.
+
+    Args:
+        words (List[str]): Words to add
+        dict_name (str): Dictionary name, as assigned in load_dict()
+    """
+    rust_add_words(list(words), dict_name)
+
+
+def remove_words(words: List[str], dict_name: str) -> None:
+    """
+    Remove words from a loaded dictionary.
+
+    Words not in the dictionary are ignored.
+
+    Args:
+        words (List[str]): Words to remove
+        dict_name (str): Dictionary name, as assigned in load_dict()
+    """
+    rust_remove_words(list(words), dict_name)
+
+
+def set_token_patterns(
+    dict_name: str,
+    presets: Optional[Iterable[str]] = None,
+    patterns: Optional[Iterable[str]] = None,
+) -> None:
+    """
+    Choose the tokens a loaded dictionary takes whole, before any dictionary lookup.
+
+    Custom patterns are tried first, then the presets. Latin words, numbers,
+    Thai numbers, spaces and line breaks are always matched last, and only
+    where the dictionary has no word.
+
+    Args:
+        dict_name (str): Dictionary name, as assigned in load_dict()
+        presets (Iterable[str], optional): Built-in patterns among "url", "email",
+            "mention", "hashtag", "emoji" and "number_with_unit"
+        patterns (Iterable[str], optional): Regular expressions of tokens
+
+    Raises:
+        ValueError: A preset name is unknown
+        RegexError: A pattern is not supported
+    """
+    rust_set_token_patterns(
+        dict_name,
+        list(presets) if presets is not None else None,
+        list(patterns) if patterns is not None else None,
+    )
+
+
+def set_config(dict_name: str, config: NewmmConfig) -> None:
+    """
+    Set the segmentation limits of a loaded dictionary.
+
+    Args:
+        dict_name (str): Dictionary name, as assigned in load_dict()
+        config (NewmmConfig): Limits, and the safe and parallel modes
+            used when a call does not choose them
+    """
+    rust_set_config(dict_name, config)
+
+
+def compile_dict(file_path: str, output_path: str) -> None:
+    """
+    Compile a dictionary file into a binary file for load_compiled_dict().
+
+    An existing file at output_path is replaced whole, so dictionaries already
+    loaded from it, in this or other processes, keep their words.
+
+    Args:
+        file_path (str): Path to a dictionary file, one word per line,
+            optionally followed by a tab and the word frequency
+        output_path (str): Path of the compiled dictionary to write
+    """
+    rust_compile_dict(str(Path(file_path).resolve()), str(Path(output_path).resolve()))
+
+
+def load_compiled_dict(
+    file_path: str,
+    dict_name: str,
+    weighted: bool = False,
+    replace: bool = False,
+) -> Tuple[str, bool]:
+    """
+    Load a dictionary compiled by compile_dict().
+
+    The file is memory-mapped, so loading takes milliseconds and
+    processes loading the same file share its memory.
+    This function does not override an existing dict name, unless replace is True.
+
+    Args:
+        file_path (str): Path to a compiled dictionary file
         dict_name (str): A unique dictionary name, used for reference
+        weighted (bool, optional): Choose the most probable segmentation
+            using word frequencies instead of the one with the fewest tokens.
+            Defaults to False.
+        replace (bool, optional): Replace the dictionary already loaded as
+            dict_name, if any. Defaults to False.
 
     Returns:
         Tuple[str, bool]: A tuple containing a human-readable result string and a boolean
     """
     path = Path(file_path).resolve()
-    return rust_load_dict(str(path), dict_name)
+    return rust_load_compiled_dict(str(path), dict_name, weighted, replace)
 
 
 def newmm(
     text: str,
     dict_name: str,
-    safe: bool = False,
-    parallel: bool = False,
+    safe: Optional[bool] = None,
+    parallel: Optional[bool] = None,
 ) -> List[str]:
     """
     Break text into tokens.
@@ -40,8 +325,10 @@ def newmm(
         text (str): Input text
         dict_name (str): Dictionary name, as assigned in load_dict()
         safe (bool, optional): Use safe mode to avoid long waiting time in
-            a text with lots of ambiguous word boundaries. Defaults to False.
-        parallel (bool, optional): Use multithread mode. Defaults to False.
+            a text with lots of ambiguous word boundaries.
+            Defaults to the safe mode of the dictionary's config, see set_config().
+        parallel (bool, optional): Use multithread mode.
+            Defaults to the parallel mode of the dictionary's config.
 
     Returns:
         List[str]: List of tokens
@@ -51,19 +338,1171 @@ def newmm(
 
     return rust_newmm(text, dict_name, safe, parallel)
 
-def normalize(text: str, whitespace_number: bool = True) -> str:
+def newmm_batch(texts: Iterable[str], dict_name: str, safe: Optional[bool] = None) -> List[List[str]]:
     """
-    Normalize Thai text.
+    Break a list of texts into tokens.
 
-    This function normalizes Thai text by applying various rules to standardize
-    the text representation.
+    The texts are segmented in parallel on all cores, with the GIL
+    released, which is much faster than calling newmm() on each text.
 
     Args:
-        text (str): Input text to be normalized
-        whitespace_number (bool, optional): If True, adds spaces around numbers. 
-                                            Defaults to True.
+        texts (Iterable[str]): Input texts
+        dict_name (str): Dictionary name, as assigned in load_dict()
+        safe (bool, optional): Use safe mode to avoid long waiting time in
+            a text with lots of ambiguous word boundaries.
+            Defaults to the safe mode of the dictionary's config.
 
     Returns:
-        str: Normalized text
+        List[List[str]]: List of tokens of each text, empty for
+            texts that are empty or not strings
+    """
+    return rust_newmm_batch([text if isinstance(text, str) else "" for text in texts], dict_name, safe)
+
+class Token(NamedTuple):
+    """
+    A token and its position in the original text.
+
+    start/end are character offsets, so text[start:end] == token.
+    byte_start/byte_end are offsets into the UTF-8 encoded text.
+    """
+    text: str
+    start: int
+    end: int
+    byte_start: int
+    byte_end: int
+
+
+def newmm_with_offsets(
+    text: str,
+    dict_name: str,
+    safe: Optional[bool] = None,
+    parallel: Optional[bool] = None,
+) -> List[Token]:
+    """
+    Break text into tokens, keeping the position of every token.
+
+    Args:
+        text (str): Input text
+        dict_name (str): Dictionary name, as assigned in load_dict()
+        safe (bool, optional): Use safe mode to avoid long waiting time in
+            a text with lots of ambiguous word boundaries.
+            Defaults to the safe mode of the dictionary's config.
+        parallel (bool, optional): Use multithread mode.
+            Defaults to the parallel mode of the dictionary's config.
+
+    Returns:
+        List[Token]: List of tokens with character and UTF-8 byte offsets
+    """
+    if not isinstance(text, str) or not text:
+        return []
+
+    return [Token(*token) for token in rust_newmm_with_offsets(text, dict_name, safe, parallel)]
+
+
+def newmm_normalized_with_offsets(
+    text: str,
+    dict_name: str,
+    normalizer: Optional["Normalizer"] = None,
+    safe: Optional[bool] = None,
+    parallel: Optional[bool] = None,
+) -> List[Token]:
+    """
+    Normalize text, then break it into tokens, keeping the position of
+    every token in the original text, e.g. to highlight tokens in user input.
+
+    Each token is the part of the original text its normalized characters
+    came from, so token texts are not normalized. Tokens made only of
+    characters added by normalization, like the spaces around numbers,
+    are left out.
+
+    Args:
+        text (str): Input text
+        dict_name (str): Dictionary name, as assigned in load_dict()
+        normalizer (Normalizer, optional): Defaults to Normalizer()
+        safe (bool, optional): Use safe mode. Defaults to the safe mode of the dictionary's config.
+        parallel (bool, optional): Use multithread mode.
+            Defaults to the parallel mode of the dictionary's config.
+
+    Returns:
+        List[Token]: List of tokens with character and UTF-8 byte offsets into text
+    """
+    if not isinstance(text, str) or not text:
+        return []
+
+    rust_normalizer = normalizer._normalizer if normalizer is not None else None
+    return [
+        Token(*token)
+        for token in rust_newmm_normalized_with_offsets(text, dict_name, rust_normalizer, safe, parallel)
+    ]
+
+
+def sentences(text: str, dict_name: str, safe: bool = False) -> List[str]:
+    """
+    Break text into sentences.
+
+    Sentences end at line breaks, terminal punctuation, sentence-final
+    particles (ครับ, ค่ะ, นะ, ...), ๆ and ฯ, and at spaces between two
+    Thai clauses not joined by a connective word (และ, ที่, ซึ่ง, ...).
+    Spaces and line breaks between sentences are dropped.
+
+    Args:
+        text (str): Input text
+        dict_name (str): Dictionary name, as assigned in load_dict()
+        safe (bool, optional): Use safe mode to avoid long waiting time in
+            a text with lots of ambiguous word boundaries. Defaults to False.
+
+    Returns:
+        List[str]: List of sentences
+    """
+    return [sentence.text for sentence in sentences_with_offsets(text, dict_name, safe)]
+
+
+def sentences_with_offsets(text: str, dict_name: str, safe: bool = False) -> List[Token]:
+    """
+    Break text into sentences, keeping the position of every sentence.
+
+    Args:
+        text (str): Input text
+        dict_name (str): Dictionary name, as assigned in load_dict()
+        safe (bool, optional): Use safe mode. Defaults to False.
+
+    Returns:
+        List[Token]: List of sentences with character and UTF-8 byte offsets
+    """
+    if not isinstance(text, str) or not text:
+        return []
+
+    return [Token(*sentence) for sentence in rust_sentences_with_offsets(text, dict_name, safe)]
+
+class Tokenizer:
+    """
+    A newmm tokenizer owning its dictionary.
+
+    Unlike newmm(), it does not use a dictionary name: every instance has
+    its own dictionary, so separate components cannot collide on names.
+    Tokenizers can be pickled, e.g. to send them to worker processes.
+
+    Args:
+        dict_path (str, optional): Path to a dictionary file, one word per line,
+            optionally followed by a tab and the word frequency
+        words (Iterable[str], optional): Words to add to the dictionary
+        weighted (bool, optional): Choose the most probable segmentation
+            using word frequencies instead of the one with the fewest tokens.
+            Defaults to False.
+        presets (Iterable[str], optional): Built-in token patterns,
+            see set_token_patterns()
+        patterns (Iterable[str], optional): Regular expressions of tokens
+            taken whole, see set_token_patterns()
+        config (NewmmConfig, optional): Segmentation limits, and the safe and
+            parallel modes used when a call does not choose them
+    """
+
+    def __init__(
+        self,
+        dict_path: Optional[str] = None,
+        words: Optional[Iterable[str]] = None,
+        weighted: bool = False,
+        presets: Optional[Iterable[str]] = None,
+        patterns: Optional[Iterable[str]] = None,
+        config: Optional[NewmmConfig] = None,
+    ):
+        path = str(Path(dict_path).resolve()) if dict_path is not None else None
+        self._tokenizer = RustTokenizer(
+            path,
+            list(words) if words is not None else None,
+            weighted,
+            list(presets) if presets is not None else None,
+            list(patterns) if patterns is not None else None,
+            config,
+        )
+
+    @classmethod
+    def from_compiled_dict(cls, file_path: str, weighted: bool = False) -> "Tokenizer":
+        """
+        Create a tokenizer from a dictionary compiled by compile_dict().
+
+        Args:
+            file_path (str): Path to a compiled dictionary file
+            weighted (bool, optional): Choose the most probable segmentation
+                using word frequencies. Defaults to False.
+
+        Returns:
+            Tokenizer: A tokenizer using the memory-mapped dictionary
+        """
+        tokenizer = cls.__new__(cls)
+        tokenizer._tokenizer = RustTokenizer.from_compiled_dict(str(Path(file_path).resolve()), weighted)
+        return tokenizer
+
+    @property
+    def weighted(self) -> bool:
+        """Whether segmentation uses word frequencies."""
+        return self._tokenizer.weighted
+
+    @property
+    def config(self) -> NewmmConfig:
+        """Segmentation limits."""
+        return self._tokenizer.config
+
+    def set_config(self, config: NewmmConfig) -> None:
+        """
+        Set the segmentation limits.
+
+        Args:
+            config (NewmmConfig): Limits, and the safe and parallel modes
+                used when a call does not choose them
+        """
+        self._tokenizer.set_config(config)
+
+    @property
+    def token_patterns(self) -> List[str]:
+        """Regular expressions of the tokens taken whole, in order of priority."""
+        return self._tokenizer.token_patterns
+
+    def __len__(self) -> int:
+        return len(self._tokenizer)
+
+    def segment(self, text: str, safe: Optional[bool] = None, parallel: Optional[bool] = None) -> List[str]:
+        """
+        Break text into tokens.
+
+        Args:
+            text (str): Input text
+            safe (bool, optional): Use safe mode to avoid long waiting time in
+                a text with lots of ambiguous word boundaries.
+                Defaults to the safe mode of the config.
+            parallel (bool, optional): Use multithread mode.
+                Defaults to the parallel mode of the config.
+
+        Returns:
+            List[str]: List of tokens
+        """
+        if not isinstance(text, str) or not text:
+            return []
+
+        return self._tokenizer.segment(text, safe, parallel)
+
+    def segment_batch(self, texts: Iterable[str], safe: Optional[bool] = None) -> List[List[str]]:
+        """
+        Break a list of texts into tokens, segmenting the texts in parallel.
+
+        Args:
+            texts (Iterable[str]): Input texts
+            safe (bool, optional): Use safe mode. Defaults to the safe mode of the config.
+
+        Returns:
+            List[List[str]]: List of tokens of each text
+        """
+        return self._tokenizer.segment_batch([text if isinstance(text, str) else "" for text in texts], safe)
+
+    def segment_with_offsets(
+        self, text: str, safe: Optional[bool] = None, parallel: Optional[bool] = None
+    ) -> List["Token"]:
+        """
+        Break text into tokens, keeping the position of every token.
+
+        Args:
+            text (str): Input text
+            safe (bool, optional): Use safe mode. Defaults to the safe mode of the config.
+            parallel (bool, optional): Use multithread mode.
+                Defaults to the parallel mode of the config.
+
+        Returns:
+            List[Token]: List of tokens with character and UTF-8 byte offsets
+        """
+        if not isinstance(text, str) or not text:
+            return []
+
+        return [Token(*token) for token in self._tokenizer.segment_with_offsets(text, safe, parallel)]
+
+    def segment_normalized_with_offsets(
+        self,
+        text: str,
+        normalizer: Optional["Normalizer"] = None,
+        safe: Optional[bool] = None,
+        parallel: Optional[bool] = None,
+    ) -> List["Token"]:
+        """
+        Normalize text, then break it into tokens, keeping the position of
+        every token in the original text, see newmm_normalized_with_offsets().
+
+        Args:
+            text (str): Input text
+            normalizer (Normalizer, optional): Defaults to Normalizer()
+            safe (bool, optional): Use safe mode. Defaults to the safe mode of the config.
+            parallel (bool, optional): Use multithread mode.
+                Defaults to the parallel mode of the config.
+
+        Returns:
+            List[Token]: List of tokens with character and UTF-8 byte offsets into text
+        """
+        if not isinstance(text, str) or not text:
+            return []
+
+        rust_normalizer = normalizer._normalizer if normalizer is not None else None
+        return [
+            Token(*token)
+            for token in self._tokenizer.segment_normalized_with_offsets(
+                text, rust_normalizer, safe, parallel
+            )
+        ]
+
+    def sentences(self, text: str, safe: bool = False) -> List[str]:
+        """
+        Break text into sentences, see sentences().
+
+        Args:
+            text (str): Input text
+            safe (bool, optional): Use safe mode. Defaults to False.
+
+        Returns:
+            List[str]: List of sentences
+        """
+        return [sentence.text for sentence in self.sentences_with_offsets(text, safe)]
+
+    def sentences_with_offsets(self, text: str, safe: bool = False) -> List["Token"]:
+        """
+        Break text into sentences, keeping the position of every sentence.
+
+        Args:
+            text (str): Input text
+            safe (bool, optional): Use safe mode. Defaults to False.
+
+        Returns:
+            List[Token]: List of sentences with character and UTF-8 byte offsets
+        """
+        if not isinstance(text, str) or not text:
+            return []
+
+        return [Token(*sentence) for sentence in self._tokenizer.sentences_with_offsets(text, safe)]
+
+    def segment_stream(self, source: "StreamSource") -> Iterator["Token"]:
+        """
+        Break text into tokens as it is read, see newmm_stream().
+
+        Args:
+            source (file object or Iterable[str] or Iterable[bytes]): A file opened
+                in text or binary mode, or pieces of the text
+
+        Returns:
+            Iterator[Token]: Tokens with character and UTF-8 byte offsets into the whole text
+        """
+        return (Token(*token) for token in self._tokenizer.segment_stream(_stream_chunks(source)))
+
+    def complete(self, prefix: str, limit: int = 10) -> List[Completion]:
+        """
+        Words of the dictionary starting with a prefix, the most frequent first.
+        See complete().
+
+        Args:
+            prefix (str): Beginning of the words
+            limit (int, optional): Largest number of words. Defaults to 10.
+
+        Returns:
+            List[Completion]: Words starting with the prefix
+        """
+        if not isinstance(prefix, str):
+            return []
+
+        return [Completion(*completion) for completion in self._tokenizer.complete(prefix, limit)]
+
+    def complete_fuzzy(self, prefix: str, max_distance: int = 1, limit: int = 10) -> List[Completion]:
+        """
+        Words of the dictionary starting with a prefix give or take some edits,
+        the closest first, then the most frequent. See complete_fuzzy().
+
+        Args:
+            prefix (str): Beginning of the words, possibly with typos
+            max_distance (int, optional): Largest number of edits. Defaults to 1.
+            limit (int, optional): Largest number of words. Defaults to 10.
+
+        Returns:
+            List[Completion]: Words starting with the prefix or a close one
+        """
+        if not isinstance(prefix, str):
+            return []
+
+        return [
+            Completion(*completion)
+            for completion in self._tokenizer.complete_fuzzy(prefix, max_distance, limit)
+        ]
+
+    def add_words(self, words: Iterable[str]) -> None:
+        """
+        Add words to the dictionary, keeping the frequency of existing words.
+
+        Args:
+            words (Iterable[str]): Words to add
+        """
+        self._tokenizer.add_words(list(words))
+
+    def remove_words(self, words: Iterable[str]) -> None:
+        """
+        Remove words from the dictionary. Words not in it are ignored.
+
+        Args:
+            words (Iterable[str]): Words to remove
+        """
+        self._tokenizer.remove_words(list(words))
+
+    def set_token_patterns(
+        self,
+        presets: Optional[Iterable[str]] = None,
+        patterns: Optional[Iterable[str]] = None,
+    ) -> None:
+        """
+        Choose the tokens taken whole, before any dictionary lookup.
+
+        Args:
+            presets (Iterable[str], optional): Built-in patterns, see set_token_patterns()
+            patterns (Iterable[str], optional): Regular expressions of tokens
+
+        Raises:
+            ValueError: A preset name is unknown
+            RegexError: A pattern is not supported
+        """
+        self._tokenizer.set_token_patterns(
+            list(presets) if presets is not None else None,
+            list(patterns) if patterns is not None else None,
+        )
+
+
+class LatticeEdge(NamedTuple):
+    """
+    A candidate word of the segmentation lattice.
+
+    start/end are character offsets, byte_start/byte_end are offsets
+    into the UTF-8 encoded text. in_dictionary is False for
+    non-dictionary runs such as Latin words, numbers and spaces.
+    """
+    text: str
+    start: int
+    end: int
+    byte_start: int
+    byte_end: int
+    cost: float
+    in_dictionary: bool
+
+
+class Lattice(NamedTuple):
+    """
+    Segmentation lattice: character positions and the candidate words between them.
     """
-    return rust_normalize(text, whitespace_number)
\ No newline at end of file
+    nodes: List[int]
+    edges: List[LatticeEdge]
+
+
+StreamSource = Union[IO[str], IO[bytes], Iterable[str], Iterable[bytes]]
+
+_STREAM_CHUNK_SIZE = 1 << 16
+
+
+def _stream_chunks(source: StreamSource) -> Iterator[Union[str, bytes]]:
+    """Read a file object in fixed-size chunks, or pass an iterable through."""
+    if hasattr(source, "read"):
+        while True:
+            chunk = source.read(_STREAM_CHUNK_SIZE)
+            if not chunk:
+                return
+            yield chunk
+    else:
+        yield from source
+
+
+def newmm_stream(source: StreamSource, dict_name: str) -> Iterator[Token]:
+    """
+    Break text into tokens as it is read, without holding the whole text in memory.
+
+    Text is carried over from one chunk to the next, so the tokens and their
+    offsets are those of newmm_with_offsets(text, dict_name, safe=True) on the
+    whole text, wherever the chunks end.
+
+    Args:
+        source (file object or Iterable[str] or Iterable[bytes]): A file opened
+            in text or binary mode, or pieces of the text; bytes are read as UTF-8
+        dict_name (str): Dictionary name, as assigned in load_dict()
+
+    Returns:
+        Iterator[Token]: Tokens with character and UTF-8 byte offsets into the whole text
+    """
+    return (Token(*token) for token in rust_newmm_stream(_stream_chunks(source), dict_name))
+
+
+def newmm_lattice(text: str, dict_name: str) -> Lattice:
+    """
+    Build the segmentation lattice of text.
+
+    Every path of edges from the first to the last node is a possible
+    segmentation. Edge costs are 1 per token, or word costs derived from
+    frequencies if the dictionary was loaded with weighted=True.
+
+    Args:
+        text (str): Input text
+        dict_name (str): Dictionary name, as assigned in load_dict()
+
+    Returns:
+        Lattice: Nodes (character positions) and edges (candidate words)
+    """
+    if not isinstance(text, str) or not text:
+        return Lattice([], [])
+
+    nodes, edges = rust_newmm_lattice(text, dict_name)
+    return Lattice(nodes, [LatticeEdge(*edge) for edge in edges])
+
+
+def newmm_nbest(text: str, dict_name: str, k: int = 5) -> List[Tuple[List[str], float]]:
+    """
+    List the k best segmentations of text, best first.
+
+    Args:
+        text (str): Input text
+        dict_name (str): Dictionary name, as assigned in load_dict()
+        k (int, optional): Maximum number of segmentations. Defaults to 5.
+
+    Returns:
+        List[Tuple[List[str], float]]: Segmentations with their total cost
+    """
+    if not isinstance(text, str) or not text:
+        return []
+
+    return rust_newmm_nbest(text, dict_name, k)
+
+
+def tcc(text: str) -> List[str]:
+    """
+    Split text into Thai Character Clusters (TCC).
+
+    A TCC cannot be split without separating a consonant from its
+    vowels and tone marks, which makes cluster boundaries safe cut points.
+
+    Args:
+        text (str): Input text
+
+    Returns:
+        List[str]: List of clusters
+    """
+    if not isinstance(text, str) or not text:
+        return []
+
+    return rust_tcc(text)
+
+
+def tcc_pos(text: str) -> Set[int]:
+    """
+    Find the end position of every Thai Character Cluster (TCC).
+
+    Args:
+        text (str): Input text
+
+    Returns:
+        Set[int]: Character offsets at which a cluster ends
+    """
+    if not isinstance(text, str) or not text:
+        return set()
+
+    return rust_tcc_pos(text)
+
+
+def syllables(text: str) -> List[str]:
+    """
+    Split text into written Thai syllables.
+
+    Syllables are cut at cluster boundaries and recognised by their
+    spelling, so "สบาย" gives "ส", "บาย". Runs of spaces, Latin letters
+    or digits are kept as one token.
+
+    Args:
+        text (str): Input text
+
+    Returns:
+        List[str]: List of syllables
+    """
+    if not isinstance(text, str) or not text:
+        return []
+
+    return rust_syllables(text)
+
+
+def syllables_with_offsets(text: str) -> List[Token]:
+    """
+    Split text into written Thai syllables, keeping the position of every syllable.
+
+    Args:
+        text (str): Input text
+
+    Returns:
+        List[Token]: List of syllables with character and UTF-8 byte offsets
+    """
+    if not isinstance(text, str) or not text:
+        return []
+
+    return [Token(*syllable) for syllable in rust_syllables_with_offsets(text)]
+
+
+class SyllableTokenizer:
+    """
+    A syllable tokenizer that also accepts the syllables of a dictionary,
+    e.g. loanword syllables the spelling rules do not cover.
+
+    Args:
+        dict_path (str, optional): Path to a syllable dictionary file,
+            one syllable per line
+        words (Iterable[str], optional): Syllables to add to the dictionary
+    """
+
+    def __init__(self, dict_path: Optional[str] = None, words: Optional[Iterable[str]] = None):
+        path = str(Path(dict_path).resolve()) if dict_path is not None else None
+        self._tokenizer = RustSyllableTokenizer(path, list(words) if words is not None else None)
+
+    def segment(self, text: str) -> List[str]:
+        """
+        Split text into syllables, see syllables().
+
+        Args:
+            text (str): Input text
+
+        Returns:
+            List[str]: List of syllables
+        """
+        if not isinstance(text, str) or not text:
+            return []
+
+        return self._tokenizer.segment(text)
+
+    def segment_with_offsets(self, text: str) -> List[Token]:
+        """
+        Split text into syllables, keeping the position of every syllable.
+
+        Args:
+            text (str): Input text
+
+        Returns:
+            List[Token]: List of syllables with character and UTF-8 byte offsets
+        """
+        if not isinstance(text, str) or not text:
+            return []
+
+        return [Token(*syllable) for syllable in self._tokenizer.segment_with_offsets(text)]
+
+
+class BpeTokenizer:
+    """
+    A byte-pair encoding (BPE) subword tokenizer over Thai character clusters.
+
+    Its initial symbols are character clusters (TCC), and merges never join
+    two pre-tokens: newmm words of a loaded dictionary, or runs of whitespace
+    and non-whitespace without one. Encode with the pre-tokens used in
+    training. Use train() or load() to create one.
+    """
+
+    def __init__(self, tokenizer: RustBpeTokenizer):
+        self._tokenizer = tokenizer
+
+    @classmethod
+    def train(
+        cls,
+        texts: Iterable[str],
+        vocab_size: int,
+        dict_name: Optional[str] = None,
+        min_frequency: int = 2,
+    ) -> "BpeTokenizer":
+        """
+        Learn a vocabulary from texts.
+
+        Args:
+            texts (Iterable[str]): Training texts, non-str entries are skipped
+            vocab_size (int): Number of tokens to reach, the unknown token
+                and the clusters included
+            dict_name (str, optional): Dictionary whose newmm words are the
+                pre-tokens, as assigned in load_dict(). Defaults to None.
+            min_frequency (int, optional): Stop when no pair of symbols occurs
+                this many times. Defaults to 2.
+
+        Returns:
+            BpeTokenizer: The trained tokenizer
+        """
+        texts = [text for text in texts if isinstance(text, str)]
+        return cls(RustBpeTokenizer.train(texts, vocab_size, dict_name, min_frequency))
+
+    @classmethod
+    def load(cls, file_path: str) -> "BpeTokenizer":
+        """
+        Read a vocabulary written by save().
+
+        Args:
+            file_path (str): Path to a vocabulary file
+
+        Returns:
+            BpeTokenizer: The tokenizer of the vocabulary
+        """
+        return cls(RustBpeTokenizer.load(str(Path(file_path).resolve())))
+
+    def save(self, file_path: str) -> None:
+        """
+        Write the vocabulary to a file.
+
+        Args:
+            file_path (str): Path to the vocabulary file
+        """
+        self._tokenizer.save(str(Path(file_path).resolve()))
+
+    @property
+    def vocab_size(self) -> int:
+        """Number of tokens, the unknown token [UNK] included."""
+        return self._tokenizer.vocab_size
+
+    def encode(self, text: str, dict_name: Optional[str] = None) -> List[str]:
+        """
+        Split text into subwords. Clusters not seen in training are kept as
+        they are, so the subwords always join back into text.
+
+        Args:
+            text (str): Input text
+            dict_name (str, optional): Dictionary used in training. Defaults to None.
+
+        Returns:
+            List[str]: List of subwords
+        """
+        if not isinstance(text, str) or not text:
+            return []
+
+        return self._tokenizer.encode(text, dict_name)
+
+    def encode_ids(self, text: str, dict_name: Optional[str] = None) -> List[int]:
+        """
+        Split text into subword ids. Clusters not seen in training get id 0,
+        the id of [UNK].
+
+        Args:
+            text (str): Input text
+            dict_name (str, optional): Dictionary used in training. Defaults to None.
+
+        Returns:
+            List[int]: List of subword ids
+        """
+        if not isinstance(text, str) or not text:
+            return []
+
+        return self._tokenizer.encode_ids(text, dict_name)
+
+    def decode(self, ids: Iterable[int]) -> str:
+        """
+        Join the tokens of ids. Unknown ids are written as [UNK].
+
+        Args:
+            ids (Iterable[int]): Subword ids
+
+        Returns:
+            str: Decoded text
+        """
+        return self._tokenizer.decode(list(ids))
+
+    def token_to_id(self, token: str) -> Optional[int]:
+        """Id of a token, or None if it is not in the vocabulary."""
+        return self._tokenizer.token_to_id(token)
+
+    def id_to_token(self, id: int) -> Optional[str]:
+        """Token of an id, or None if it is not in the vocabulary."""
+        return self._tokenizer.id_to_token(id)
+
+def normalize(text: str, whitespace_number: bool = True) -> str:
+    """
+    Normalize Thai text.
+
+    This function normalizes Thai text by applying various rules to standardize
+    the text representation.
+
+    Args:
+        text (str): Input text to be normalized
+        whitespace_number (bool, optional): If True, adds spaces around numbers. 
+                                            Defaults to True.
+
+    Returns:
+        str: Normalized text
+    """
+    return rust_normalize(text, whitespace_number)
+
+
+class Normalizer:
+    """
+    A reusable Thai text normalizer made of selectable steps.
+
+    Steps always run in this order, whatever the order they are given in:
+
+    - "nfc": Unicode canonical composition
+    - "space_numbers": spaces around numbers
+    - "remove_zero_width": remove zero-width spaces
+    - "remove_invisible_chars": remove zero-width non-joiners, word joiners
+      and byte order marks
+    - "collapse_whitespace": runs of spaces, tabs or line breaks to a single one
+    - "reorder_marks": tone marks after the vowels above or below them,
+      "เเ" to "แ" and "ํา" to "ำ"
+    - "remove_duplicate_marks": repeated vowels, tone marks and signs to a single one
+
+    Args:
+        steps (Iterable[str], optional): Names of the steps.
+            Defaults to every step but "nfc", "space_numbers" and
+            "remove_invisible_chars", as normalize(text, whitespace_number=False) does.
+
+    Raises:
+        ValueError: If a step name is unknown
+    """
+
+    STEPS = (
+        "nfc",
+        "space_numbers",
+        "remove_zero_width",
+        "remove_invisible_chars",
+        "collapse_whitespace",
+        "reorder_marks",
+        "remove_duplicate_marks",
+    )
+
+    def __init__(self, steps: Optional[Iterable[str]] = None):
+        self._normalizer = RustNormalizer(list(steps) if steps is not None else None)
+
+    @property
+    def steps(self) -> List[str]:
+        """The steps, in the order they run."""
+        return self._normalizer.steps
+
+    def normalize(self, text: str) -> str:
+        """
+        Normalize text.
+
+        Args:
+            text (str): Input text
+
+        Returns:
+            str: Normalized text
+        """
+        if not isinstance(text, str) or not text:
+            return ""
+
+        return self._normalizer.normalize(text)
+
+    def normalize_with_alignment(self, text: str) -> Tuple[str, List[Tuple[int, int]]]:
+        """
+        Normalize text, keeping for every normalized character the range of
+        characters of text it came from.
+
+        Reordered characters keep their own range, characters replacing
+        several others (like "แ" for "เเ") have the range of all of them, and
+        characters added by normalization have an empty range.
+
+        Args:
+            text (str): Input text
+
+        Returns:
+            Tuple[str, List[Tuple[int, int]]]: The normalized text, and for each
+            of its characters the (start, end) character offsets in text
+        """
+        if not isinstance(text, str) or not text:
+            return "", []
+
+        return self._normalizer.normalize_with_alignment(text)
+
+    def normalize_batch(self, texts: Iterable[str]) -> List[str]:
+        """
+        Normalize texts in parallel, without holding the GIL.
+
+        Args:
+            texts (Iterable[str]): Input texts
+
+        Returns:
+            List[str]: Normalized texts, in the order of texts
+        """
+        return self._normalizer.normalize_batch(list(texts))
+
+
+def romanize(text: str, dict_name: Optional[str] = None) -> str:
+    """
+    Transcribe Thai text into Latin script with the Royal Thai General
+    System of Transcription (RTGS), without tones or vowel length.
+
+    Every written syllable is read from its initial consonants, vowel form
+    and final consonant, so "เชียงใหม่" gives "chiangmai". Vowels that are
+    not written are not restored, except the "a" of a lone consonant and
+    the "o" between two consonants.
+
+    Args:
+        text (str): Input text
+        dict_name (str, optional): Dictionary name, as assigned in load_dict().
+            If given, the text is broken into words first so that no
+            syllable straddles two words.
+
+    Returns:
+        str: Romanized text
+
+    Raises:
+        RuntimeError: If dict_name is not loaded
+    """
+    if not isinstance(text, str) or not text:
+        return ""
+
+    return rust_romanize(text, dict_name)
+
+
+def romanize_words(words: Iterable[str]) -> List[str]:
+    """
+    Romanize every word with RTGS, see romanize().
+
+    Args:
+        words (Iterable[str]): Input words, e.g. the tokens of newmm()
+
+    Returns:
+        List[str]: Romanized words
+    """
+    return rust_romanize_words([word if isinstance(word, str) else "" for word in words])
+
+
+def transliterate(word: str) -> str:
+    """
+    Transcribe a Thai word into the International Phonetic Alphabet (IPA).
+
+    The sounds of every syllable are read from its spelling, and its tone
+    from the class of its initial consonant, whether it is live or dead,
+    and its tone mark. A Chao tone letter follows every syllable, and
+    syllables are separated by ".", so "ข้าว" gives "kʰaːw˥˩".
+
+    Args:
+        word (str): Input word, e.g. a token of newmm()
+
+    Returns:
+        str: IPA of the word
+    """
+    if not isinstance(word, str) or not word:
+        return ""
+
+    return rust_transliterate(word)
+
+
+class Transliterator:
+    """
+    A transliterator into IPA that reads the words of an exceptions
+    dictionary as given there, e.g. words with unwritten vowels
+    or irregular tones. See transliterate().
+
+    Args:
+        dict_path (str, optional): Path to an exceptions dictionary file,
+            one word and its IPA separated by a tab per line
+        exceptions (Dict[str, str], optional): IPA of more words
+
+    Raises:
+        DictionaryError: If a line of the dictionary file has no tab
+    """
+
+    def __init__(self, dict_path: Optional[str] = None, exceptions: Optional[Dict[str, str]] = None):
+        path = str(Path(dict_path).resolve()) if dict_path is not None else None
+        self._transliterator = RustTransliterator(path, dict(exceptions) if exceptions is not None else None)
+
+    def __len__(self) -> int:
+        return len(self._transliterator)
+
+    def transliterate(self, word: str) -> str:
+        """
+        Transcribe a word into IPA, using the exceptions first.
+
+        Args:
+            word (str): Input word
+
+        Returns:
+            str: IPA of the word
+        """
+        if not isinstance(word, str) or not word:
+            return ""
+
+        return self._transliterator.transliterate(word)
+
+    def transliterate_words(self, words: Iterable[str]) -> List[str]:
+        """
+        Transcribe every word into IPA in parallel, without holding the GIL.
+
+        Args:
+            words (Iterable[str]): Input words, e.g. the tokens of newmm()
+
+        Returns:
+            List[str]: IPA of every word
+        """
+        return self._transliterator.transliterate_words(
+            [word if isinstance(word, str) else "" for word in words]
+        )
+
+
+SOUNDEX_ENGINES = ("lk82", "udom83", "metasound")
+
+
+def soundex(text: str, engine: str = "udom83") -> str:
+    """
+    Phonetic key of a Thai name, for matching names spelled in different ways.
+
+    Names that sound alike, such as "รัก" and "รักษ์", get the same key.
+    Every whitespace-separated part of the text, e.g. the first and last
+    name, has its own key, and keys are joined by a space. Letters of other
+    scripts are lowercased and kept.
+
+    Args:
+        text (str): Input text
+        engine (str, optional): One of SOUNDEX_ENGINES: "lk82", "udom83"
+            or "metasound" (with keys of 4 characters). Defaults to "udom83".
+
+    Returns:
+        str: Phonetic key
+
+    Raises:
+        ValueError: If engine is not one of SOUNDEX_ENGINES
+    """
+    if not isinstance(text, str) or not text:
+        return ""
+
+    return rust_soundex(text, engine)
+
+
+def soundex_batch(texts: Iterable[str], engine: str = "udom83") -> List[str]:
+    """
+    Phonetic keys of many names in parallel, without holding the GIL.
+
+    Args:
+        texts (Iterable[str]): Input texts
+        engine (str, optional): One of SOUNDEX_ENGINES. Defaults to "udom83".
+
+    Returns:
+        List[str]: Phonetic key of each text, see soundex()
+
+    Raises:
+        ValueError: If engine is not one of SOUNDEX_ENGINES
+    """
+    return rust_soundex_batch([text if isinstance(text, str) else "" for text in texts], engine)
+
+
+def lk82(text: str) -> str:
+    """
+    LK82 phonetic key of a Thai name: its first consonant, then four codes.
+
+    Args:
+        text (str): Input text
+
+    Returns:
+        str: Phonetic key, see soundex()
+    """
+    return soundex(text, "lk82")
+
+
+def udom83(text: str) -> str:
+    """
+    Udom83 phonetic key of a Thai name: its first consonant, then six codes.
+
+    Args:
+        text (str): Input text
+
+    Returns:
+        str: Phonetic key, see soundex()
+    """
+    return soundex(text, "udom83")
+
+
+def metasound(text: str, length: int = 4) -> str:
+    """
+    MetaSound phonetic key of a Thai name: its first consonant, then codes
+    of the other consonants by their sound as a final.
+
+    Args:
+        text (str): Input text
+        length (int, optional): Length of the key of each part. Defaults to 4.
+
+    Returns:
+        str: Phonetic key, see soundex()
+    """
+    if not isinstance(text, str) or not text:
+        return ""
+
+    return rust_metasound(text, length)
+
+
+class Suggestion(NamedTuple):
+    """
+    A dictionary word close to a misspelled word.
+
+    distance is the weighted number of edits from the misspelled word;
+    tone mark and vowel confusions count as half an edit.
+    frequency is 0 for a word without a frequency in the dictionary.
+    """
+    word: str
+    distance: float
+    frequency: int
+
+
+class SpellChecker:
+    """
+    A spell checker that suggests and corrects Thai words from the words
+    of a dictionary, ranked by distance, then by frequency.
+
+    Args:
+        dict_path (str, optional): Path to a dictionary file, one word per line,
+            optionally followed by a tab and the word frequency
+        words (List[str], optional): Words to add to the dictionary
+        max_distance (int, optional): Largest number of edits between a word
+            and its suggestions. Defaults to 2.
+
+    Raises:
+        DictionaryError: If the dictionary file cannot be read
+    """
+
+    def __init__(
+        self,
+        dict_path: Optional[str] = None,
+        words: Optional[List[str]] = None,
+        max_distance: int = 2,
+    ):
+        path = str(Path(dict_path).resolve()) if dict_path is not None else None
+        self._spell_checker = RustSpellChecker(path, words, max_distance)
+
+    def __contains__(self, word: object) -> bool:
+        return isinstance(word, str) and word in self._spell_checker
+
+    def suggest(self, word: str) -> List[Suggestion]:
+        """
+        Dictionary words within the maximum distance of a word, the closest
+        and most frequent first. A dictionary word is its own first suggestion.
+
+        Args:
+            word (str): Input word
+
+        Returns:
+            List[Suggestion]: Suggestions for the word
+        """
+        if not isinstance(word, str) or not word:
+            return []
+
+        return [Suggestion(*suggestion) for suggestion in self._spell_checker.suggest(word)]
+
+    def correct(self, word: str) -> str:
+        """
+        The best suggestion for a word, or the word itself if it is in the
+        dictionary or has no suggestion.
+
+        Args:
+            word (str): Input word
+
+        Returns:
+            str: Corrected word
+        """
+        if not isinstance(word, str) or not word:
+            return ""
+
+        return self._spell_checker.correct(word)
+
+    def correct_text(self, text: str) -> str:
+        """
+        Segment text with newmm and correct the Thai words that are not in
+        the dictionary, keeping everything else as written.
+
+        Args:
+            text (str): Input text
+
+        Returns:
+            str: Corrected text
+        """
+        if not isinstance(text, str) or not text:
+            return ""
+
+        return self._spell_checker.correct_text(text)